@@ -0,0 +1,1541 @@
+//! Heuristics that summarize a code for reviewers and code assistants.
+//!
+//! These analyses are built on top of the function spaces computed by
+//! [`get_function_spaces`] and complement the traditional metrics.
+//!
+//! [`get_function_spaces`]: crate::get_function_spaces
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{
+    getter::Getter,
+    halstead::{Halstead, HalsteadMaps},
+    langs::{action, LANG},
+    node::{Node, Tree},
+    spaces::{FuncSpace, SpaceKind},
+    traits::{Callback, ParserTrait},
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// Error handling information of a single function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FunctionErrorHandling {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// Whether the function handles or propagates errors
+    pub has_error_handling: bool,
+}
+
+/// The `Error Handling Coverage` of a code.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ErrorHandlingCoverage {
+    /// Every function found in the code, in source order
+    pub functions: Vec<FunctionErrorHandling>,
+    /// The number of functions with error handling
+    pub covered: usize,
+    /// The ratio of functions with error handling to the total number
+    /// of functions, `0` when there are no functions
+    pub ratio: f64,
+}
+
+/// A function whose comment density is below a threshold.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CommentDensitySmell {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The ratio of comment lines to source lines of the function
+    pub density: f64,
+}
+
+/// The `Testability Score` of a single function.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FunctionTestability {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The score, from `0` (hard to test) to `100` (easy to test)
+    pub score: f64,
+}
+
+/// The `Readability Score` of a single function.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FunctionReadability {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The score, from `0` (hard to read) to `100` (easy to read)
+    pub score: f64,
+}
+
+/// The `Change Risk` of a single function.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FunctionChangeRisk {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The score, from `0` (safe to change) to `100` (risky to change)
+    pub score: f64,
+}
+
+/// The `Change Risk` of the functions of a code.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FileChangeRisk {
+    /// Every function found in the code, in source order
+    pub functions: Vec<FunctionChangeRisk>,
+    /// The highest score of the functions, `0` when there are no functions
+    pub max: f64,
+    /// The average score of the functions, `0` when there are no functions
+    pub average: f64,
+}
+
+/// Thresholds above which a class is a `God Class`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct GodClassConfig {
+    /// The maximum number of methods, nested ones included
+    pub max_methods: f64,
+    /// The maximum number of source lines
+    pub max_sloc: f64,
+    /// The maximum sum of the cyclomatic complexities of the class and
+    /// of its methods
+    pub max_cyclomatic: f64,
+}
+
+impl Default for GodClassConfig {
+    fn default() -> Self {
+        Self {
+            max_methods: 20.,
+            max_sloc: 500.,
+            max_cyclomatic: 50.,
+        }
+    }
+}
+
+/// A threshold of [`GodClassConfig`] exceeded by a class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GodClassTrigger {
+    /// The number of methods
+    Methods,
+    /// The number of source lines
+    Sloc,
+    /// The aggregated cyclomatic complexity
+    Cyclomatic,
+}
+
+/// A class exceeding at least one threshold of a [`GodClassConfig`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GodClass {
+    /// The name of the class
+    pub name: Option<String>,
+    /// The first line of the class
+    pub start_line: usize,
+    /// The last line of the class
+    pub end_line: usize,
+    /// The number of methods, nested ones included
+    pub methods: f64,
+    /// The number of source lines
+    pub sloc: f64,
+    /// The sum of the cyclomatic complexities of the class and of its methods
+    pub cyclomatic: f64,
+    /// The exceeded thresholds
+    pub triggers: Vec<GodClassTrigger>,
+}
+
+/// An exception handler whose body is empty or only contains comments.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EmptyCatch {
+    /// The name of the innermost function containing the handler, `None`
+    /// at the top level
+    pub function: Option<String>,
+    /// The first line of the handler
+    pub start_line: usize,
+    /// The last line of the handler
+    pub end_line: usize,
+}
+
+/// The case of an identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameCase {
+    /// `snake_case`, without uppercase letters
+    Snake,
+    /// `camelCase`, starting with a lowercase letter, without underscores
+    Camel,
+    /// `PascalCase`, starting with an uppercase letter, without underscores
+    Pascal,
+}
+
+impl NameCase {
+    /// Checks if `name` is written in this case.
+    ///
+    /// Leading underscores and `$` signs, e.g. of private or magic names,
+    /// are ignored.
+    #[must_use]
+    pub fn matches(self, name: &str) -> bool {
+        let name = name.trim_start_matches(['_', '$']);
+        let Some(first) = name.chars().next() else {
+            return true;
+        };
+        match self {
+            Self::Snake => !name.chars().any(char::is_uppercase),
+            Self::Camel => !first.is_uppercase() && !name.contains('_'),
+            Self::Pascal => !first.is_lowercase() && !name.contains('_'),
+        }
+    }
+}
+
+/// The expected cases of the names checked by [`naming`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct NamingConventions {
+    /// The case of the functions and methods, not checked when `None`
+    pub functions: Option<NameCase>,
+    /// The case of the classes, structs, interfaces and traits, not
+    /// checked when `None`
+    pub classes: Option<NameCase>,
+}
+
+impl NamingConventions {
+    /// Returns the usual conventions of a language.
+    ///
+    /// Functions are in `snake_case` in `Rust`, `Python` and the `BEAM`
+    /// languages, in `camelCase` in `JavaScript`, `TypeScript`, `Java` and
+    /// `Kotlin`, and in `PascalCase` in `C#`. Classes are in `PascalCase`.
+    /// The other cases are not checked.
+    #[must_use]
+    pub fn for_language(lang: LANG) -> Self {
+        let functions = match lang {
+            LANG::Rust | LANG::Python | LANG::Elixir | LANG::Erlang | LANG::Gleam => {
+                Some(NameCase::Snake)
+            }
+            LANG::Javascript | LANG::Typescript | LANG::Tsx | LANG::Java | LANG::Kotlin => {
+                Some(NameCase::Camel)
+            }
+            LANG::Csharp => Some(NameCase::Pascal),
+            LANG::Cpp | LANG::Go | LANG::Lua | LANG::R | LANG::Perl | LANG::Graphql => None,
+        };
+        Self {
+            functions,
+            classes: Some(NameCase::Pascal),
+        }
+    }
+}
+
+/// A name which is not written in the expected case.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NamingViolation {
+    /// The name, without its qualifiers
+    pub name: String,
+    /// The kind of the named space
+    pub kind: SpaceKind,
+    /// The expected case
+    pub expected: NameCase,
+    /// The line of the name
+    pub line: usize,
+    /// The first line of the named space
+    pub start_line: usize,
+    /// The last line of the named space
+    pub end_line: usize,
+}
+
+/// A function with more parameters than a threshold and no options object.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LongParameterList {
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The number of parameters, the receiver excluded
+    pub parameters: usize,
+}
+
+/// Weight of the number of arguments in the `Testability Score`.
+pub const TESTABILITY_ARGS_WEIGHT: f64 = 25.;
+/// Weight of the cyclomatic complexity in the `Testability Score`.
+pub const TESTABILITY_COMPLEXITY_WEIGHT: f64 = 35.;
+/// Weight of the fan-out in the `Testability Score`.
+pub const TESTABILITY_FAN_OUT_WEIGHT: f64 = 20.;
+/// Weight of the absence of side effects in the `Testability Score`.
+pub const TESTABILITY_SIDE_EFFECTS_WEIGHT: f64 = 20.;
+
+/// Weight of the average identifier length in the `Readability Score`.
+pub const READABILITY_IDENTIFIER_WEIGHT: f64 = 50.;
+/// Weight of the `Halstead` vocabulary in the `Readability Score`.
+pub const READABILITY_VOCABULARY_WEIGHT: f64 = 25.;
+/// Weight of the comment ratio in the `Readability Score`.
+pub const READABILITY_COMMENT_WEIGHT: f64 = 25.;
+
+/// Weight of the cyclomatic complexity in the `Change Risk`.
+pub const CHANGE_RISK_CYCLOMATIC_WEIGHT: f64 = 30.;
+/// Weight of the cognitive complexity in the `Change Risk`.
+pub const CHANGE_RISK_COGNITIVE_WEIGHT: f64 = 30.;
+/// Weight of the `SLOC` in the `Change Risk`.
+pub const CHANGE_RISK_SLOC_WEIGHT: f64 = 25.;
+/// Weight of the number of arguments in the `Change Risk`.
+pub const CHANGE_RISK_ARGS_WEIGHT: f64 = 15.;
+
+// Values at which each factor of the `Change Risk` reaches half its weight
+const CHANGE_RISK_DECISIONS: f64 = 10.;
+const CHANGE_RISK_COGNITIVE: f64 = 15.;
+const CHANGE_RISK_SLOC: f64 = 50.;
+const CHANGE_RISK_ARGS: f64 = 4.;
+
+// Average identifier length from which the names are considered descriptive
+const READABILITY_IDENTIFIER_LENGTH: f64 = 8.;
+// Vocabulary up to which a function is easily taken in
+const READABILITY_VOCABULARY: f64 = 20.;
+// Comment ratio from which a function is considered well commented
+const READABILITY_COMMENT_RATIO: f64 = 0.2;
+
+// Suffixes of the types which gather the options of a function, such as
+// `RenderOptions` or a `RequestBuilder`
+const OPTIONS_TYPE_SUFFIXES: &[&str] = &[
+    "Args", "Builder", "Config", "Configuration", "Options", "Opts", "Params", "Settings",
+];
+
+// Last segment of the callees which usually perform I/O or change a global state
+const SIDE_EFFECT_CALLS: &[&str] = &[
+    "delete", "exec", "fetch", "fopen", "input", "log", "open", "print", "printf", "println",
+    "puts", "read", "remove", "request", "save", "send", "sleep", "spawn", "system", "unlink",
+    "write", "writeln",
+];
+
+/// Computes the `Testability Score` of `space`, the average score of its
+/// functions, or `100` when it has no functions.
+///
+/// `space` must be the root space computed from `code` for `lang`. Unlike
+/// the other factors, the fan-out and the side effects of a function are
+/// not metrics of its space, so they are read from the calls in `code`.
+///
+/// See [`testability_by_function`] for the score of each function.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{ai::testability, get_function_spaces, LANG};
+///
+/// let code = b"def double(x):\n    return x * 2\n";
+/// let space = get_function_spaces(&LANG::Python, code.to_vec(), Path::new("a.py"), None)
+///     .unwrap();
+/// assert_eq!(testability(&space, code, LANG::Python), 100.);
+/// ```
+#[must_use]
+pub fn testability(space: &FuncSpace, code: &[u8], lang: LANG) -> f64 {
+    let functions = testability_by_function(space, code, lang);
+    if functions.is_empty() {
+        return 100.;
+    }
+    functions.iter().map(|function| function.score).sum::<f64>() / usize_to_f64(functions.len())
+}
+
+/// Computes the `Testability Score` of each function in `space`.
+///
+/// `space` must be the root space computed from `code` for `lang`.
+///
+/// The score of a function is the weighted sum of four factors, each one
+/// ranging from `0` to `1`:
+///
+/// - the inverse of the number of arguments, `1` up to one argument,
+///   weighted [`TESTABILITY_ARGS_WEIGHT`]
+/// - the inverse of the cyclomatic complexity,
+///   weighted [`TESTABILITY_COMPLEXITY_WEIGHT`]
+/// - the inverse of one plus the fan-out, i.e. the number of distinct
+///   functions called, weighted [`TESTABILITY_FAN_OUT_WEIGHT`]
+/// - `1` when no call looks like a side effect, e.g. `print` or `open`,
+///   weighted [`TESTABILITY_SIDE_EFFECTS_WEIGHT`]
+///
+/// A pure function with at most one argument and no branches scores `100`.
+#[must_use]
+pub fn testability_by_function(
+    space: &FuncSpace,
+    code: &[u8],
+    lang: LANG,
+) -> Vec<FunctionTestability> {
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return Vec::new();
+    };
+    let calls = calls(tree.get_root(), code);
+
+    let mut functions = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            let callees: HashSet<&str> = calls
+                .iter()
+                .filter(|(line, _)| owns_line(space, *line))
+                .map(|(_, callee)| *callee)
+                .collect();
+            let has_side_effects = callees
+                .iter()
+                .any(|callee| SIDE_EFFECT_CALLS.contains(&last_segment(callee)));
+
+            let metrics = &space.metrics;
+            let args = metrics.nargs.fn_args() + metrics.nargs.closure_args();
+            let fan_out = usize_to_f64(callees.len());
+            let score = TESTABILITY_ARGS_WEIGHT / args.max(1.)
+                + TESTABILITY_COMPLEXITY_WEIGHT / metrics.cyclomatic.cyclomatic().max(1.)
+                + TESTABILITY_FAN_OUT_WEIGHT / (1. + fan_out)
+                + if has_side_effects {
+                    0.
+                } else {
+                    TESTABILITY_SIDE_EFFECTS_WEIGHT
+                };
+
+            functions.push(FunctionTestability {
+                name: space.name.clone(),
+                start_line: space.start_line,
+                end_line: space.end_line,
+                score,
+            });
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    functions
+}
+
+/// Computes the `Readability Score` of the functions in `space`.
+///
+/// `space` must be the root space computed from `code` for `lang`.
+///
+/// The score of a function is the weighted sum of three factors, each one
+/// ranging from `0` to `1`:
+///
+/// - the average length of the distinct identifiers among its `Halstead`
+///   operands divided by `8`, weighted [`READABILITY_IDENTIFIER_WEIGHT`]
+/// - `20` divided by its `Halstead` vocabulary, `1` up to a vocabulary
+///   of `20`, weighted [`READABILITY_VOCABULARY_WEIGHT`]
+/// - its comment ratio divided by `0.2`, i.e. `1` from one comment line
+///   out of five lines, weighted [`READABILITY_COMMENT_WEIGHT`]
+///
+/// Each factor is capped at `1`, so a short, commented function with
+/// descriptive names scores `100`.
+#[must_use]
+pub fn readability(space: &FuncSpace, code: &[u8], lang: LANG) -> Vec<FunctionReadability> {
+    let lengths = action::<IdentifierLengths>(&lang, code.to_vec(), Path::new(""), None, ());
+
+    let mut functions = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            let average_length = lengths
+                .get(&(space.start_line, space.end_line, space.name.clone()))
+                .copied()
+                .unwrap_or_default();
+
+            let metrics = &space.metrics;
+            let comment_ratio = metrics.loc.comment_ratio();
+            let comment_ratio = if comment_ratio.is_nan() {
+                0.
+            } else {
+                comment_ratio
+            };
+            let score = READABILITY_IDENTIFIER_WEIGHT
+                * (average_length / READABILITY_IDENTIFIER_LENGTH).min(1.)
+                + READABILITY_VOCABULARY_WEIGHT
+                    * (READABILITY_VOCABULARY / metrics.halstead.vocabulary().max(1.)).min(1.)
+                + READABILITY_COMMENT_WEIGHT
+                    * (comment_ratio / READABILITY_COMMENT_RATIO).min(1.);
+
+            functions.push(FunctionReadability {
+                name: space.name.clone(),
+                start_line: space.start_line,
+                end_line: space.end_line,
+                score,
+            });
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    functions
+}
+
+struct IdentifierLengths {
+    _guard: (),
+}
+
+impl Callback for IdentifierLengths {
+    // The average length of the distinct identifiers among the `Halstead`
+    // operands of each function, keyed by its lines and its name
+    type Res = HashMap<(usize, usize, Option<String>), f64>;
+    type Cfg = ();
+
+    fn call<T: ParserTrait>((): Self::Cfg, parser: &T) -> Self::Res {
+        let code = parser.get_code();
+        let mut lengths = HashMap::new();
+        let mut stack = vec![parser.get_root()];
+        while let Some(node) = stack.pop() {
+            if T::Getter::get_space_kind(&node) == SpaceKind::Function {
+                // The operands of a space include the ones of its subspaces
+                let mut maps = HalsteadMaps::new();
+                let mut nodes = vec![node];
+                while let Some(node) = nodes.pop() {
+                    T::Halstead::compute(&node, code, &mut maps);
+                    nodes.extend(node.children());
+                }
+                let identifiers: Vec<&str> = maps
+                    .operands
+                    .keys()
+                    .filter_map(|operand| std::str::from_utf8(operand).ok())
+                    .filter(|operand| is_identifier(operand))
+                    .collect();
+                let average_length = if identifiers.is_empty() {
+                    0.
+                } else {
+                    let length: usize = identifiers.iter().map(|name| name.chars().count()).sum();
+                    usize_to_f64(length) / usize_to_f64(identifiers.len())
+                };
+                // The names of the spaces are normalized the same way
+                let name = T::Getter::get_func_space_name(&node, code)
+                    .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "));
+                lengths.insert(
+                    (node.start_row() + 1, node.end_row() + 1, name),
+                    average_length,
+                );
+            }
+            stack.extend(node.children());
+        }
+        lengths
+    }
+}
+
+/// Computes the `Change Risk` of a function space from its metrics, to
+/// prioritize the functions to review or to refactor.
+///
+/// The score is the weighted sum of four factors, each one ranging from
+/// `0` to `1` and computed as `x / (x + h)`, so it reaches `0.5` when `x`
+/// is `h` and grows ever slower beyond:
+///
+/// - the decisions, i.e. the cyclomatic complexity minus one, with `h`
+///   being `10`, weighted [`CHANGE_RISK_CYCLOMATIC_WEIGHT`]
+/// - the cognitive complexity, with `h` being `15`, weighted
+///   [`CHANGE_RISK_COGNITIVE_WEIGHT`]
+/// - the `SLOC`, with `h` being `50`, weighted [`CHANGE_RISK_SLOC_WEIGHT`]
+/// - the number of arguments, with `h` being `4`, weighted
+///   [`CHANGE_RISK_ARGS_WEIGHT`]
+///
+/// The score ranges from `0` to `100`, which it never reaches. The
+/// metrics are the ones of the space alone, except for the `SLOC`, which
+/// spans its nested functions.
+#[must_use]
+pub fn change_risk(space: &FuncSpace) -> f64 {
+    let factor = |value: f64, half: f64| {
+        let value = value.max(0.);
+        value / (value + half)
+    };
+    let metrics = &space.metrics;
+    let args = metrics.nargs.fn_args() + metrics.nargs.closure_args();
+    CHANGE_RISK_CYCLOMATIC_WEIGHT
+        * factor(metrics.cyclomatic.cyclomatic() - 1., CHANGE_RISK_DECISIONS)
+        + CHANGE_RISK_COGNITIVE_WEIGHT
+            * factor(metrics.cognitive.cognitive(), CHANGE_RISK_COGNITIVE)
+        + CHANGE_RISK_SLOC_WEIGHT * factor(metrics.loc.sloc(), CHANGE_RISK_SLOC)
+        + CHANGE_RISK_ARGS_WEIGHT * factor(args, CHANGE_RISK_ARGS)
+}
+
+/// Computes the [`change_risk`] of the functions in `space`, along with
+/// their highest and their average score.
+#[must_use]
+pub fn file_change_risk(space: &FuncSpace) -> FileChangeRisk {
+    let mut functions = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            functions.push(FunctionChangeRisk {
+                name: space.name.clone(),
+                start_line: space.start_line,
+                end_line: space.end_line,
+                score: change_risk(space),
+            });
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+
+    let max = functions
+        .iter()
+        .map(|function| function.score)
+        .fold(0., f64::max);
+    let average = if functions.is_empty() {
+        0.
+    } else {
+        functions.iter().map(|function| function.score).sum::<f64>() / usize_to_f64(functions.len())
+    };
+    FileChangeRisk {
+        functions,
+        max,
+        average,
+    }
+}
+
+// Whether an operand is an identifier rather than a literal
+fn is_identifier(operand: &str) -> bool {
+    operand
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && operand.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Computes the `Error Handling Coverage` of the functions in `space`.
+///
+/// `space` must be the root space computed from `code` for `lang`.
+///
+/// A function is covered when its own body, excluding nested functions,
+/// contains an idiomatic error handling construct:
+///
+/// - `try`/`catch` blocks in languages with exceptions
+/// - the `?` operator, a `Result` return type or an `Err(..)` pattern in Rust
+/// - an `if err != nil` check in Go
+/// - `pcall`/`xpcall` in Lua and `try`/`tryCatch` in R
+#[must_use]
+pub fn error_handling_coverage(
+    space: &FuncSpace,
+    code: &[u8],
+    lang: LANG,
+) -> ErrorHandlingCoverage {
+    let lines = Tree::with_lang(code, &lang)
+        .map(|tree| error_handling_lines(tree.get_root(), code, lang))
+        .unwrap_or_default();
+
+    let mut functions = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            let has_error_handling = lines.iter().any(|line| owns_line(space, *line));
+            functions.push(FunctionErrorHandling {
+                name: space.name.clone(),
+                start_line: space.start_line,
+                end_line: space.end_line,
+                has_error_handling,
+            });
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+
+    let covered = functions
+        .iter()
+        .filter(|function| function.has_error_handling)
+        .count();
+    let ratio = if functions.is_empty() {
+        0.
+    } else {
+        usize_to_f64(covered) / usize_to_f64(functions.len())
+    };
+
+    ErrorHandlingCoverage {
+        functions,
+        covered,
+        ratio,
+    }
+}
+
+/// Finds the functions in `space` whose comment density, the ratio of
+/// comment lines to source lines, is lower than `threshold`.
+#[must_use]
+pub fn comment_density_smells(space: &FuncSpace, threshold: f64) -> Vec<CommentDensitySmell> {
+    let mut smells = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        let sloc = space.metrics.loc.sloc();
+        if space.kind == SpaceKind::Function && sloc > 0. {
+            let density = space.metrics.loc.cloc() / sloc;
+            if density < threshold {
+                smells.push(CommentDensitySmell {
+                    name: space.name.clone(),
+                    start_line: space.start_line,
+                    end_line: space.end_line,
+                    density,
+                });
+            }
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    smells
+}
+
+/// Finds the classes in `space` exceeding at least one of the thresholds
+/// of `config` on their number of methods, source lines and aggregated
+/// cyclomatic complexity.
+///
+/// Classes are the class and struct spaces, e.g. of `Java`, `C#`,
+/// `Kotlin`, `C++` or `TypeScript` code. Nested classes are checked too.
+#[must_use]
+pub fn god_class(space: &FuncSpace, config: &GodClassConfig) -> Vec<GodClass> {
+    let mut classes = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if matches!(space.kind, SpaceKind::Class | SpaceKind::Struct) {
+            let metrics = &space.metrics;
+            let methods = metrics.nom.functions_sum();
+            let sloc = metrics.loc.sloc();
+            let cyclomatic = metrics.cyclomatic.cyclomatic_sum();
+
+            let triggers: Vec<_> = [
+                (GodClassTrigger::Methods, methods > config.max_methods),
+                (GodClassTrigger::Sloc, sloc > config.max_sloc),
+                (GodClassTrigger::Cyclomatic, cyclomatic > config.max_cyclomatic),
+            ]
+            .into_iter()
+            .filter_map(|(trigger, exceeded)| exceeded.then_some(trigger))
+            .collect();
+
+            if !triggers.is_empty() {
+                classes.push(GodClass {
+                    name: space.name.clone(),
+                    start_line: space.start_line,
+                    end_line: space.end_line,
+                    methods,
+                    sloc,
+                    cyclomatic,
+                    triggers,
+                });
+            }
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    classes
+}
+
+/// Finds the exception handlers of a code whose body is empty or only
+/// contains comments, such as `catch (e) {}` or `except: pass`.
+///
+/// `space` must be the root space computed from `code` for `lang`.
+///
+/// The `catch` clauses of `Java`, `C#`, `C++`, `JavaScript`, `TypeScript`
+/// and `Kotlin` and the `except` clauses of `Python`, whose body only
+/// contains `pass` statements, are checked. No handler is returned for
+/// the other languages.
+#[must_use]
+pub fn empty_catch(space: &FuncSpace, code: &[u8], lang: LANG) -> Vec<EmptyCatch> {
+    let handler_kind = match lang {
+        LANG::Java
+        | LANG::Csharp
+        | LANG::Cpp
+        | LANG::Javascript
+        | LANG::Typescript
+        | LANG::Tsx => "catch_clause",
+        LANG::Kotlin => "catch_block",
+        LANG::Python => "except_clause",
+        _ => return Vec::new(),
+    };
+
+    let mut handlers = Vec::new();
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return handlers;
+    };
+    let mut stack = vec![tree.get_root()];
+    while let Some(node) = stack.pop() {
+        let is_empty = node.kind() == handler_kind
+            && handler_body(&node).is_some_and(|body| is_empty_body(&body));
+        if is_empty {
+            let start_line = node.start_row() + 1;
+            handlers.push(EmptyCatch {
+                function: innermost_function(space, start_line)
+                    .and_then(|function| function.name.clone()),
+                start_line,
+                end_line: node.end_row() + 1,
+            });
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev());
+    }
+    handlers
+}
+
+/// Finds the functions of a code taking more than `max_parameters`
+/// parameters without gathering their options into a single object.
+///
+/// `space` must be the root space computed from `code` for `lang`.
+///
+/// Unlike `NArgs`, a long parameter list is not reported when one of the
+/// parameters is an options object: a destructured object in
+/// `JavaScript` and `TypeScript`, or a parameter whose type is named like
+/// an options type or a builder, e.g. `ServerConfig` or `RequestBuilder`,
+/// in the typed languages. The `self` receivers of `Rust` and `Python`
+/// are not counted, while each name of a `Go` declaration such as
+/// `a, b int` is. Functions are returned in source order.
+#[must_use]
+pub fn long_parameter_lists(
+    space: &FuncSpace,
+    code: &[u8],
+    lang: LANG,
+    max_parameters: usize,
+) -> Vec<LongParameterList> {
+    let mut functions = Vec::new();
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return functions;
+    };
+    let mut stack = vec![tree.get_root()];
+    while let Some(node) = stack.pop() {
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let parameters: Vec<_> = list
+                .children()
+                .filter(|parameter| is_counted_parameter(parameter, code))
+                .collect();
+            let has_options = parameters
+                .iter()
+                .any(|parameter| is_options_parameter(parameter, code));
+            let count = parameters
+                .iter()
+                .map(|parameter| declared_parameters(parameter, lang))
+                .sum();
+            if count > max_parameters && !has_options {
+                let start_line = node.start_row() + 1;
+                functions.push(LongParameterList {
+                    name: innermost_function(space, start_line)
+                        .and_then(|function| function.name.clone()),
+                    start_line,
+                    end_line: node.end_row() + 1,
+                    parameters: count,
+                });
+            }
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev());
+    }
+    functions
+}
+
+/// Finds the functions and classes in `space` whose names are not written
+/// in the case expected by `conventions`, or by the usual conventions of
+/// `lang` when `None`.
+///
+/// `space` must be the root space computed from `code` for `lang`, so the
+/// names are the ones given by the language getters. Only the last segment
+/// of a qualified name, e.g. `bar` in `Foo::bar`, is checked, and the
+/// anonymous functions are skipped. Violations are returned in source order.
+#[must_use]
+pub fn naming(
+    space: &FuncSpace,
+    code: &[u8],
+    lang: LANG,
+    conventions: Option<&NamingConventions>,
+) -> Vec<NamingViolation> {
+    let conventions = conventions.copied().unwrap_or_else(|| NamingConventions::for_language(lang));
+
+    let mut violations = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        let expected = match space.kind {
+            SpaceKind::Function => conventions.functions,
+            SpaceKind::Class | SpaceKind::Struct | SpaceKind::Interface | SpaceKind::Trait => {
+                conventions.classes
+            }
+            _ => None,
+        };
+        let name = space.name.as_deref().and_then(unqualified_name);
+        if let (Some(expected), Some(name)) = (expected, name)
+            && !expected.matches(name)
+        {
+            violations.push(NamingViolation {
+                name: name.to_string(),
+                kind: space.kind,
+                expected,
+                line: name_line(space, code, name),
+                start_line: space.start_line,
+                end_line: space.end_line,
+            });
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    violations
+}
+
+// Returns the last segment of a qualified name, if it is an identifier
+fn unqualified_name(name: &str) -> Option<&str> {
+    let name = name.rsplit(['.', ':']).next()?;
+    let is_identifier =
+        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    is_identifier.then_some(name)
+}
+
+// Returns the first line of a space containing its name, since a space
+// can start with attributes or annotations
+fn name_line(space: &FuncSpace, code: &[u8], name: &str) -> usize {
+    code.split(|c| *c == b'\n')
+        .enumerate()
+        .skip(space.start_line.saturating_sub(1))
+        .take(space.length())
+        .find(|(_, line)| line.windows(name.len()).any(|window| window == name.as_bytes()))
+        .map_or(space.start_line, |(row, _)| row + 1)
+}
+
+// Checks if `line` belongs to `space` and not to one of its nested functions
+fn owns_line(space: &FuncSpace, line: usize) -> bool {
+    contains_line(space, line) && !in_nested_function(space, line)
+}
+
+fn in_nested_function(space: &FuncSpace, line: usize) -> bool {
+    space.spaces.iter().any(|child| {
+        if child.kind == SpaceKind::Function {
+            contains_line(child, line)
+        } else {
+            in_nested_function(child, line)
+        }
+    })
+}
+
+#[inline]
+fn contains_line(space: &FuncSpace, line: usize) -> bool {
+    (space.start_line..=space.end_line).contains(&line)
+}
+
+fn innermost_function(space: &FuncSpace, line: usize) -> Option<&FuncSpace> {
+    let nested = space
+        .spaces
+        .iter()
+        .filter(|child| contains_line(child, line))
+        .find_map(|child| innermost_function(child, line));
+    nested.or_else(|| {
+        (space.kind == SpaceKind::Function && contains_line(space, line)).then_some(space)
+    })
+}
+
+// Checks if a node of a parameter list is a parameter other than a receiver
+fn is_counted_parameter(parameter: &Node, code: &[u8]) -> bool {
+    match parameter.kind() {
+        "attribute_item" | "self_parameter" => false,
+        "identifier" => parameter
+            .utf8_text(code)
+            .is_some_and(|name| name != "self" && name != "cls"),
+        kind => parameter.is_named() && !kind.contains("comment"),
+    }
+}
+
+// The number of parameters declared by a node of a parameter list, since
+// `Go` declares `a, b int` as a single node with a name per parameter
+fn declared_parameters(parameter: &Node, lang: LANG) -> usize {
+    if lang == LANG::Go && parameter.kind() == "parameter_declaration" {
+        parameter
+            .children()
+            .filter(|child| child.kind() == "identifier")
+            .count()
+            .max(1)
+    } else {
+        1
+    }
+}
+
+// Checks if a parameter is a destructured object or is typed as an
+// options object or a builder
+fn is_options_parameter(parameter: &Node, code: &[u8]) -> bool {
+    let pattern = ["pattern", "left"]
+        .into_iter()
+        .find_map(|field| parameter.child_by_field_name(field))
+        .unwrap_or(*parameter);
+    if pattern.kind() == "object_pattern" {
+        return true;
+    }
+    parameter
+        .child_by_field_name("type")
+        .and_then(|ty| ty.utf8_text(code))
+        .and_then(|ty| {
+            // The name of `&mut Foo<T>`, `: Foo` or `foo::Foo` is `Foo`
+            ty.split('<')
+                .next()?
+                .rsplit(|c: char| !c.is_alphanumeric() && c != '_')
+                .find(|segment| !segment.is_empty())
+        })
+        .is_some_and(|name| OPTIONS_TYPE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+}
+
+// The block of an exception handler, its `body` field or its last block
+fn handler_body<'a>(handler: &Node<'a>) -> Option<Node<'a>> {
+    handler.child_by_field_name("body").or_else(|| {
+        handler
+            .children()
+            .filter(|child| child.kind() == "block")
+            .last()
+    })
+}
+
+// Checks if a block only contains comments, `Python` `pass` statements or
+// empty lists of statements
+fn is_empty_body(body: &Node) -> bool {
+    body.children().filter(Node::is_named).all(|child| {
+        child.kind().contains("comment")
+            || child.kind() == "pass_statement"
+            || (child.kind() == "statements" && is_empty_body(&child))
+    })
+}
+
+// Returns the lines, starting from 1, containing an error handling construct
+fn error_handling_lines(root: Node, code: &[u8], lang: LANG) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if is_error_handling(&node, code, lang) {
+            lines.push(node.start_row() + 1);
+        }
+        stack.extend(node.children());
+    }
+    lines
+}
+
+fn is_error_handling(node: &Node, code: &[u8], lang: LANG) -> bool {
+    let field_text = |name: &str, check: fn(&str) -> bool| {
+        node.child_by_field_name(name)
+            .and_then(|child| child.utf8_text(code))
+            .is_some_and(check)
+    };
+    match lang {
+        LANG::Rust => match node.kind() {
+            "try_expression" => true,
+            "function_item" => field_text("return_type", |ret| ret.contains("Result")),
+            "tuple_struct_pattern" => field_text("type", |path| path == "Err"),
+            _ => false,
+        },
+        LANG::Go => {
+            node.kind() == "if_statement"
+                && node
+                    .child_by_field_name("condition")
+                    .is_some_and(|condition| is_go_error_check(&condition, code))
+        }
+        LANG::Python
+        | LANG::Javascript
+        | LANG::Typescript
+        | LANG::Tsx
+        | LANG::Java
+        | LANG::Cpp
+        | LANG::Csharp => matches!(
+            node.kind(),
+            "try_statement" | "try_with_resources_statement"
+        ),
+        LANG::Kotlin => node.kind() == "try_expression",
+        LANG::Erlang => matches!(node.kind(), "try_expr" | "catch_expr"),
+        LANG::Elixir => is_call_to(node, code, &["try"]),
+        LANG::Lua => is_call_to(node, code, &["pcall", "xpcall"]),
+        LANG::R => is_call_to(node, code, &["try", "tryCatch"]),
+        LANG::Perl => matches!(node.kind(), "eval_expression" | "try_statement"),
+        LANG::Gleam | LANG::Graphql => false,
+    }
+}
+
+// Returns the callees, with the line of their call starting from 1
+fn calls<'a>(root: Node<'a>, code: &'a [u8]) -> Vec<(usize, &'a str)> {
+    let mut calls = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let callee = match node.kind() {
+            "call" | "call_expression" | "function_call" | "invocation_expression" => node
+                .child_by_field_name("function")
+                .or_else(|| node.child_by_field_name("name"))
+                .or_else(|| node.child(0)),
+            "method_invocation" => node.child_by_field_name("name"),
+            "macro_invocation" => node.child_by_field_name("macro"),
+            _ => None,
+        };
+        if let Some(callee) = callee.and_then(|callee| callee.utf8_text(code)) {
+            calls.push((node.start_row() + 1, callee));
+        }
+        stack.extend(node.children());
+    }
+    calls
+}
+
+// The name of a callee without its receiver or path, e.g. `log` for `console.log`
+fn last_segment(callee: &str) -> &str {
+    callee
+        .rsplit(['.', ':', '>'])
+        .next()
+        .unwrap_or(callee)
+}
+
+// Checks for an `err != nil` comparison, the operands can be swapped
+fn is_go_error_check(condition: &Node, code: &[u8]) -> bool {
+    if condition.kind() != "binary_expression" {
+        return false;
+    }
+    let operator = condition.child_by_field_name("operator");
+    let left = condition.child_by_field_name("left");
+    let right = condition.child_by_field_name("right");
+    let (Some(operator), Some(left), Some(right)) = (operator, left, right) else {
+        return false;
+    };
+    let is_error = |operand: &Node| {
+        operand.kind() == "identifier"
+            && operand
+                .utf8_text(code)
+                .is_some_and(|name| name == "err" || name.ends_with("Err"))
+    };
+    operator.kind() == "!="
+        && ((is_error(&left) && right.kind() == "nil")
+            || (left.kind() == "nil" && is_error(&right)))
+}
+
+fn is_call_to(node: &Node, code: &[u8], names: &[&str]) -> bool {
+    matches!(node.kind(), "call" | "function_call")
+        && node.child(0).is_some_and(|callee| {
+            callee.kind() == "identifier"
+                && callee
+                    .utf8_text(code)
+                    .is_some_and(|name| names.contains(&name))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::get_function_spaces;
+
+    fn coverage(code: &str, path: &str, lang: LANG) -> ErrorHandlingCoverage {
+        let source = code.as_bytes().to_vec();
+        let space = get_function_spaces(&lang, source.clone(), Path::new(path), None).unwrap();
+        error_handling_coverage(&space, &source, lang)
+    }
+
+    #[test]
+    fn go_error_checks() {
+        let result = coverage(
+            "package main
+
+            func checked() error {
+                f, err := open()
+                if err != nil {
+                    return err
+                }
+                return f.Close()
+            }
+
+            func ignored() {
+                f, _ := open()
+                f.Close()
+            }",
+            "foo.go",
+            LANG::Go,
+        );
+
+        let covered: Vec<_> = result
+            .functions
+            .iter()
+            .map(|function| (function.name.as_deref(), function.has_error_handling))
+            .collect();
+        assert_eq!(covered, [(Some("checked"), true), (Some("ignored"), false)]);
+        assert_eq!(result.covered, 1);
+        assert!((result.ratio - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn rust_nested_function_is_not_covered() {
+        let result = coverage(
+            "fn outer() -> Result<(), Error> {
+                fn inner() -> u8 {
+                    0
+                }
+                check(inner())?;
+                Ok(())
+            }",
+            "foo.rs",
+            LANG::Rust,
+        );
+
+        let covered: Vec<_> = result
+            .functions
+            .iter()
+            .map(|function| (function.name.as_deref(), function.has_error_handling))
+            .collect();
+        assert_eq!(covered, [(Some("outer"), true), (Some("inner"), false)]);
+    }
+
+    #[test]
+    fn python_comment_density() {
+        let source = "def documented():
+    # Adds one
+    return 1 + 1
+
+def undocumented():
+    a = 1
+    return a + 1
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let smells = comment_density_smells(&space, 0.2);
+        assert_eq!(smells.len(), 1);
+        assert_eq!(smells[0].name.as_deref(), Some("undocumented"));
+    }
+
+    #[test]
+    fn java_god_class() {
+        use std::fmt::Write;
+
+        let mut source = String::from("class Huge {\n");
+        for i in 0..30 {
+            writeln!(source, "    int get{i}() {{ return {i}; }}").unwrap();
+        }
+        source.push_str("}\n\nclass Small {\n    int get() { return 0; }\n}\n");
+        let space =
+            get_function_spaces(&LANG::Java, source.into_bytes(), Path::new("Foo.java"), None)
+                .unwrap();
+
+        let classes = god_class(&space, &GodClassConfig::default());
+        assert_eq!(classes.len(), 1);
+        assert_eq!(classes[0].name.as_deref(), Some("Huge"));
+        assert_eq!(classes[0].methods, 30.);
+        assert_eq!(classes[0].triggers, [GodClassTrigger::Methods]);
+    }
+
+    #[test]
+    fn javascript_empty_catch() {
+        let source = "function load(path) {
+    try {
+        return read(path);
+    } catch (e) {}
+    try {
+        return parse(path);
+    } catch (e) {
+        // Ignored on purpose
+    }
+    try {
+        return fetch(path);
+    } catch (e) {
+        console.log(e);
+    }
+}
+";
+        let space = get_function_spaces(
+            &LANG::Javascript,
+            source.as_bytes().to_vec(),
+            Path::new("foo.js"),
+            None,
+        )
+        .unwrap();
+
+        let handlers = empty_catch(&space, source.as_bytes(), LANG::Javascript);
+        let lines: Vec<_> = handlers
+            .iter()
+            .map(|handler| (handler.function.as_deref(), handler.start_line, handler.end_line))
+            .collect();
+        assert_eq!(lines, [(Some("load"), 4, 4), (Some("load"), 7, 9)]);
+    }
+
+    #[test]
+    fn kotlin_empty_catch() {
+        let source = "fun load(path: String): String {
+    try {
+        return read(path)
+    } catch (e: Exception) {}
+    try {
+        return fetch(path)
+    } catch (e: Exception) {
+        println(e)
+    }
+    return \"\"
+}
+";
+        let space = get_function_spaces(
+            &LANG::Kotlin,
+            source.as_bytes().to_vec(),
+            Path::new("foo.kt"),
+            None,
+        )
+        .unwrap();
+
+        let handlers = empty_catch(&space, source.as_bytes(), LANG::Kotlin);
+        assert_eq!(
+            handlers,
+            [EmptyCatch {
+                function: Some("load".to_owned()),
+                start_line: 4,
+                end_line: 4,
+            }]
+        );
+    }
+
+    #[test]
+    fn python_except_pass() {
+        let source = "def load(path):
+    try:
+        return read(path)
+    except:
+        pass
+
+try:
+    load(\"a\")
+except ValueError as e:
+    print(e)
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let handlers = empty_catch(&space, source.as_bytes(), LANG::Python);
+        assert_eq!(
+            handlers,
+            [EmptyCatch {
+                function: Some("load".to_owned()),
+                start_line: 4,
+                end_line: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn rust_naming() {
+        let source = "fn my_func() {}
+
+#[inline]
+fn MyFunc() {}
+
+trait shape {}
+";
+        let space = get_function_spaces(
+            &LANG::Rust,
+            source.as_bytes().to_vec(),
+            Path::new("foo.rs"),
+            None,
+        )
+        .unwrap();
+
+        let violations = naming(&space, source.as_bytes(), LANG::Rust, None);
+        let names: Vec<_> = violations
+            .iter()
+            .map(|violation| (violation.name.as_str(), violation.expected, violation.line))
+            .collect();
+        assert_eq!(names, [("MyFunc", NameCase::Snake, 4), ("shape", NameCase::Pascal, 6)]);
+
+        let conventions = NamingConventions {
+            functions: Some(NameCase::Pascal),
+            classes: None,
+        };
+        let violations = naming(&space, source.as_bytes(), LANG::Rust, Some(&conventions));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].name, "my_func");
+    }
+
+    #[test]
+    fn python_testability() {
+        let source = "def double(x):
+    return x * 2
+
+def report(a, b, c, d, e, f):
+    if a > b:
+        print(a)
+    elif c:
+        for item in d:
+            if item and e:
+                save(item)
+    return f
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let scores = testability_by_function(&space, source.as_bytes(), LANG::Python);
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].name.as_deref(), Some("double"));
+        assert_eq!(scores[0].score, 100.);
+        assert_eq!(scores[1].name.as_deref(), Some("report"));
+        assert!(scores[1].score < 30., "{}", scores[1].score);
+
+        let average = testability(&space, source.as_bytes(), LANG::Python);
+        assert_eq!(average, (scores[0].score + scores[1].score) / 2.);
+    }
+
+    #[test]
+    fn python_readability() {
+        let source = "def f(a, b):
+    c = a * b
+    return c + a
+
+def compute_total_price(unit_price, quantity):
+    # The price before any discount
+    total_price = unit_price * quantity
+    return total_price
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let scores = readability(&space, source.as_bytes(), LANG::Python);
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].name.as_deref(), Some("f"));
+        assert!(scores[0].score < 40., "{}", scores[0].score);
+        assert_eq!(scores[1].name.as_deref(), Some("compute_total_price"));
+        assert!(scores[1].score > 70., "{}", scores[1].score);
+    }
+
+    #[test]
+    fn python_change_risk() {
+        let source = "class Order:
+    def total(self):
+        return self.amount
+
+    def ship(self, carrier, address, express, retries, notify):
+        if not address:
+            raise ValueError(address)
+        for attempt in range(retries):
+            try:
+                if express and carrier.available():
+                    label = carrier.express_label(address)
+                elif carrier.available():
+                    label = carrier.label(address)
+                else:
+                    continue
+                if notify:
+                    for user in self.watchers:
+                        if user.active and not user.muted:
+                            user.send(label)
+                return label
+            except TimeoutError:
+                if attempt == retries - 1:
+                    raise
+        return None
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let risk = file_change_risk(&space);
+        assert_eq!(risk.functions.len(), 2);
+        let getter = &risk.functions[0];
+        assert_eq!(getter.name.as_deref(), Some("total"));
+        assert!(getter.score < 10., "{}", getter.score);
+        let ship = &risk.functions[1];
+        assert_eq!(ship.name.as_deref(), Some("ship"));
+        assert!(ship.score > 40., "{}", ship.score);
+
+        assert_eq!(risk.max, ship.score);
+        assert_eq!(risk.average, (getter.score + ship.score) / 2.);
+    }
+
+    #[test]
+    fn rust_long_parameter_lists() {
+        let source = "fn plain(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8) {}
+
+fn configured(options: &RenderOptions) {}
+
+fn built(a: u8, b: u8, c: u8, d: u8, e: u8, builder: RequestBuilder) {}
+
+impl Shape {
+    fn method(&self, a: u8, b: u8, c: u8, d: u8, e: u8) {}
+}
+";
+        let space = get_function_spaces(
+            &LANG::Rust,
+            source.as_bytes().to_vec(),
+            Path::new("foo.rs"),
+            None,
+        )
+        .unwrap();
+
+        let functions = long_parameter_lists(&space, source.as_bytes(), LANG::Rust, 5);
+        assert_eq!(
+            functions,
+            [LongParameterList {
+                name: Some("plain".to_owned()),
+                start_line: 1,
+                end_line: 1,
+                parameters: 7,
+            }]
+        );
+    }
+
+    #[test]
+    fn go_long_parameter_lists() {
+        let source = "package main
+
+func plain(a, b, c int, d, e, f string) {}
+
+func configured(a, b, c, d, e int, options ServerOptions) {}
+
+func (s *Shape) method(a, b int, c string) {}
+";
+        let space = get_function_spaces(
+            &LANG::Go,
+            source.as_bytes().to_vec(),
+            Path::new("foo.go"),
+            None,
+        )
+        .unwrap();
+
+        let functions = long_parameter_lists(&space, source.as_bytes(), LANG::Go, 5);
+        assert_eq!(
+            functions,
+            [LongParameterList {
+                name: Some("plain".to_owned()),
+                start_line: 3,
+                end_line: 3,
+                parameters: 6,
+            }]
+        );
+    }
+
+    #[test]
+    fn javascript_long_parameter_lists() {
+        let source = "function plain(a, b, c, d, e, f, g) {}
+
+function configured(a, b, c, d, e, { f, g }) {}
+
+class Shape {
+  method(a, b, c, d, e, f) {}
+}
+";
+        let space = get_function_spaces(
+            &LANG::Javascript,
+            source.as_bytes().to_vec(),
+            Path::new("foo.js"),
+            None,
+        )
+        .unwrap();
+
+        let functions = long_parameter_lists(&space, source.as_bytes(), LANG::Javascript, 5);
+        let found: Vec<_> = functions
+            .iter()
+            .map(|function| (function.start_line, function.parameters))
+            .collect();
+        assert_eq!(found, [(1, 7), (6, 6)]);
+        assert_eq!(functions[0].name.as_deref(), Some("plain"));
+    }
+}
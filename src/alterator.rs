@@ -209,3 +209,9 @@ impl Alterator for GoCode {}
 impl Alterator for CsharpCode {}
 
 impl Alterator for KotlinCode {}
+
+impl Alterator for RCode {}
+
+impl Alterator for PerlCode {}
+
+impl Alterator for GraphqlCode {}
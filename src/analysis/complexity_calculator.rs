@@ -179,6 +179,9 @@ pub fn get_function_patterns(language: LANG) -> Vec<&'static str> {
         LANG::Erlang => vec!["-spec ", "when "],
         LANG::Gleam => vec!["pub fn ", "fn "],
         LANG::Lua => vec!["function "],
+        LANG::R => vec!["function(", "function ("],
+        LANG::Perl => vec!["sub "],
+        LANG::Graphql => vec![],
     }
 }
 
@@ -200,6 +203,9 @@ pub fn get_control_flow_patterns(language: LANG) -> Vec<&'static str> {
         LANG::Erlang => vec!["case ", "if ", "receive "],
         LANG::Gleam => vec!["case ", "if ", "try "],
         LANG::Lua => vec!["if ", "elseif ", "for ", "while "],
+        LANG::R => vec!["if ", "if(", "else ", "for ", "for(", "while ", "while(", "repeat "],
+        LANG::Perl => vec!["if ", "elsif ", "unless ", "for ", "foreach ", "while ", "until "],
+        LANG::Graphql => vec![],
     }
 }
 
@@ -217,6 +223,9 @@ pub fn get_operator_patterns(language: LANG) -> Vec<&'static str> {
         LANG::Csharp => vec!["&&", "||", "!", "==", "!=", "??"],
         LANG::Erlang => vec!["and", "or", "not", "andalso", "orelse"],
         LANG::Lua => vec!["and", "or", "not"],
+        LANG::R => vec!["&&", "||", "&", "|", "!", "==", "!="],
+        LANG::Perl => vec!["&&", "||", "//", "!", "and", "or", "not"],
+        LANG::Graphql => vec![],
     }
 }
 
@@ -292,7 +301,7 @@ pub fn calculate_comment_ratio(code: &str, language: LANG) -> f64 {
 #[inline]
 pub fn get_comment_patterns(language: LANG) -> Vec<&'static str> {
     match language {
-        LANG::Elixir | LANG::Python => vec!["#"],
+        LANG::Elixir | LANG::Python | LANG::R | LANG::Perl | LANG::Graphql => vec!["#"],
         LANG::Rust
         | LANG::Javascript
         | LANG::Typescript
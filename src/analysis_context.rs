@@ -1,4 +1,10 @@
-use std::{cell::RefCell, slice};
+use std::{
+    cell::{Cell, RefCell},
+    slice,
+    time::Instant,
+};
+
+use crate::output::KeyStyle;
 
 #[derive(Clone, Copy)]
 struct CodeRef {
@@ -8,6 +14,10 @@ struct CodeRef {
 
 thread_local! {
     static CURRENT_CODE: RefCell<Option<CodeRef>> = const { RefCell::new(None) };
+    static PARSE_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static TIMED_OUT: Cell<bool> = const { Cell::new(false) };
+    static KEY_STYLE: Cell<KeyStyle> = const { Cell::new(KeyStyle::Conventional) };
+    static FLOAT_PRECISION: Cell<Option<u8>> = const { Cell::new(None) };
 }
 
 /// Guard that clears the current code slice when dropped.
@@ -40,6 +50,92 @@ fn clear_current_code() {
     });
 }
 
+/// Guard that clears the current parse deadline when dropped.
+pub(crate) struct DeadlineGuard;
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        PARSE_DEADLINE.with(|deadline| deadline.set(None));
+    }
+}
+
+/// Set the instant after which parsing on this thread is cancelled and
+/// return a guard that will clear it on drop.
+pub(crate) fn enter_deadline(deadline: Option<Instant>) -> DeadlineGuard {
+    PARSE_DEADLINE.with(|slot| slot.set(deadline));
+    TIMED_OUT.with(|timed_out| timed_out.set(false));
+    DeadlineGuard
+}
+
+/// Return the parse deadline of this thread, if any.
+pub(crate) fn parse_deadline() -> Option<Instant> {
+    PARSE_DEADLINE.with(Cell::get)
+}
+
+/// Record that a parse on this thread was cancelled by its deadline.
+pub(crate) fn mark_timed_out() {
+    TIMED_OUT.with(|timed_out| timed_out.set(true));
+}
+
+/// Check whether a parse was cancelled since the deadline was entered.
+pub(crate) fn timed_out() -> bool {
+    TIMED_OUT.with(Cell::get)
+}
+
+/// Guard that restores the previous key style when dropped.
+pub(crate) struct KeyStyleGuard(KeyStyle);
+
+impl Drop for KeyStyleGuard {
+    fn drop(&mut self) {
+        KEY_STYLE.with(|style| style.set(self.0));
+    }
+}
+
+/// Set the key style of the metrics serialized on this thread and return
+/// a guard that will restore the previous one on drop.
+pub(crate) fn enter_key_style(style: KeyStyle) -> KeyStyleGuard {
+    KeyStyleGuard(KEY_STYLE.with(|slot| slot.replace(style)))
+}
+
+/// Return the key style of the metrics serialized on this thread.
+pub(crate) fn key_style() -> KeyStyle {
+    KEY_STYLE.with(Cell::get)
+}
+
+/// Guard that restores the previous float precision when dropped.
+pub(crate) struct FloatPrecisionGuard(Option<u8>);
+
+impl Drop for FloatPrecisionGuard {
+    fn drop(&mut self) {
+        FLOAT_PRECISION.with(|precision| precision.set(self.0));
+    }
+}
+
+/// Set the number of decimals of the metrics serialized on this thread and
+/// return a guard that will restore the previous one on drop.
+pub(crate) fn enter_float_precision(precision: Option<u8>) -> FloatPrecisionGuard {
+    FloatPrecisionGuard(FLOAT_PRECISION.with(|slot| slot.replace(precision)))
+}
+
+/// Round `value` to the float precision of this thread, if any.
+///
+/// Values which are not finite, or which would overflow once scaled, are
+/// returned unchanged.
+pub(crate) fn round_float(value: f64) -> f64 {
+    match FLOAT_PRECISION.with(Cell::get) {
+        Some(precision) if value.is_finite() => {
+            let factor = 10_f64.powi(i32::from(precision));
+            let rounded = (value * factor).round() / factor;
+            if rounded.is_finite() {
+                rounded
+            } else {
+                value
+            }
+        }
+        _ => value,
+    }
+}
+
 /// Execute a closure with access to the current source code slice, if available.
 pub(crate) fn with_current_code<F, R>(f: F) -> Option<R>
 where
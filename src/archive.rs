@@ -0,0 +1,175 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use globset::GlobSet;
+
+use crate::code_analyzer::AnalyzerError;
+use crate::spaces::{FuncSpace, MetricsOptions};
+use crate::{get_function_spaces_with_options, get_language_for_file, LANG};
+
+/// Options for [`analyze_archive`].
+#[derive(Debug, Clone)]
+pub struct ArchiveOptions {
+    /// Entries analyzed, all of them when empty.
+    pub include: GlobSet,
+    /// Entries skipped.
+    pub exclude: GlobSet,
+    /// Options tuning how the metrics of each entry are computed.
+    pub metrics: MetricsOptions,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            metrics: MetricsOptions::default(),
+        }
+    }
+}
+
+/// Analyzes the source files contained in a `zip` or `tar` archive,
+/// without extracting them to disk.
+///
+/// The format is detected from the first bytes of `reader`. The language
+/// of each entry is detected from the extension of its name, and the
+/// entries of an unknown language or not matching the globs of `options`
+/// are skipped.
+///
+/// Returns the name and the root space of each analyzed entry, in the
+/// order of the archive.
+///
+/// # Errors
+/// Returns [`AnalyzerError::Io`] if the archive cannot be read, or
+/// [`AnalyzerError::AnalysisFailed`] if the metrics of an entry could
+/// not be computed.
+pub fn analyze_archive<R: Read + Seek>(
+    mut reader: R,
+    options: &ArchiveOptions,
+) -> Result<Vec<(String, FuncSpace)>, AnalyzerError> {
+    let mut magic = [0; 2];
+    let is_zip = reader.read_exact(&mut magic).is_ok() && magic == *b"PK";
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut results = Vec::new();
+    if is_zip {
+        let mut archive = zip::ZipArchive::new(reader).map_err(io::Error::other)?;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(io::Error::other)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            if let Some(lang) = entry_language(&name, options) {
+                let mut source = Vec::new();
+                entry.read_to_end(&mut source)?;
+                results.push(analyze_entry(name, source, lang, options)?);
+            }
+        }
+    } else {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path()?.to_string_lossy().into_owned();
+            if let Some(lang) = entry_language(&name, options) {
+                let mut source = Vec::new();
+                entry.read_to_end(&mut source)?;
+                results.push(analyze_entry(name, source, lang, options)?);
+            }
+        }
+    }
+    Ok(results)
+}
+
+// Returns the language of an entry which has to be analyzed
+fn entry_language(name: &str, options: &ArchiveOptions) -> Option<LANG> {
+    let path = Path::new(name);
+    let selected = (options.include.is_empty() || options.include.is_match(path))
+        && (options.exclude.is_empty() || !options.exclude.is_match(path));
+    if selected {
+        get_language_for_file(path)
+    } else {
+        None
+    }
+}
+
+fn analyze_entry(
+    name: String,
+    source: Vec<u8>,
+    language: LANG,
+    options: &ArchiveOptions,
+) -> Result<(String, FuncSpace), AnalyzerError> {
+    let space = get_function_spaces_with_options(
+        &language,
+        source,
+        Path::new(&name),
+        None,
+        &options.metrics,
+    )
+    .ok_or_else(|| AnalyzerError::AnalysisFailed {
+        language,
+        reason: format!("metric pipeline returned no data for `{name}`"),
+    })?;
+    Ok((name, space))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use globset::{Glob, GlobSetBuilder};
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    fn zip_archive(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let file_options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, source) in files {
+            writer.start_file(*name, file_options).unwrap();
+            writer.write_all(source.as_bytes()).unwrap();
+        }
+        let mut archive = writer.finish().unwrap();
+        archive.set_position(0);
+        archive
+    }
+
+    #[test]
+    fn zip_entries_are_analyzed() {
+        let archive = zip_archive(&[
+            ("src/double.py", "def double(x):\n    return x * 2\n"),
+            ("README.md", "# Double\n"),
+            ("src/lib.rs", "fn double(x: u32) -> u32 {\n    x * 2\n}\n"),
+        ]);
+
+        let results = analyze_archive(archive, &ArchiveOptions::default()).unwrap();
+        let names: Vec<_> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["src/double.py", "src/lib.rs"]);
+        for (_, space) in &results {
+            assert_eq!(space.spaces.len(), 1);
+            assert_eq!(space.spaces[0].name.as_deref(), Some("double"));
+        }
+    }
+
+    #[test]
+    fn zip_entries_are_filtered() {
+        let archive = zip_archive(&[
+            ("src/double.py", "def double(x):\n    return x * 2\n"),
+            ("tests/test_double.py", "def test_double():\n    pass\n"),
+        ]);
+        let mut exclude = GlobSetBuilder::new();
+        exclude.add(Glob::new("tests/**").unwrap());
+        let options = ArchiveOptions {
+            exclude: exclude.build().unwrap(),
+            ..ArchiveOptions::default()
+        };
+
+        let results = analyze_archive(archive, &options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "src/double.py");
+    }
+}
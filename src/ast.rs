@@ -3,8 +3,12 @@ use serde::{
     Deserialize, Serialize,
 };
 
+use std::fmt::Write;
+
 use crate::{
     alterator::Alterator,
+    langs::LANG,
+    node::Tree,
     traits::{Callback, ParserTrait},
 };
 
@@ -128,6 +132,64 @@ fn build<T: ParserTrait>(parser: &T, span: bool, comment: bool) -> Option<AstNod
     }
 }
 
+/// Returns the `AST` of a code as a tree-sitter S-expression.
+///
+/// Only named nodes appear in the S-expression, e.g. `a = 1` in `Python`
+/// gives `(module (expression_statement (assignment left: (identifier)
+/// right: (integer))))`.
+#[must_use]
+pub fn to_sexp(code: &[u8], lang: LANG) -> String {
+    Tree::with_lang(code, &lang).map_or_else(String::new, |tree| tree.get_root().to_sexp())
+}
+
+/// Returns the `AST` of a code as a Graphviz `DOT` graph.
+///
+/// Every node, anonymous ones included, is labelled with its kind, and
+/// leaves are also labelled with their text.
+#[must_use]
+pub fn to_dot(code: &[u8], lang: LANG) -> String {
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return String::new();
+    };
+    let mut dot = String::from("digraph ast {\n    node [shape=box];\n");
+
+    let mut id = 0;
+    let mut stack = vec![(tree.get_root(), None)];
+    while let Some((node, parent)) = stack.pop() {
+        let mut label = node.kind().to_owned();
+        let text = node
+            .utf8_text(code)
+            .filter(|text| node.child_count() == 0 && *text != label);
+        if let Some(text) = text {
+            label.push('\n');
+            label.push_str(text);
+        }
+        let _ = writeln!(dot, "    n{id} [label=\"{}\"];", escape_dot(&label));
+        if let Some(parent) = parent {
+            let _ = writeln!(dot, "    n{parent} -> n{id};");
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev().map(|child| (child, Some(id))));
+        id += 1;
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn escape_dot(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub struct AstCallback {
     _guard: (),
 }
@@ -155,3 +217,30 @@ impl Callback for AstCallback {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn python_sexp() {
+        let sexp = to_sexp(b"a = 1", LANG::Python);
+        assert!(
+            sexp.starts_with("(module (expression_statement (assignment"),
+            "{sexp}"
+        );
+        assert!(sexp.contains("(identifier)"));
+        assert!(sexp.contains("(integer)"));
+    }
+
+    #[test]
+    fn python_dot() {
+        let dot = to_dot(b"s = \"a\"", LANG::Python);
+        assert!(dot.starts_with("digraph ast {"));
+        assert!(dot.contains("n0 [label=\"module\"];"));
+        assert!(dot.contains("[label=\"identifier\\ns\"];"));
+        assert!(dot.contains("n0 -> n1;"));
+        // Each node but the root has a single incoming edge
+        assert_eq!(dot.matches(" -> ").count() + 1, dot.matches("[label=").count());
+    }
+}
@@ -7,7 +7,9 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use comfy_table::{presets::UTF8_FULL, Cell, Color, Table};
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
 use serde_json::json;
+use singularity_analysis_engine::{with_float_precision, RoundedJson};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -29,6 +31,10 @@ struct Cli {
     #[arg(short, long, global = true, default_value = "table")]
     format: OutputFormat,
 
+    /// Number of decimals of the floats in the JSON output
+    #[arg(long, global = true)]
+    float_precision: Option<u8>,
+
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
@@ -159,12 +165,19 @@ fn main() -> Result<()> {
             language,
             insights,
             recursive,
-        } => analyze_command(&path, language, insights, recursive, cli.format)?,
+        } => analyze_command(
+            &path,
+            language,
+            insights,
+            recursive,
+            cli.format,
+            cli.float_precision,
+        )?,
         Commands::Metrics {
             path,
             language,
             metric,
-        } => metrics_command(&path, language, metric, cli.format)?,
+        } => metrics_command(&path, language, metric, cli.format, cli.float_precision)?,
         Commands::Languages => languages_command(cli.format)?,
         Commands::Complexity {
             path,
@@ -190,6 +203,7 @@ fn analyze_command(
     _insights: bool,
     recursive: bool,
     format: OutputFormat,
+    float_precision: Option<u8>,
 ) -> Result<()> {
     let start = Instant::now();
 
@@ -219,7 +233,7 @@ fn analyze_command(
 
     match format {
         OutputFormat::Table => display_table(&results),
-        OutputFormat::Json => display_json(&results)?,
+        OutputFormat::Json => display_json(&results, float_precision)?,
         OutputFormat::Pretty => display_pretty(&results),
         OutputFormat::Csv => display_csv(&results),
     }
@@ -235,6 +249,7 @@ fn metrics_command(
     _language: Option<String>,
     metric: Option<MetricType>,
     format: OutputFormat,
+    float_precision: Option<u8>,
 ) -> Result<()> {
     if !path.exists() {
         anyhow::bail!("File does not exist: {}", path.display());
@@ -251,7 +266,7 @@ fn metrics_command(
 
     match format {
         OutputFormat::Table => display_metrics_table(&metrics, metric),
-        OutputFormat::Json => display_metrics_json(&metrics, metric)?,
+        OutputFormat::Json => display_metrics_json(&metrics, metric, float_precision)?,
         OutputFormat::Pretty => display_metrics_pretty(&metrics, metric),
         OutputFormat::Csv => display_metrics_csv(&metrics, metric),
     }
@@ -274,6 +289,9 @@ fn languages_command(format: OutputFormat) -> Result<()> {
         ("Kotlin", "⚠", "Partial metrics"),
         ("C#", "✓", "Full support"),
         ("Lua", "✓", "Full support"),
+        ("R", "⚠", "Partial metrics"),
+        ("Perl", "⚠", "Partial metrics"),
+        ("GraphQL", "⚠", "Partial metrics"),
     ];
 
     match format {
@@ -503,8 +521,13 @@ fn is_source_file(ext: &str) -> bool {
     matches!(
         ext,
         "rs" | "py"
+            | "ipynb"
             | "js"
+            | "mjs"
+            | "cjs"
             | "ts"
+            | "mts"
+            | "cts"
             | "tsx"
             | "jsx"
             | "java"
@@ -521,6 +544,12 @@ fn is_source_file(ext: &str) -> bool {
             | "kt"
             | "cs"
             | "lua"
+            | "r"
+            | "R"
+            | "pl"
+            | "pm"
+            | "graphql"
+            | "gql"
     )
 }
 
@@ -657,9 +686,8 @@ fn display_table(results: &[AnalysisResult]) {
     println!("{table}");
 }
 
-fn display_json(results: &[AnalysisResult]) -> Result<()> {
-    println!("{}", serde_json::to_string_pretty(results)?);
-    Ok(())
+fn display_json(results: &[AnalysisResult], float_precision: Option<u8>) -> Result<()> {
+    print_json(results, float_precision)
 }
 
 fn display_pretty(results: &[AnalysisResult]) {
@@ -711,8 +739,21 @@ fn display_metrics_table(metrics: &MetricsData, filter: Option<MetricType>) {
     println!("{table}");
 }
 
-fn display_metrics_json(metrics: &MetricsData, _filter: Option<MetricType>) -> Result<()> {
-    println!("{}", serde_json::to_string_pretty(metrics)?);
+fn display_metrics_json(
+    metrics: &MetricsData,
+    _filter: Option<MetricType>,
+    float_precision: Option<u8>,
+) -> Result<()> {
+    print_json(metrics, float_precision)
+}
+
+// Prints `value` as JSON, with its floats rounded to `float_precision`
+fn print_json(value: &impl Serialize, float_precision: Option<u8>) -> Result<()> {
+    let value = serde_json::to_value(value)?;
+    let json = with_float_precision(float_precision, || {
+        serde_json::to_string_pretty(&RoundedJson(&value))
+    })?;
+    println!("{json}");
     Ok(())
 }
 
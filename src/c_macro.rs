@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::c_langs_macros::is_predefined_macros;
 
@@ -60,6 +60,160 @@ pub fn replace<S: ::std::hash::BuildHasher>(
     }
 }
 
+// The state of an `#if`/`#ifdef`/`#ifndef` group
+struct Conditional {
+    // Whether the code enclosing the group is compiled
+    parent_active: bool,
+    // Whether a branch of the group has already been compiled
+    taken: bool,
+    // Whether a condition of the group could not be evaluated
+    unknown: bool,
+}
+
+/// Blanks the lines of the `#if`/`#ifdef`/`#ifndef` branches which are not
+/// compiled when the macros in `defines` are defined.
+///
+/// Macros defined or undefined in the code itself are taken into account.
+/// Conditions are evaluated on a best effort basis: `defined`, `!`, `&&`,
+/// `||`, integer literals and macro values are supported, while a group
+/// with any other condition is left untouched.
+///
+/// Blanked lines keep their line breaks, so the line numbers don't change.
+/// Returns `None` when no line is blanked.
+pub fn resolve_conditionals<S: ::std::hash::BuildHasher>(
+    code: &[u8],
+    defines: &HashMap<String, String, S>,
+) -> Option<Vec<u8>> {
+    let mut defines: HashMap<&str, &str> = defines
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    let mut new_code = code.to_vec();
+    let mut stack: Vec<Conditional> = Vec::new();
+    let mut active = true;
+    let mut blanked = false;
+    let mut start = 0;
+
+    for line in code.split_inclusive(|c| *c == b'\n') {
+        let end = start + line.len();
+        let text = std::str::from_utf8(line).unwrap_or_default().trim();
+        let directive = text.strip_prefix('#').map(|directive| {
+            let directive = directive.trim_start();
+            let name_end = directive
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(directive.len());
+            (&directive[..name_end], directive[name_end..].trim())
+        });
+
+        match directive {
+            Some((kind @ ("if" | "ifdef" | "ifndef"), condition)) => {
+                let result = match kind {
+                    "ifdef" => Some(defines.contains_key(condition)),
+                    "ifndef" => Some(!defines.contains_key(condition)),
+                    _ => eval_condition(condition, &defines),
+                };
+                stack.push(Conditional {
+                    parent_active: active,
+                    taken: result == Some(true),
+                    unknown: result.is_none(),
+                });
+                active &= result.unwrap_or(true);
+            }
+            Some(("elif", condition)) => {
+                if let Some(group) = stack.last_mut() {
+                    if group.unknown {
+                        active = group.parent_active;
+                    } else if group.taken {
+                        active = false;
+                    } else {
+                        let result = eval_condition(condition, &defines);
+                        group.taken = result == Some(true);
+                        group.unknown = result.is_none();
+                        active = group.parent_active && result.unwrap_or(true);
+                    }
+                }
+            }
+            Some(("else", _)) => {
+                if let Some(group) = stack.last_mut() {
+                    active = group.parent_active && (group.unknown || !group.taken);
+                    group.taken = true;
+                }
+            }
+            Some(("endif", _)) => {
+                if let Some(group) = stack.pop() {
+                    active = group.parent_active;
+                }
+            }
+            Some(("define", definition)) if active => {
+                let mut parts = definition.splitn(2, char::is_whitespace);
+                let name = parts.next().unwrap_or_default();
+                let name = name.split('(').next().unwrap_or(name);
+                defines.insert(name, parts.next().unwrap_or_default().trim());
+            }
+            Some(("undef", name)) if active => {
+                defines.remove(name);
+            }
+            _ if !active => {
+                for c in &mut new_code[start..end] {
+                    if *c != b'\n' && *c != b'\r' {
+                        *c = b' ';
+                    }
+                }
+                blanked = true;
+            }
+            _ => {}
+        }
+        start = end;
+    }
+
+    blanked.then_some(new_code)
+}
+
+// Evaluates an `#if` condition, `None` if it is not supported
+fn eval_condition(condition: &str, defines: &HashMap<&str, &str>) -> Option<bool> {
+    let mut any = false;
+    for alternative in condition.split("||") {
+        let mut all = true;
+        for term in alternative.split("&&") {
+            all &= eval_term(term.trim(), defines)?;
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+fn eval_term(term: &str, defines: &HashMap<&str, &str>) -> Option<bool> {
+    if let Some(term) = term.strip_prefix('!') {
+        return eval_term(term.trim(), defines).map(|result| !result);
+    }
+    if let Some(name) = term.strip_prefix("defined") {
+        let name = name.trim();
+        let name = name
+            .strip_prefix('(')
+            .and_then(|name| name.strip_suffix(')'))
+            .unwrap_or(name)
+            .trim();
+        return is_identifier(name).then(|| defines.contains_key(name));
+    }
+    if let Ok(number) = term.parse::<i64>() {
+        return Some(number != 0);
+    }
+    if is_identifier(term) {
+        // An undefined macro evaluates to 0
+        return match defines.get(term) {
+            None => Some(false),
+            Some(value) => value.parse::<i64>().ok().map(|number| number != 0),
+        };
+    }
+    None
+}
+
+fn is_identifier(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.first().is_some_and(|c| is_identifier_starter(*c))
+        && bytes.iter().all(|c| is_identifier_part(*c))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +247,27 @@ mod tests {
             replace(b"abc def ghi z9_ jkl", &mac).unwrap()
         );
     }
+
+    #[test]
+    fn test_resolve_conditionals() {
+        let code = concat!(
+            "#ifdef FOO\nint a;\n#else\nint b;\n#endif\n",
+            "#if defined(BAR) && VERSION > 1\nint c;\n#endif\n"
+        );
+
+        let mut defines = HashMap::new();
+        defines.insert("FOO".to_string(), String::new());
+        assert_eq!(
+            resolve_conditionals(code.as_bytes(), &defines).unwrap(),
+            code.replace("int b;", "      ").into_bytes()
+        );
+
+        // The second group is left untouched since `>` is not supported
+        defines.clear();
+        defines.insert("BAR".to_string(), String::new());
+        assert_eq!(
+            resolve_conditionals(code.as_bytes(), &defines).unwrap(),
+            code.replace("int a;", "      ").into_bytes()
+        );
+    }
 }
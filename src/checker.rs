@@ -84,6 +84,40 @@ pub trait Checker {
     fn is_error(node: &Node) -> bool {
         node.has_error()
     }
+
+    /// Checks whether a node is test code, which is skipped along with its
+    /// descendants when [`MetricsOptions::exclude_tests`] is set.
+    ///
+    /// `test_file` tells whether the name of the analyzed file marks it as
+    /// a test file.
+    fn is_test(_node: &Node, _code: &[u8], _filter: &TestFilter, _test_file: bool) -> bool {
+        false
+    }
+}
+
+// Checks whether an attribute is one of the `Rust` test attributes
+fn is_rust_test_attribute(node: &Node, code: &[u8], filter: &TestFilter) -> bool {
+    node.kind() == "attribute_item"
+        && node.utf8_text(code).is_some_and(|text| {
+            let attribute: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+            attribute
+                .strip_prefix("#[")
+                .and_then(|attribute| attribute.strip_suffix(']'))
+                .is_some_and(|attribute| {
+                    filter.rust_attributes.iter().any(|test| *test == attribute)
+                })
+        })
+}
+
+// Checks whether a node is a call declaring `JavaScript` tests, such as
+// `describe(...)` or `it.only(...)`
+fn is_js_test(node: &Node, code: &[u8], filter: &TestFilter) -> bool {
+    node.kind() == "call_expression"
+        && node
+            .child_by_field_name("function")
+            .and_then(|function| function.utf8_text(code))
+            .and_then(|function| function.split(['.', '(']).next())
+            .is_some_and(|name| filter.js_functions.iter().any(|test| *test == name.trim()))
 }
 
 impl Checker for PreprocCode {
@@ -280,6 +314,19 @@ impl Checker for PythonCode {
     fn is_primitive(_id: u16) -> bool {
         false
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, test_file: bool) -> bool {
+        let prefixes = match node.kind() {
+            "function_definition" => filter.python_functions,
+            "class_definition" => filter.python_classes,
+            _ => return false,
+        };
+        test_file
+            && node
+                .child_by_field_name("name")
+                .and_then(|name| name.utf8_text(code))
+                .is_some_and(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+    }
 }
 
 impl Checker for JavaCode {
@@ -294,14 +341,14 @@ impl Checker for JavaCode {
     fn is_func_space(node: &Node) -> bool {
         matches!(
             node.kind(),
-            "program" | "class_declaration" | "interface_declaration"
+            "program" | "class_declaration" | "interface_declaration" | "record_declaration"
         )
     }
 
     fn is_func(node: &Node) -> bool {
         matches!(
             node.kind(),
-            "method_declaration" | "constructor_declaration"
+            "method_declaration" | "constructor_declaration" | "compact_constructor_declaration"
         )
     }
 
@@ -382,6 +429,10 @@ impl Checker for MozjsCode {
     fn is_primitive(_id: u16) -> bool {
         false
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, _test_file: bool) -> bool {
+        is_js_test(node, code, filter)
+    }
 }
 
 impl Checker for JavascriptCode {
@@ -436,6 +487,10 @@ impl Checker for JavascriptCode {
     fn is_primitive(_id: u16) -> bool {
         false
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, _test_file: bool) -> bool {
+        is_js_test(node, code, filter)
+    }
 }
 
 impl Checker for TypescriptCode {
@@ -492,6 +547,10 @@ impl Checker for TypescriptCode {
     fn is_primitive(id: u16) -> bool {
         id == Typescript::PredefinedType
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, _test_file: bool) -> bool {
+        is_js_test(node, code, filter)
+    }
 }
 
 impl Checker for TsxCode {
@@ -547,6 +606,10 @@ impl Checker for TsxCode {
     fn is_primitive(id: u16) -> bool {
         id == Tsx::PredefinedType
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, _test_file: bool) -> bool {
+        is_js_test(node, code, filter)
+    }
 }
 
 impl Checker for RustCode {
@@ -568,7 +631,12 @@ impl Checker for RustCode {
     fn is_func_space(node: &Node) -> bool {
         matches!(
             node.kind(),
-            "source_file" | "function_item" | "impl_item" | "trait_item" | "closure_expression"
+            "source_file"
+                | "function_item"
+                | "impl_item"
+                | "trait_item"
+                | "closure_expression"
+                | "macro_definition"
         )
     }
 
@@ -607,6 +675,26 @@ impl Checker for RustCode {
     fn is_primitive(id: u16) -> bool {
         id == Rust::PrimitiveType
     }
+
+    fn is_test(node: &Node, code: &[u8], filter: &TestFilter, _test_file: bool) -> bool {
+        if is_rust_test_attribute(node, code, filter) {
+            return true;
+        }
+        if node.kind() == "attribute_item" || Self::is_comment(node) {
+            return false;
+        }
+        // The attributes of an item are its previous siblings
+        let mut sibling = node.previous_sibling();
+        while let Some(previous) = sibling
+            && (previous.kind() == "attribute_item" || Self::is_comment(&previous))
+        {
+            if is_rust_test_attribute(&previous, code, filter) {
+                return true;
+            }
+            sibling = previous.previous_sibling();
+        }
+        false
+    }
 }
 
 // Kotlin implementation - based on tree-sitter-kotlin (currently disabled due to API differences)
@@ -650,8 +738,22 @@ impl Checker for KotlinCode {
         matches!(node.kind(), "string_literal" | "multiline_string_literal")
     }
 
-    fn is_else_if(_: &Node) -> bool {
-        false
+    fn is_else_if(node: &Node) -> bool {
+        if node.kind() != "if_expression" {
+            return false;
+        }
+        // The else branch may be wrapped in a `control_structure_body`
+        let mut branch = *node;
+        while let Some(parent) = branch.parent() {
+            if parent.kind() != "control_structure_body" {
+                break;
+            }
+            branch = parent;
+        }
+        branch.parent().is_some_and(|parent| parent.kind() == "if_expression")
+            && branch
+                .previous_sibling()
+                .is_some_and(|sibling| sibling.kind() == "else")
     }
 
     fn is_primitive(_id: u16) -> bool {
@@ -967,7 +1069,7 @@ impl Checker for CsharpCode {
     fn is_func(node: &Node) -> bool {
         matches!(
             node.kind(),
-            "method_declaration" | "constructor_declaration"
+            "method_declaration" | "constructor_declaration" | "local_function_statement"
         )
     }
 
@@ -1001,3 +1103,182 @@ impl Checker for CsharpCode {
         false
     }
 }
+
+/// Returns `true` when an `R` function definition is the value side of an
+/// assignment such as `f <- function(x) x`, `f = function(x) x` or
+/// `function(x) x -> f`.
+fn is_r_assigned_function(node: &Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if parent.kind() != "binary_operator" {
+        return false;
+    }
+    let Some(operator) = parent.child_by_field_name("operator") else {
+        return false;
+    };
+    let value_field = match operator.kind() {
+        "<-" | "<<-" | "=" | ":=" => "rhs",
+        "->" | "->>" => "lhs",
+        _ => return false,
+    };
+    parent
+        .child_by_field_name(value_field)
+        .is_some_and(|bound| bound.id() == node.id())
+}
+
+// R implementation - based on tree-sitter-r 1.1.0
+impl Checker for RCode {
+    fn is_comment(node: &Node) -> bool {
+        node.kind() == "comment"
+    }
+
+    fn is_useful_comment(_: &Node, _: &[u8]) -> bool {
+        false
+    }
+
+    fn is_func_space(node: &Node) -> bool {
+        matches!(node.kind(), "program" | "function_definition")
+    }
+
+    fn is_func(node: &Node) -> bool {
+        node.kind() == "function_definition" && is_r_assigned_function(node)
+    }
+
+    fn is_closure(node: &Node) -> bool {
+        node.kind() == "function_definition" && !is_r_assigned_function(node)
+    }
+
+    fn is_call(node: &Node) -> bool {
+        node.kind() == "call"
+    }
+
+    fn is_non_arg(_: &Node) -> bool {
+        false
+    }
+
+    fn is_string(node: &Node) -> bool {
+        node.kind() == "string"
+    }
+
+    fn is_else_if(node: &Node) -> bool {
+        if node.kind() != "if_statement" {
+            return false;
+        }
+        node.parent().is_some_and(|parent| {
+            parent.kind() == "if_statement"
+                && parent
+                    .child_by_field_name("alternative")
+                    .is_some_and(|alternative| alternative.id() == node.id())
+        })
+    }
+
+    fn is_primitive(_id: u16) -> bool {
+        false
+    }
+}
+
+// Perl implementation - based on tree-sitter-perl 1.1.0
+impl Checker for PerlCode {
+    fn is_comment(node: &Node) -> bool {
+        matches!(node.kind(), "comment" | "pod")
+    }
+
+    fn is_useful_comment(node: &Node, code: &[u8]) -> bool {
+        // The shebang line selects the interpreter and its flags
+        node.start_byte() == 0 && code.starts_with(b"#!")
+    }
+
+    fn is_func_space(node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "source_file"
+                | "subroutine_declaration_statement"
+                | "method_declaration_statement"
+                | "anonymous_subroutine_expression"
+        )
+    }
+
+    fn is_func(node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "subroutine_declaration_statement" | "method_declaration_statement"
+        )
+    }
+
+    fn is_closure(node: &Node) -> bool {
+        node.kind() == "anonymous_subroutine_expression"
+    }
+
+    fn is_call(node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "function_call_expression"
+                | "ambiguous_function_call_expression"
+                | "method_call_expression"
+        )
+    }
+
+    fn is_non_arg(_: &Node) -> bool {
+        false
+    }
+
+    fn is_string(node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "string_literal" | "interpolated_string_literal" | "command_string"
+        )
+    }
+
+    fn is_else_if(node: &Node) -> bool {
+        node.kind() == "elsif" && node.is_named()
+    }
+
+    fn is_primitive(_id: u16) -> bool {
+        false
+    }
+}
+
+// GraphQL implementation - based on tree-sitter-graphql 0.1.0
+impl Checker for GraphqlCode {
+    fn is_comment(node: &Node) -> bool {
+        node.kind() == "comment"
+    }
+
+    fn is_useful_comment(_: &Node, _: &[u8]) -> bool {
+        false
+    }
+
+    // A schema has no functions, so the document is its only space
+    fn is_func_space(node: &Node) -> bool {
+        node.kind() == "source_file"
+    }
+
+    fn is_func(_: &Node) -> bool {
+        false
+    }
+
+    fn is_closure(_: &Node) -> bool {
+        false
+    }
+
+    fn is_call(_: &Node) -> bool {
+        false
+    }
+
+    fn is_non_arg(_: &Node) -> bool {
+        false
+    }
+
+    fn is_string(node: &Node) -> bool {
+        node.kind() == "string_value"
+    }
+
+    fn is_else_if(_: &Node) -> bool {
+        false
+    }
+
+    fn is_primitive(_id: u16) -> bool {
+        false
+    }
+}
@@ -1,10 +1,26 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::analysis_context::{enter_deadline, timed_out};
+use crate::anonymize::anonymize;
+use crate::cognitive::CognitiveConfig;
+use crate::custom::CustomMetricFactory;
+use crate::cyclomatic::CyclomaticConfig;
+use crate::node::{Node, Tree};
+use crate::notebook::{is_notebook, notebook_spaces, NotebookError, NotebookOptions};
 use crate::parser_registry::ParserRegistry;
+use crate::plain_text::{plain_text_space_with_syntax, CommentSyntax};
 use crate::preproc::PreprocResults;
-use crate::{get_function_spaces, spaces::FuncSpace, LANG};
+use crate::tools::{detect_eol, normalize_eol, EolStyle, FileFlags};
+use crate::spaces::{AverageSpaces, FuncSpace, MetricsOptions, SpaceKind, TestFilter};
+use crate::{
+    function_by_name, functions_by_name, get_function_spaces_with_custom, with_float_precision,
+    LANG,
+};
 
 /// Error returned by the [`SingularityCodeAnalyzer`].
 #[derive(Debug)]
@@ -13,8 +29,24 @@ pub enum AnalyzerError {
     UnsupportedLanguage(String),
     /// The underlying metrics pipeline failed to produce data.
     AnalysisFailed { language: LANG, reason: String },
+    /// No function with the requested name exists in the analyzed source.
+    FunctionNotFound(String),
     /// I/O error while reading the source under analysis.
     Io(std::io::Error),
+    /// The analysis took longer than [`AnalyzeOptions::per_file_timeout`].
+    Timeout(Duration),
+    /// A tree given by the caller was not parsed with the grammar of the
+    /// requested language.
+    LanguageMismatch(LANG),
+    /// The source contains syntax errors and [`AnalyzeOptions::error_policy`]
+    /// is [`ErrorPolicy::Fail`].
+    ParseError {
+        language: LANG,
+        errors: Vec<ErrorSpan>,
+    },
+    /// The analysis panicked, see
+    /// [`SingularityCodeAnalyzer::analyze_untrusted`].
+    Panicked { language: LANG, message: String },
 }
 
 impl fmt::Display for AnalyzerError {
@@ -29,7 +61,26 @@ impl fmt::Display for AnalyzerError {
             AnalyzerError::AnalysisFailed { language, reason } => {
                 write!(f, "failed to compute metrics for {language:?}: {reason}")
             }
+            AnalyzerError::FunctionNotFound(name) => {
+                write!(f, "no function named `{name}` was found")
+            }
             AnalyzerError::Io(err) => write!(f, "failed to read source: {err}"),
+            AnalyzerError::Timeout(timeout) => {
+                write!(f, "analysis exceeded the timeout of {timeout:?}")
+            }
+            AnalyzerError::LanguageMismatch(language) => {
+                write!(f, "the tree was not parsed with the {language:?} grammar")
+            }
+            AnalyzerError::ParseError { language, errors } => {
+                write!(f, "found {} syntax errors in the {language:?} code", errors.len())?;
+                if let Some(first) = errors.first() {
+                    write!(f, ", the first one at line {}", first.start_line)?;
+                }
+                Ok(())
+            }
+            AnalyzerError::Panicked { language, message } => {
+                write!(f, "the analysis of the {language:?} code panicked: {message}")
+            }
         }
     }
 }
@@ -52,10 +103,63 @@ impl From<std::io::Error> for AnalyzerError {
 /// Result of a language analysis request.
 #[derive(Debug, Clone)]
 pub struct AnalyzerResult {
-    /// Language that was analyzed.
-    pub language: LANG,
+    /// Language that was analyzed, `None` when no language matches the
+    /// path of a file analyzed with [`AnalyzeOptions::loc_fallback`].
+    pub language: Option<LANG>,
     /// Root function space containing nested spaces and metrics.
     pub root_space: FuncSpace,
+    /// Line ending style detected in the analyzed source.
+    pub eol: EolStyle,
+    /// File-level flags, e.g. [`FileFlags::MixedLineEndings`].
+    pub flags: Vec<FileFlags>,
+    /// The syntax errors of the source, only collected when
+    /// [`AnalyzeOptions::error_policy`] is [`ErrorPolicy::Annotate`].
+    pub syntax_errors: Option<SyntaxErrors>,
+    /// The number of decimals of the floats serialized by
+    /// [`to_json`](Self::to_json), see [`AnalyzeOptions::float_precision`].
+    pub float_precision: Option<u8>,
+}
+
+/// How [`SingularityCodeAnalyzer::analyze_language`] handles a source
+/// containing syntax errors.
+///
+/// The parser always recovers from syntax errors, so the metrics are
+/// computed on the parseable portions of the source, but they may be
+/// skewed around the errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// The errors are not looked for.
+    #[default]
+    Ignore,
+    /// The errors are listed in [`AnalyzerResult::syntax_errors`].
+    Annotate,
+    /// The analysis fails with [`AnalyzerError::ParseError`].
+    Fail,
+}
+
+/// The span of a syntax error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorSpan {
+    /// The first line of the error
+    pub start_line: usize,
+    /// The last line of the error
+    pub end_line: usize,
+    /// The byte offset where the error starts
+    pub start_byte: usize,
+    /// The byte offset where the error ends
+    pub end_byte: usize,
+    /// Whether the parser inserted a missing token, e.g. a closing
+    /// parenthesis, instead of skipping unexpected code
+    pub missing: bool,
+}
+
+/// The syntax errors found in a source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyntaxErrors {
+    /// The number of errors
+    pub error_count: usize,
+    /// The spans of the errors, in source order
+    pub spans: Vec<ErrorSpan>,
 }
 
 impl AnalyzerResult {
@@ -64,15 +168,171 @@ impl AnalyzerResult {
     pub fn metrics(&self) -> &crate::spaces::CodeMetrics {
         &self.root_space.metrics
     }
+
+    /// Serializes the root space to `JSON`, with its floats rounded to the
+    /// [`float_precision`](Self::float_precision).
+    ///
+    /// # Errors
+    /// Returns the error of [`serde_json::to_value`] when the space cannot
+    /// be serialized.
+    pub fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        with_float_precision(self.float_precision, || {
+            serde_json::to_value(&self.root_space)
+        })
+    }
 }
 
 /// Options for running the analyzer over in-memory content.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct AnalyzeOptions<'a> {
     /// Optional virtual path to associate with the content.
     pub virtual_path: Option<&'a Path>,
     /// Optional preprocessing results (macros, includes, ...).
     pub preprocessor: Option<Arc<PreprocResults>>,
+    /// Whether closures and lambdas count as functions (default `true`).
+    ///
+    /// When `false`, anonymous functions don't get their own spaces: their
+    /// complexity rolls up to the enclosing function and averages only
+    /// divide by the number of named functions.
+    pub count_anonymous: bool,
+    /// Whether the arguments of well-known `Rust` macros count toward the
+    /// `Cognitive Complexity` (default `false`).
+    ///
+    /// See [`MetricsOptions::rust_macro_bodies`].
+    pub rust_macro_bodies: bool,
+    /// Configuration of the `Cyclomatic` metric.
+    pub cyclomatic: CyclomaticConfig,
+    /// Configuration of the `Cognitive Complexity` metric.
+    pub cognitive: CognitiveConfig,
+    /// Whether top-level imports are left out of `SLOC`, `PLOC` and `LLOC`
+    /// (default `false`).
+    ///
+    /// See [`MetricsOptions::exclude_imports`].
+    pub exclude_imports: bool,
+    /// The increment of the `Cognitive Complexity` for the `Go` error checks
+    /// (default `None`, counting them as any other `if`).
+    ///
+    /// See [`MetricsOptions::go_error_check_increment`].
+    pub go_error_check_increment: Option<usize>,
+    /// The increment of the `Cognitive Complexity` for each `Rust` `?`
+    /// operator (default `0`, ignoring them).
+    ///
+    /// See [`MetricsOptions::rust_try_increment`].
+    pub rust_try_increment: usize,
+    /// The heuristics recognizing the test code left out of the metrics
+    /// (default `None`, keeping the tests).
+    ///
+    /// See [`MetricsOptions::exclude_tests`].
+    pub exclude_tests: Option<TestFilter>,
+    /// Whether each space gets the hash of its source (default `false`).
+    ///
+    /// See [`MetricsOptions::content_hash`].
+    pub content_hash: bool,
+    /// The spaces over which the averages of the metrics are computed
+    /// (default [`AverageSpaces::PerMetric`]).
+    ///
+    /// See [`MetricsOptions::averages`].
+    pub averages: AverageSpaces,
+    /// Whether the content of a language without a parser still gets its
+    /// line metrics (default `false`).
+    ///
+    /// When `true`, such a content is not rejected with
+    /// [`AnalyzerError::UnsupportedLanguage`]: its `SLOC`, `PLOC`, `CLOC`
+    /// and `BLANK` metrics are computed by [`plain_text_space`](crate::plain_text_space).
+    pub loc_fallback: bool,
+    /// The comment delimiters recognized by the
+    /// [`loc_fallback`](Self::loc_fallback), besides the `#`, `//` and
+    /// `/* */` ones, keyed by file extension without the leading dot, e.g.
+    /// `asm`.
+    ///
+    /// The extension of the [`virtual_path`](Self::virtual_path) is used
+    /// when given, else the name of the language.
+    pub comment_syntaxes: BTreeMap<String, CommentSyntax>,
+    /// Maximum time allowed to analyze the content.
+    ///
+    /// Parsing is cancelled once it expires, and the analysis then fails
+    /// with [`AnalyzerError::Timeout`].
+    pub per_file_timeout: Option<Duration>,
+    /// The depth at which spaces are reported (default
+    /// [`Granularity::Function`]).
+    pub granularity: Granularity,
+    /// Whether the names of the reported spaces are replaced with stable
+    /// hashes (default `false`).
+    ///
+    /// See [`anonymize`](crate::anonymize()).
+    pub anonymize: bool,
+    /// The number of decimals of the floats serialized by
+    /// [`AnalyzerResult::to_json`] (default `None`, keeping all of them).
+    ///
+    /// See [`with_float_precision`].
+    pub float_precision: Option<u8>,
+    /// How syntax errors in the content are handled (default
+    /// [`ErrorPolicy::Ignore`]).
+    ///
+    /// Looking for the errors parses the content a second time.
+    pub error_policy: ErrorPolicy,
+    /// Metrics defined outside of this crate, computed during the same
+    /// traversal as the built-in ones.
+    ///
+    /// Each factory creates a new instance of its metric for the analysis,
+    /// whose result is stored in [`FuncSpace::custom`] of the root space.
+    pub custom_metrics: Vec<CustomMetricFactory>,
+}
+
+impl Default for AnalyzeOptions<'_> {
+    fn default() -> Self {
+        Self {
+            virtual_path: None,
+            preprocessor: None,
+            count_anonymous: true,
+            rust_macro_bodies: false,
+            cyclomatic: CyclomaticConfig::default(),
+            cognitive: CognitiveConfig::default(),
+            exclude_imports: false,
+            go_error_check_increment: None,
+            rust_try_increment: 0,
+            exclude_tests: None,
+            content_hash: false,
+            averages: AverageSpaces::default(),
+            loc_fallback: false,
+            comment_syntaxes: BTreeMap::new(),
+            per_file_timeout: None,
+            granularity: Granularity::default(),
+            anonymize: false,
+            float_precision: None,
+            error_policy: ErrorPolicy::default(),
+            custom_metrics: Vec::new(),
+        }
+    }
+}
+
+/// The depth at which [`SingularityCodeAnalyzer::analyze_language`]
+/// reports spaces.
+///
+/// The metrics of a space always include the ones of its subspaces, so
+/// the spaces which are not reported still count in their parents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    /// Only the root space of the file.
+    File,
+    /// The classes, structs, interfaces, traits and impls, without their
+    /// methods. Namespaces are kept to reach their classes, while free
+    /// functions are not reported.
+    Class,
+    /// Every space, methods and nested functions included.
+    #[default]
+    Function,
+}
+
+/// Which functions [`SingularityCodeAnalyzer::analyze_function`] returns
+/// when several functions share the requested name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FunctionMatch {
+    /// Only the first function in source order.
+    #[default]
+    First,
+    /// Every function with the requested name, e.g. overloads.
+    All,
 }
 
 /// High-level façade for running Singularity's multi-language metrics engine.
@@ -134,16 +394,26 @@ impl SingularityCodeAnalyzer {
     }
 
     /// Detect the language for the given file path using the registry's extension table.
+    ///
+    /// A Jupyter notebook is detected as `Python`, the default language of its
+    /// kernel, since the path alone does not tell the language of its cells.
     #[must_use]
     pub fn detect_language_from_path(&self, path: &Path) -> Option<LANG> {
+        if is_notebook(path) {
+            return Some(LANG::Python);
+        }
         self.registry.detect_language_from_path(path)
     }
 
     /// Analyze the provided source buffer for the specified language.
     ///
     /// # Errors
-    /// Returns [`AnalyzerError::UnsupportedLanguage`] when the language is not registered,
-    /// or [`AnalyzerError::AnalysisFailed`] when metrics could not be produced.
+    /// Returns [`AnalyzerError::UnsupportedLanguage`] when the language is not registered
+    /// and [`AnalyzeOptions::loc_fallback`] is not set,
+    /// [`AnalyzerError::AnalysisFailed`] when metrics could not be produced,
+    /// [`AnalyzerError::Timeout`] when the analysis exceeds the per-file timeout, or
+    /// [`AnalyzerError::ParseError`] when the source contains syntax errors and the
+    /// error policy is [`ErrorPolicy::Fail`].
     pub fn analyze_language(
         &self,
         language: LANG,
@@ -151,6 +421,9 @@ impl SingularityCodeAnalyzer {
         options: AnalyzeOptions<'_>,
     ) -> Result<AnalyzerResult, AnalyzerError> {
         if self.registry.get_factory(&language).is_none() {
+            if options.loc_fallback {
+                return Ok(plain_text_result(Some(language), source.as_ref(), &options));
+            }
             return Err(AnalyzerError::UnsupportedLanguage(
                 language.get_name().to_string(),
             ));
@@ -161,32 +434,325 @@ impl SingularityCodeAnalyzer {
             PathBuf::from,
         );
 
-        let buffer = source.as_ref().to_vec();
-        let root_space = get_function_spaces(&language, buffer, &path_buf, options.preprocessor)
-            .ok_or_else(|| AnalyzerError::AnalysisFailed {
-                language,
-                reason: "metric pipeline returned no data".to_string(),
-            })?;
+        let mut buffer = source.as_ref().to_vec();
+        let eol = detect_eol(&buffer);
+        // Lines are counted on `\n`, so lone `\r` endings must not merge lines
+        normalize_eol(&mut buffer);
 
-        Ok(AnalyzerResult {
+        let mut flags = Vec::new();
+        if eol == EolStyle::Mixed {
+            flags.push(FileFlags::MixedLineEndings);
+        }
+
+        let deadline = options
+            .per_file_timeout
+            .map(|timeout| Instant::now() + timeout);
+        let _deadline_guard = enter_deadline(deadline);
+
+        let syntax_errors = match options.error_policy {
+            ErrorPolicy::Ignore => None,
+            ErrorPolicy::Annotate | ErrorPolicy::Fail => {
+                let tree = Tree::with_lang(&buffer, &language).ok_or_else(|| {
+                    AnalyzerError::AnalysisFailed {
+                        language,
+                        reason: "the grammar cannot be loaded".to_string(),
+                    }
+                })?;
+                let mut spans = Vec::new();
+                collect_errors(&tree.get_root(), &mut spans);
+                if options.error_policy == ErrorPolicy::Fail && !spans.is_empty() {
+                    return Err(AnalyzerError::ParseError {
+                        language,
+                        errors: spans,
+                    });
+                }
+                Some(SyntaxErrors {
+                    error_count: spans.len(),
+                    spans,
+                })
+            }
+        };
+
+        let metrics_options = MetricsOptions {
+            count_anonymous: options.count_anonymous,
+            rust_macro_bodies: options.rust_macro_bodies,
+            cyclomatic: options.cyclomatic,
+            cognitive: options.cognitive,
+            exclude_imports: options.exclude_imports,
+            go_error_check_increment: options.go_error_check_increment,
+            rust_try_increment: options.rust_try_increment,
+            exclude_tests: options.exclude_tests,
+            content_hash: options.content_hash,
+            averages: options.averages,
+        };
+        let mut root_space = get_function_spaces_with_custom(
+            &language,
+            buffer,
+            &path_buf,
+            options.preprocessor,
+            &metrics_options,
+            options
+                .custom_metrics
+                .iter()
+                .map(CustomMetricFactory::create)
+                .collect(),
+        )
+        .ok_or_else(|| AnalyzerError::AnalysisFailed {
             language,
+            reason: "metric pipeline returned no data".to_string(),
+        })?;
+
+        if timed_out() || deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            let timeout = options.per_file_timeout.unwrap_or_default();
+            return Err(AnalyzerError::Timeout(timeout));
+        }
+
+        prune_spaces(&mut root_space, options.granularity);
+        if options.anonymize {
+            anonymize(&mut root_space);
+        }
+
+        Ok(AnalyzerResult {
+            language: Some(language),
             root_space,
+            eol,
+            flags,
+            syntax_errors,
+            float_precision: options.float_precision,
         })
     }
 
+    /// Analyze a source buffer which may be malformed or adversarial, e.g.
+    /// untrusted input or fuzzed data, for the specified language.
+    ///
+    /// It behaves as [`analyze_language`](Self::analyze_language), except
+    /// that a panic raised during the analysis is caught and returned as an
+    /// error instead of unwinding into the caller. The panic message is
+    /// still printed by the panic hook of the process.
+    ///
+    /// # Errors
+    /// Returns [`AnalyzerError::Panicked`] when the analysis panics, or
+    /// whatever error [`analyze_language`](Self::analyze_language) returns.
+    pub fn analyze_untrusted(
+        &self,
+        language: LANG,
+        source: impl AsRef<[u8]>,
+        options: AnalyzeOptions<'_>,
+    ) -> Result<AnalyzerResult, AnalyzerError> {
+        let source = source.as_ref();
+        // The thread-local settings are restored by their guards on unwind
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            self.analyze_language(language, source, options)
+        }))
+        .unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(AnalyzerError::Panicked { language, message })
+        })
+    }
+
+    /// Analyze the provided source buffer and return only the function spaces
+    /// named `name`, searching nested functions as well.
+    ///
+    /// # Errors
+    /// Returns [`AnalyzerError::FunctionNotFound`] when no function matches,
+    /// or whatever error [`analyze_language`](Self::analyze_language) returns.
+    pub fn analyze_function(
+        &self,
+        language: LANG,
+        source: impl AsRef<[u8]>,
+        name: &str,
+        matches: FunctionMatch,
+    ) -> Result<Vec<FuncSpace>, AnalyzerError> {
+        let result = self.analyze_language(language, source, AnalyzeOptions::default())?;
+
+        let found: Vec<FuncSpace> = match matches {
+            FunctionMatch::First => function_by_name(&result.root_space, name)
+                .into_iter()
+                .cloned()
+                .collect(),
+            FunctionMatch::All => functions_by_name(&result.root_space, name)
+                .into_iter()
+                .cloned()
+                .collect(),
+        };
+
+        if found.is_empty() {
+            return Err(AnalyzerError::FunctionNotFound(name.to_string()));
+        }
+        Ok(found)
+    }
+
     /// Analyze a file on disk. The language is detected from the file extension if possible.
     ///
     /// # Errors
-    /// Returns an [`AnalyzerError::Io`] if the file cannot be read, an
+    /// Returns an [`AnalyzerError::Io`] if the file cannot be read or is a
+    /// notebook which is not valid `JSON`, an
     /// [`AnalyzerError::UnsupportedLanguage`] if no language matches the path, or
     /// whatever error [`analyze_language`](Self::analyze_language) returns when the
     /// metric pipeline fails.
     pub fn analyze_file(&self, path: &Path) -> Result<AnalyzerResult, AnalyzerError> {
+        self.analyze_file_with_options(path, AnalyzeOptions::default())
+    }
+
+    /// Analyze a file on disk with the given options. The language is detected
+    /// from the file extension if possible.
+    ///
+    /// When no language matches the path and [`AnalyzeOptions::loc_fallback`] is
+    /// set, only the line metrics of the file are computed.
+    ///
+    /// A Jupyter notebook is analyzed as with
+    /// [`get_notebook_spaces`](crate::get_notebook_spaces), in the language of
+    /// its kernel.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`analyze_file`](Self::analyze_file).
+    pub fn analyze_file_with_options(
+        &self,
+        path: &Path,
+        options: AnalyzeOptions<'_>,
+    ) -> Result<AnalyzerResult, AnalyzerError> {
         let contents = std::fs::read(path)?;
-        let language = self
-            .detect_language_from_path(path)
-            .ok_or_else(|| AnalyzerError::UnsupportedLanguage(path.display().to_string()))?;
+        if is_notebook(path) {
+            return notebook_result(path, &contents, &options);
+        }
+        let Some(language) = self.detect_language_from_path(path) else {
+            if options.loc_fallback {
+                let options = AnalyzeOptions {
+                    virtual_path: options.virtual_path.or(Some(path)),
+                    ..options
+                };
+                return Ok(plain_text_result(None, &contents, &options));
+            }
+            return Err(AnalyzerError::UnsupportedLanguage(path.display().to_string()));
+        };
+
+        self.analyze_language(language, contents, options)
+    }
+}
+
+// Analyzes the code cells of a notebook
+fn notebook_result(
+    path: &Path,
+    source: &[u8],
+    options: &AnalyzeOptions<'_>,
+) -> Result<AnalyzerResult, AnalyzerError> {
+    let path = options.virtual_path.unwrap_or(path);
+    let (language, mut root_space) = notebook_spaces(source, path, &NotebookOptions::default())
+        .map_err(|err| match err {
+            NotebookError::Json(err) => {
+                AnalyzerError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            }
+            NotebookError::UnsupportedLanguage(lang) => AnalyzerError::UnsupportedLanguage(lang),
+        })?;
+    prune_spaces(&mut root_space, options.granularity);
+    if options.anonymize {
+        anonymize(&mut root_space);
+    }
+
+    let eol = detect_eol(source);
+    let mut flags = Vec::new();
+    if eol == EolStyle::Mixed {
+        flags.push(FileFlags::MixedLineEndings);
+    }
+
+    Ok(AnalyzerResult {
+        language: Some(language),
+        root_space,
+        eol,
+        flags,
+        syntax_errors: None,
+        float_precision: options.float_precision,
+    })
+}
+
+// Computes the line metrics of a content which cannot be parsed
+fn plain_text_result(
+    language: Option<LANG>,
+    source: &[u8],
+    options: &AnalyzeOptions<'_>,
+) -> AnalyzerResult {
+    let extension = language.map_or("txt", |language| language.get_name());
+    let path_buf = options.virtual_path.map_or_else(
+        || PathBuf::from(format!("memory.{extension}")),
+        PathBuf::from,
+    );
+
+    let mut buffer = source.to_vec();
+    let eol = detect_eol(&buffer);
+    normalize_eol(&mut buffer);
 
-        self.analyze_language(language, contents, AnalyzeOptions::default())
+    let mut flags = Vec::new();
+    if eol == EolStyle::Mixed {
+        flags.push(FileFlags::MixedLineEndings);
+    }
+
+    let extension = path_buf.extension().and_then(|extension| extension.to_str());
+    let default_syntax = CommentSyntax::default();
+    let syntax = options
+        .comment_syntaxes
+        .iter()
+        .find(|(key, _)| extension.is_some_and(|extension| extension.eq_ignore_ascii_case(key)))
+        .map_or(&default_syntax, |(_, syntax)| syntax);
+    let mut root_space = plain_text_space_with_syntax(&buffer, &path_buf, syntax);
+    if options.anonymize {
+        anonymize(&mut root_space);
+    }
+
+    AnalyzerResult {
+        language,
+        root_space,
+        eol,
+        flags,
+        syntax_errors: None,
+        float_precision: options.float_precision,
+    }
+}
+
+// Lists the outermost error and missing nodes of the tree rooted at `node`
+fn collect_errors(node: &Node, spans: &mut Vec<ErrorSpan>) {
+    if node.is_error() || node.is_missing() {
+        spans.push(ErrorSpan {
+            start_line: node.start_row() + 1,
+            end_line: node.end_row() + 1,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            missing: node.is_missing(),
+        });
+    } else if node.has_error() {
+        for child in node.children() {
+            collect_errors(&child, spans);
+        }
+    }
+}
+
+// Drops the subspaces finer than `granularity`
+fn prune_spaces(space: &mut FuncSpace, granularity: Granularity) {
+    match granularity {
+        Granularity::File => space.spaces.clear(),
+        Granularity::Class => {
+            space.spaces.retain(|child| {
+                matches!(
+                    child.kind,
+                    SpaceKind::Class
+                        | SpaceKind::Struct
+                        | SpaceKind::Interface
+                        | SpaceKind::Trait
+                        | SpaceKind::Impl
+                        | SpaceKind::Namespace
+                )
+            });
+            for child in &mut space.spaces {
+                if child.kind == SpaceKind::Namespace {
+                    prune_spaces(child, granularity);
+                } else {
+                    child.spaces.clear();
+                }
+            }
+        }
+        Granularity::Function => {}
     }
 }
@@ -1,14 +1,20 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
     thread,
+    time::{Duration, Instant},
 };
 
 use crossbeam::channel::{unbounded, Receiver, Sender};
 use globset::GlobSet;
 use walkdir::{DirEntry, WalkDir};
 
+use crate::analysis_context::{enter_deadline, enter_float_precision, timed_out};
+use crate::code_analyzer::{AnalyzerError, SingularityCodeAnalyzer};
+use crate::spaces::FuncSpace;
+use crate::summary::{RankMetric, TopFunctions};
+
 type ProcFilesFunction<Config> = dyn Fn(PathBuf, &Config) -> std::io::Result<()> + Send + Sync;
 
 type ProcDirPathsFunction<Config> =
@@ -20,47 +26,133 @@ type ProcPathFunction<Config> = dyn Fn(&Path, &Config) + Send + Sync;
 fn null_proc_dir_paths<Config>(_: &mut HashMap<String, Vec<PathBuf>>, _: &Path, _: &Config) {}
 fn null_proc_path<Config>(_: &Path, _: &Config) {}
 
+// A job is a single file, or a batch of small files
 #[derive(Debug)]
 struct JobItem<Config> {
-    path: PathBuf,
+    paths: Vec<PathBuf>,
     cfg: Arc<Config>,
 }
 
 type JobReceiver<Config> = Receiver<Option<JobItem<Config>>>;
 type JobSender<Config> = Sender<Option<JobItem<Config>>>;
 
+// The files whose analysis exceeded the per-file timeout
+type TimedOutFiles = Arc<Mutex<Vec<PathBuf>>>;
+
 #[allow(clippy::needless_pass_by_value)]
-fn consumer<Config, ProcFiles>(receiver: JobReceiver<Config>, func: Arc<ProcFiles>)
-where
-    ProcFiles: Fn(PathBuf, &Config) -> std::io::Result<()> + Send + Sync,
+fn consumer<Config, ProcFiles>(
+    receiver: JobReceiver<Config>,
+    func: Arc<ProcFiles>,
+    timeout: Option<Duration>,
+    float_precision: Option<u8>,
+    timed_out_files: TimedOutFiles,
+) where
+    ProcFiles: Fn(PathBuf, &Config) -> std::io::Result<()> + Send + Sync + ?Sized,
 {
+    // The metrics serialized by the files processed on this worker are
+    // rounded to the float precision of the runner
+    let _precision_guard = enter_float_precision(float_precision);
     while let Ok(job) = receiver.recv() {
         if job.is_none() {
             break;
         }
         // Cannot panic because of the check immediately above.
         let job = job.expect("TODO: Add context for why this shouldn't fail");
-        let path = job.path.clone();
-
-        if let Err(err) = func(job.path, &job.cfg) {
-            eprintln!("{err:?} for file {}", path.display());
+        for path in job.paths {
+            process_file(&*func, path, &job.cfg, timeout, &timed_out_files);
         }
     }
 }
 
-fn send_file<T>(
+// Processes a single file, reporting its failure and recording its timeout
+fn process_file<Config, ProcFiles>(
+    func: &ProcFiles,
     path: PathBuf,
+    cfg: &Config,
+    timeout: Option<Duration>,
+    timed_out_files: &Mutex<Vec<PathBuf>>,
+) where
+    ProcFiles: Fn(PathBuf, &Config) -> std::io::Result<()> + ?Sized,
+{
+    let display_path = path.clone();
+
+    // Parsing is cancelled once the deadline expires, so a slow file
+    // cannot block this worker
+    let _deadline_guard = enter_deadline(timeout.map(|timeout| Instant::now() + timeout));
+    let result = func(path, cfg);
+
+    if timed_out() {
+        if let Ok(mut timed_out_files) = timed_out_files.lock() {
+            timed_out_files.push(display_path);
+        }
+    } else if let Err(err) = result {
+        eprintln!("{err:?} for file {}", display_path.display());
+    }
+}
+
+fn send_files<T>(
+    paths: Vec<PathBuf>,
     cfg: &Arc<T>,
     sender: &JobSender<T>,
 ) -> Result<(), ConcurrentErrors> {
     sender
         .send(Some(JobItem {
-            path,
+            paths,
             cfg: Arc::clone(cfg),
         }))
         .map_err(|e| ConcurrentErrors::Sender(e.to_string()))
 }
 
+// The maximum number of files of a batch, so a batch of empty files
+// cannot hold back the other workers
+const MAX_BATCH_FILES: usize = 64;
+
+// Hands the files found by the producer over to the consumers. The files
+// smaller than the batch threshold are grouped into a single job, sent
+// once their total size reaches the threshold
+struct Dispatcher<'a, Config> {
+    sender: &'a JobSender<Config>,
+    batch_threshold: Option<u64>,
+    batch: Vec<PathBuf>,
+    batch_bytes: u64,
+}
+
+impl<'a, Config> Dispatcher<'a, Config> {
+    fn new(sender: &'a JobSender<Config>, batch_threshold: Option<u64>) -> Self {
+        Self {
+            sender,
+            batch_threshold,
+            batch: Vec::new(),
+            batch_bytes: 0,
+        }
+    }
+
+    fn dispatch(&mut self, path: PathBuf, cfg: &Arc<Config>) -> Result<(), ConcurrentErrors> {
+        let Some(threshold) = self.batch_threshold else {
+            return send_files(vec![path], cfg, self.sender);
+        };
+        let size = path.metadata().map_or(u64::MAX, |metadata| metadata.len());
+        if size >= threshold {
+            return send_files(vec![path], cfg, self.sender);
+        }
+        self.batch.push(path);
+        self.batch_bytes += size;
+        if self.batch_bytes >= threshold || self.batch.len() >= MAX_BATCH_FILES {
+            self.flush(cfg)?;
+        }
+        Ok(())
+    }
+
+    // Sends the files left in the batch
+    fn flush(&mut self, cfg: &Arc<Config>) -> Result<(), ConcurrentErrors> {
+        self.batch_bytes = 0;
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        send_files(std::mem::take(&mut self.batch), cfg, self.sender)
+    }
+}
+
 fn is_hidden(entry: &DirEntry) -> bool {
     entry
         .file_name()
@@ -68,12 +160,22 @@ fn is_hidden(entry: &DirEntry) -> bool {
         .is_some_and(|s| s.starts_with('.'))
 }
 
+// Paths are compared in their canonical form, so an allowlist entry
+// matches a walked file however both of them are spelled
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn is_allowed(path: &Path, only_files: Option<&HashSet<PathBuf>>) -> bool {
+    only_files.is_none_or(|only_files| only_files.contains(&canonical_path(path)))
+}
+
 fn explore<Config, ProcDirPaths, ProcPath>(
     files_data: FilesData,
     cfg: &Arc<Config>,
     proc_dir_paths: ProcDirPaths,
     proc_path: ProcPath,
-    sender: &JobSender<Config>,
+    dispatcher: &mut Dispatcher<Config>,
 ) -> Result<HashMap<String, Vec<PathBuf>>, ConcurrentErrors>
 where
     ProcDirPaths: Fn(&mut HashMap<String, Vec<PathBuf>>, &Path, &Config) + Send + Sync,
@@ -83,8 +185,12 @@ where
         mut paths,
         ref include,
         ref exclude,
+        only_files,
     } = files_data;
 
+    let only_files: Option<HashSet<PathBuf>> =
+        only_files.map(|files| files.iter().map(PathBuf::as_path).map(canonical_path).collect());
+
     let mut all_files: HashMap<String, Vec<PathBuf>> = HashMap::new();
 
     for path in std::mem::take(&mut paths) {
@@ -105,19 +211,22 @@ where
                 if (include.is_empty() || include.is_match(&path))
                     && (exclude.is_empty() || !exclude.is_match(&path))
                     && path.is_file()
+                    && is_allowed(&path, only_files.as_ref())
                 {
                     proc_dir_paths(&mut all_files, &path, cfg);
-                    send_file(path, cfg, sender)?;
+                    dispatcher.dispatch(path, cfg)?;
                 }
             }
         } else if (include.is_empty() || include.is_match(&path))
             && (exclude.is_empty() || !exclude.is_match(&path))
             && path.is_file()
+            && is_allowed(&path, only_files.as_ref())
         {
             proc_path(&path, cfg);
-            send_file(path, cfg, sender)?;
+            dispatcher.dispatch(path, cfg)?;
         }
     }
+    dispatcher.flush(cfg)?;
 
     Ok(all_files)
 }
@@ -141,6 +250,11 @@ pub enum ConcurrentErrors {
     ///
     /// A general error occurred when a thread is being spawned or run.
     Thread(String),
+    /// Timeout error.
+    ///
+    /// The analysis of these files exceeded the per-file timeout, so their
+    /// results are missing. The other files were processed.
+    Timeout(Vec<PathBuf>),
 }
 
 /// Data related to files.
@@ -152,6 +266,11 @@ pub struct FilesData {
     pub exclude: GlobSet,
     /// List of file paths.
     pub paths: Vec<PathBuf>,
+    /// Explicit allowlist of files, e.g. the files changed in a pull request.
+    ///
+    /// When set, only the files found in `paths` which are also in this
+    /// list are processed, even if other files match `include`.
+    pub only_files: Option<HashSet<PathBuf>>,
 }
 
 /// A runner to process files concurrently.
@@ -160,6 +279,9 @@ pub struct ConcurrentRunner<Config> {
     proc_dir_paths: Box<ProcDirPathsFunction<Config>>,
     proc_path: Box<ProcPathFunction<Config>>,
     num_jobs: usize,
+    per_file_timeout: Option<Duration>,
+    float_precision: Option<u8>,
+    batch_threshold: Option<u64>,
 }
 
 impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
@@ -177,6 +299,9 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
             proc_dir_paths: Box::new(null_proc_dir_paths),
             proc_path: Box::new(null_proc_path),
             num_jobs,
+            per_file_timeout: None,
+            float_precision: None,
+            batch_threshold: None,
         }
     }
 
@@ -202,13 +327,51 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         self
     }
 
+    /// Sets the maximum time allowed to parse a single file.
+    ///
+    /// Files exceeding it are reported as timed out instead of
+    /// keeping a worker busy: once all the files are processed,
+    /// [`ConcurrentRunner::run`] fails with [`ConcurrentErrors::Timeout`]
+    /// listing them.
+    #[must_use]
+    pub fn set_per_file_timeout(mut self, timeout: Duration) -> Self {
+        self.per_file_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the number of decimals of the floats of the metrics serialized
+    /// while processing the files.
+    ///
+    /// The function processing the files runs as inside
+    /// [`with_float_precision`](crate::with_float_precision), so the metrics
+    /// it writes out are rounded to `precision` decimals.
+    #[must_use]
+    pub fn set_float_precision(mut self, precision: u8) -> Self {
+        self.float_precision = Some(precision);
+        self
+    }
+
+    /// Sets the size in bytes under which files are sent to the workers
+    /// in batches rather than one at a time.
+    ///
+    /// The small files are grouped until their total size reaches `bytes`,
+    /// so the cost of sending a file to a worker is shared by the files of
+    /// a batch. This speeds up the runs over many small files, such as the
+    /// modules of a large repository.
+    #[must_use]
+    pub fn set_batch_threshold(mut self, bytes: u64) -> Self {
+        self.batch_threshold = Some(bytes);
+        self
+    }
+
     /// Runs the producer-consumer approach to process the files
     /// contained in a directory and in its own subdirectories.
     ///
     /// # Errors
     ///
     /// Returns [`ConcurrentErrors`] when any thread fails or file traversal
-    /// encounters unrecoverable issues.
+    /// encounters unrecoverable issues, and [`ConcurrentErrors::Timeout`]
+    /// when the analysis of some files exceeded the per-file timeout.
     ///
     /// * `config` - Information used to process a file.
     /// * `files_data` - Information about the files to be included or excluded from a search more the number of paths considered in the search.
@@ -220,19 +383,25 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         let cfg = Arc::new(config);
 
         let (sender, receiver) = unbounded();
+        let proc_files = Arc::new(self.proc_files);
+        let per_file_timeout = self.per_file_timeout;
+        let float_precision = self.float_precision;
+        let timed_out_files: TimedOutFiles = Arc::default();
 
         let producer = {
             let sender = sender.clone();
+            let batch_threshold = self.batch_threshold;
 
             match thread::Builder::new()
                 .name(String::from("Producer"))
                 .spawn(move || {
+                    let mut dispatcher = Dispatcher::new(&sender, batch_threshold);
                     explore(
                         files_data,
                         &cfg,
                         self.proc_dir_paths,
                         self.proc_path,
-                        &sender,
+                        &mut dispatcher,
                     )
                 }) {
                 Ok(producer) => producer,
@@ -241,15 +410,21 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         };
 
         let mut receivers = Vec::with_capacity(self.num_jobs);
-        let proc_files = Arc::new(self.proc_files);
         for i in 0..self.num_jobs {
             let receiver = receiver.clone();
             let proc_files = proc_files.clone();
+            let timed_out_files = Arc::clone(&timed_out_files);
 
             let t = match thread::Builder::new()
                 .name(format!("Consumer {i}"))
                 .spawn(move || {
-                    consumer(receiver, proc_files);
+                    consumer(
+                        receiver,
+                        proc_files,
+                        per_file_timeout,
+                        float_precision,
+                        timed_out_files,
+                    );
                 }) {
                 Ok(receiver) => receiver,
                 Err(e) => return Err(ConcurrentErrors::Thread(e.to_string())),
@@ -279,6 +454,439 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
             }
         }
 
-        all_files
+        let all_files = all_files?;
+        let mut timed_out_files = timed_out_files
+            .lock()
+            .map(|mut files| std::mem::take(&mut *files))
+            .unwrap_or_default();
+        if !timed_out_files.is_empty() {
+            timed_out_files.sort();
+            return Err(ConcurrentErrors::Timeout(timed_out_files));
+        }
+
+        Ok(all_files)
+    }
+}
+
+/// The results of [`run_collect`], keyed by file path.
+#[derive(Debug, Default)]
+pub struct BatchResults {
+    /// The function space of each analyzed file, or the error which
+    /// prevented its analysis.
+    pub files: HashMap<PathBuf, Result<FuncSpace, AnalyzerError>>,
+}
+
+impl BatchResults {
+    /// Returns the number of files which were analyzed.
+    #[must_use]
+    pub fn succeeded(&self) -> usize {
+        self.files.values().filter(|result| result.is_ok()).count()
+    }
+
+    /// Returns the number of files which could not be analyzed.
+    #[must_use]
+    pub fn failed(&self) -> usize {
+        self.files.len() - self.succeeded()
+    }
+}
+
+/// Analyzes the files described by `files_data` concurrently, collecting
+/// the result of each file.
+///
+/// Unlike [`ConcurrentRunner::run`], a file which cannot be analyzed does
+/// not stop the run: its error is recorded in the returned [`BatchResults`]
+/// and the other files are still analyzed.
+///
+/// # Errors
+///
+/// Returns [`ConcurrentErrors`] when any thread fails or file traversal
+/// encounters unrecoverable issues.
+pub fn run_collect(
+    num_jobs: usize,
+    files_data: FilesData,
+) -> Result<BatchResults, ConcurrentErrors> {
+    let files = Arc::new(Mutex::new(HashMap::new()));
+    let runner = {
+        let files = Arc::clone(&files);
+        ConcurrentRunner::new(num_jobs, move |path: PathBuf, analyzer: &SingularityCodeAnalyzer| {
+            let result = analyzer.analyze_file(&path).map(|result| {
+                let mut space = result.root_space;
+                space.name = path.to_str().map(ToString::to_string);
+                space
+            });
+            if let Ok(mut files) = files.lock() {
+                files.insert(path, result);
+            }
+            Ok(())
+        })
+    };
+    runner.run(SingularityCodeAnalyzer::new(), files_data)?;
+
+    let files = Arc::try_unwrap(files)
+        .ok()
+        .and_then(|files| files.into_inner().ok())
+        .ok_or_else(|| {
+            ConcurrentErrors::Receiver("A thread used to process a file panicked".to_owned())
+        })?;
+    Ok(BatchResults { files })
+}
+
+/// Analyzes the files described by `files_data` concurrently, keeping only
+/// the `top` worst functions for `metric` and the worst function of each
+/// file.
+///
+/// The spaces of each file are dropped once ranked, so unlike
+/// [`run_collect`] the memory used does not grow with the size of the
+/// analyzed code. The files which cannot be analyzed are skipped.
+///
+/// # Errors
+///
+/// Returns [`ConcurrentErrors`] when any thread fails or file traversal
+/// encounters unrecoverable issues.
+pub fn run_top_functions(
+    num_jobs: usize,
+    files_data: FilesData,
+    metric: RankMetric,
+    top: usize,
+) -> Result<TopFunctions, ConcurrentErrors> {
+    let ranking = Arc::new(Mutex::new(TopFunctions::new(metric, top)));
+    let runner = {
+        let ranking = Arc::clone(&ranking);
+        ConcurrentRunner::new(num_jobs, move |path: PathBuf, analyzer: &SingularityCodeAnalyzer| {
+            if let Ok(result) = analyzer.analyze_file(&path)
+                && let Ok(mut ranking) = ranking.lock()
+            {
+                ranking.add(&path, &result.root_space);
+            }
+            Ok(())
+        })
+    };
+    runner.run(SingularityCodeAnalyzer::new(), files_data)?;
+
+    Arc::try_unwrap(ranking)
+        .ok()
+        .and_then(|ranking| ranking.into_inner().ok())
+        .map(TopFunctions::finish)
+        .ok_or_else(|| {
+            ConcurrentErrors::Receiver("A thread used to process a file panicked".to_owned())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Mutex};
+
+    use super::*;
+
+    #[test]
+    fn only_files_allowlist() {
+        let dir = std::env::temp_dir().join("concurrent_files_only_files");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(dir.join(name), "fn f() {}\n").unwrap();
+        }
+
+        let processed = Arc::new(Mutex::new(Vec::new()));
+        let runner = {
+            let processed = Arc::clone(&processed);
+            ConcurrentRunner::new(2, move |path: PathBuf, (): &()| {
+                processed.lock().unwrap().push(path);
+                Ok(())
+            })
+        };
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.clone()],
+            only_files: Some(HashSet::from([dir.join("b.rs")])),
+        };
+        runner.run((), files_data).unwrap();
+
+        let processed = processed.lock().unwrap();
+        assert_eq!(*processed, [dir.join("b.rs")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn timed_out_files_are_reported() {
+        let dir = std::env::temp_dir().join("concurrent_files_timeout");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["fast.rs", "slow.rs"] {
+            fs::write(dir.join(name), "fn f() {}\n").unwrap();
+        }
+        // Empty, so sent in a batch
+        fs::write(dir.join("tiny.rs"), "").unwrap();
+
+        // The parses of `slow.rs` and `tiny.rs` are cancelled by their
+        // deadline
+        let runner = ConcurrentRunner::new(2, |path: PathBuf, (): &()| {
+            if path.ends_with("slow.rs") || path.ends_with("tiny.rs") {
+                crate::analysis_context::mark_timed_out();
+            }
+            Ok(())
+        })
+        .set_per_file_timeout(Duration::from_secs(1))
+        .set_batch_threshold(1);
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.clone()],
+            only_files: None,
+        };
+
+        let Err(ConcurrentErrors::Timeout(timed_out)) = runner.run((), files_data) else {
+            panic!("the timed out files are not reported");
+        };
+        assert_eq!(timed_out, [dir.join("slow.rs"), dir.join("tiny.rs")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn float_precision_of_the_runner() {
+        let dir = std::env::temp_dir().join("concurrent_files_float_precision");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.py"), "a = b + c\n").unwrap();
+
+        let volumes = Arc::new(Mutex::new(Vec::new()));
+        let runner = {
+            let volumes = Arc::clone(&volumes);
+            ConcurrentRunner::new(2, move |path: PathBuf, analyzer: &SingularityCodeAnalyzer| {
+                let space = analyzer.analyze_file(&path).unwrap().root_space;
+                let json = serde_json::to_value(&space).unwrap();
+                let volume = json["metrics"]["halstead"]["volume"].as_f64().unwrap();
+                volumes.lock().unwrap().push((volume, space.metrics.halstead.volume()));
+                Ok(())
+            })
+            .set_float_precision(1)
+        };
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.clone()],
+            only_files: None,
+        };
+        runner.run(SingularityCodeAnalyzer::new(), files_data).unwrap();
+
+        let volumes = volumes.lock().unwrap();
+        let [(volume, full)] = volumes[..] else {
+            panic!("the file is not processed");
+        };
+        assert_ne!(volume, full);
+        assert_eq!(volume, (full * 10.).round() / 10.);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn small_files_are_batched() {
+        let dir = std::env::temp_dir().join("concurrent_files_batches");
+        fs::create_dir_all(&dir).unwrap();
+        for i in 0..25 {
+            fs::write(dir.join(format!("f{i:02}.rs")), "fn f() {}\n").unwrap();
+        }
+        fs::write(dir.join("large.rs"), "fn f() {}\n".repeat(10)).unwrap();
+
+        // Batches of 10 files of 10 bytes, the last one holding the
+        // remaining 5 files, and the large file on its own
+        let (sender, receiver) = unbounded();
+        let mut dispatcher = Dispatcher::new(&sender, Some(100));
+        let cfg = Arc::new(());
+        let mut paths: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        for path in paths {
+            dispatcher.dispatch(path, &cfg).unwrap();
+        }
+        dispatcher.flush(&cfg).unwrap();
+        drop(sender);
+
+        let mut jobs: Vec<_> = receiver
+            .iter()
+            .map(|job| job.unwrap().paths.len())
+            .collect();
+        jobs.sort_unstable();
+        assert_eq!(jobs, [1, 5, 10, 10]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Runs the analysis of many tiny files, one file or one batch of files
+    // per job, returning the cyclomatic complexity of each file and the
+    // time taken
+    fn analyze_tiny_files(
+        dir: &Path,
+        batch_threshold: Option<u64>,
+    ) -> (Vec<(PathBuf, f64)>, Duration) {
+        type Runner = ConcurrentRunner<SingularityCodeAnalyzer>;
+        let processed = Arc::new(Mutex::new(Vec::new()));
+        let collector = Arc::clone(&processed);
+        let mut runner = Runner::new(
+            4,
+            move |path: PathBuf, analyzer: &SingularityCodeAnalyzer| {
+                let result = analyzer
+                    .analyze_file(&path)
+                    .map_err(std::io::Error::other)?;
+                collector
+                    .lock()
+                    .unwrap()
+                    .push((path, result.metrics().cyclomatic.cyclomatic_sum()));
+                Ok(())
+            },
+        );
+        if let Some(bytes) = batch_threshold {
+            runner = runner.set_batch_threshold(bytes);
+        }
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.to_path_buf()],
+            only_files: None,
+        };
+        let start = Instant::now();
+        runner
+            .run(SingularityCodeAnalyzer::new(), files_data)
+            .unwrap();
+        let elapsed = start.elapsed();
+        let mut processed = std::mem::take(&mut *processed.lock().unwrap());
+        processed.sort_by(|a, b| a.0.cmp(&b.0));
+        (processed, elapsed)
+    }
+
+    fn write_tiny_files(dir: &Path, count: usize) {
+        fs::create_dir_all(dir).unwrap();
+        for i in 0..count {
+            fs::write(
+                dir.join(format!("f{i}.py")),
+                format!("def f{i}(x):\n    return x\n"),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn tiny_files_batch_parity() {
+        let dir = std::env::temp_dir().join("concurrent_files_batch_parity");
+        write_tiny_files(&dir, 500);
+
+        let (threaded, _) = analyze_tiny_files(&dir, None);
+        let (batched, _) = analyze_tiny_files(&dir, Some(1024));
+
+        assert_eq!(batched.len(), 500);
+        assert_eq!(batched, threaded);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --ignored --nocapture in release mode"]
+    fn tiny_files_batch_throughput() {
+        let dir = std::env::temp_dir().join("concurrent_files_batch_throughput");
+        write_tiny_files(&dir, 5000);
+
+        // Warms up the file system cache
+        analyze_tiny_files(&dir, None);
+        let (_, threaded) = analyze_tiny_files(&dir, None);
+        let (_, batched) = analyze_tiny_files(&dir, Some(4096));
+
+        let throughput = |elapsed: Duration| 5000. / elapsed.as_secs_f64();
+        println!(
+            "one file per job: {:.0} files/s, batches: {:.0} files/s",
+            throughput(threaded),
+            throughput(batched)
+        );
+        assert!(batched <= threaded);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn run_collect_keeps_going_after_failures() {
+        let dir = std::env::temp_dir().join("concurrent_files_run_collect");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.py"), "def f(x):\n    if x:\n        return 1\n").unwrap();
+        fs::write(dir.join("b.rs"), "fn g() {}\n").unwrap();
+        // No parser exists for this file
+        fs::write(dir.join("broken.xyz"), [0xff, 0xfe, 0x00, 0x42]).unwrap();
+        // Truncated code with invalid UTF-8, parsed with error nodes
+        fs::write(dir.join("truncated.py"), b"def h(x):\n    if x \xff\xfe(").unwrap();
+        // Truncated notebook, which is not valid JSON
+        fs::write(dir.join("truncated.ipynb"), r#"{"cells": [{"cell_type": "co"#).unwrap();
+
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.clone()],
+            only_files: None,
+        };
+        let results = run_collect(2, files_data).unwrap();
+
+        assert_eq!((results.succeeded(), results.failed()), (3, 2));
+        assert!(matches!(
+            results.files[&dir.join("broken.xyz")],
+            Err(AnalyzerError::UnsupportedLanguage(_))
+        ));
+        assert!(matches!(
+            results.files[&dir.join("truncated.ipynb")],
+            Err(AnalyzerError::Io(_))
+        ));
+        assert!(results.files[&dir.join("truncated.py")].is_ok());
+        let space = results.files[&dir.join("a.py")].as_ref().unwrap();
+        assert_eq!(space.metrics.cyclomatic.cyclomatic_sum(), 3.);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn run_top_functions_ranks_functions_and_files() {
+        let dir = std::env::temp_dir().join("concurrent_files_run_top_functions");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a.py"),
+            "def one(x):\n    return x\n\ndef three(x):\n    if x > 0:\n        return 1\n    \
+             elif x < 0:\n        return -1\n    return 0\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.py"),
+            "def two(x):\n    if x:\n        return 1\n    return 0\n\ndef four(x):\n    \
+             if x and x > 1:\n        return 2\n    elif x:\n        return 1\n    return 0\n",
+        )
+        .unwrap();
+        fs::write(dir.join("c.py"), "x = 1\n").unwrap();
+
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![dir.clone()],
+            only_files: None,
+        };
+        let ranking = run_top_functions(2, files_data, RankMetric::Cyclomatic, 3).unwrap();
+
+        let functions: Vec<_> = ranking
+            .functions
+            .iter()
+            .map(|function| (function.name.as_deref(), function.value))
+            .collect();
+        assert_eq!(
+            functions,
+            [(Some("four"), 4.), (Some("three"), 3.), (Some("two"), 2.)]
+        );
+
+        let files: Vec<_> = ranking
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.functions))
+            .collect();
+        assert_eq!(
+            files,
+            [(dir.join("b.py"), 2), (dir.join("a.py"), 2), (dir.join("c.py"), 0)]
+        );
+        assert!(ranking.files[2].worst.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
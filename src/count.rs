@@ -2,12 +2,17 @@
 
 use std::{
     fmt,
+    path::Path,
     sync::{Arc, Mutex},
 };
 
 use num_format::{Locale, ToFormattedString};
 
-use crate::traits::{Callback, ParserTrait};
+use crate::{
+    langs::{action, LANG},
+    loc::count_logical_lines,
+    traits::{Callback, ParserTrait},
+};
 
 #[inline]
 fn usize_to_f64(value: usize) -> f64 {
@@ -47,6 +52,49 @@ pub fn count<T: ParserTrait>(parser: &T, filters: &[String]) -> (usize, usize) {
     (good, total)
 }
 
+/// Counts the logical lines of a code, the same value as the `LLOC` metric
+/// of its root space.
+///
+/// A logical line is an executable statement, whatever the number of
+/// physical lines it spans:
+///
+/// - in the C family, i.e. `C/C++`, `Java`, `C#`, `JavaScript` and
+///   `TypeScript`, and in `Rust`, each statement terminated by a `;` and
+///   each control flow statement, e.g. `a = 1; b = 2;` counts 2;
+/// - in `Python`, each simple or compound statement, so `a = 1` and
+///   `b = 2` on two lines count 2;
+/// - in `Go`, each statement of a block, so `a := 1; b := 2` counts 2;
+/// - in `Kotlin`, `Lua`, `Elixir`, `Erlang`, `Gleam`, `R` and `Perl`, each
+///   line containing code, so `a = 1; b = 2` on a single line counts 1.
+///   Their statements are not told apart from the expressions they hold.
+///
+/// Function and type definitions are not logical lines.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{lloc, LANG};
+///
+/// assert_eq!(lloc(b"void f() { a = 1; b = 2; }", LANG::Cpp), 2);
+/// ```
+#[must_use]
+pub fn lloc(code: &[u8], lang: LANG) -> usize {
+    action::<LlocCode>(&lang, code.to_vec(), Path::new(""), None, ())
+}
+
+struct LlocCode {
+    _guard: (),
+}
+
+impl Callback for LlocCode {
+    type Res = usize;
+    type Cfg = ();
+
+    fn call<T: ParserTrait>(_cfg: Self::Cfg, parser: &T) -> Self::Res {
+        count_logical_lines::<T::Loc>(&parser.get_root())
+    }
+}
+
 /// Configuration options for counting different
 /// types of nodes in a code.
 #[derive(Debug)]
@@ -0,0 +1,106 @@
+//! Structural metrics of `CSS` stylesheets.
+//!
+//! Stylesheets are parsed with the `tree-sitter-css` grammar. `CSS` is not
+//! a language of [`LANG`](crate::LANG), since its rules hold no code to
+//! compute the function metrics on.
+
+use serde::Serialize;
+use tree_sitter::Parser;
+
+use crate::node::{Node, Tree};
+
+/// The structural metrics of a `CSS` stylesheet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CssStats {
+    /// The number of `@media` and `@supports` at-rules, each one making
+    /// the rules of its block conditional
+    pub css_conditions: usize,
+    /// The deepest nesting of rules, `1` for the rules which are not nested
+    /// in another rule. The conditional at-rules do not nest their rules
+    pub css_max_nesting: usize,
+}
+
+/// Computes the structural metrics of a `CSS` stylesheet.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::css_stats;
+///
+/// let stats = css_stats(b"@media print { .card { & p { color: black; } } }");
+/// assert_eq!(stats.css_conditions, 1);
+/// assert_eq!(stats.css_max_nesting, 2);
+/// ```
+#[must_use]
+pub fn css_stats(source: &[u8]) -> CssStats {
+    let mut parser = Parser::new();
+    let mut stats = CssStats::default();
+    if parser
+        .set_language(&tree_sitter_css::LANGUAGE.into())
+        .is_err()
+    {
+        return stats;
+    }
+    if let Some(tree) = parser.parse(source, None) {
+        let tree = Tree::from_ts_tree(tree);
+        collect_stats(&tree.get_root(), 0, &mut stats);
+    }
+    stats
+}
+
+fn collect_stats(node: &Node, depth: usize, stats: &mut CssStats) {
+    let depth = match node.kind() {
+        "media_statement" | "supports_statement" => {
+            stats.css_conditions += 1;
+            depth
+        }
+        "rule_set" => {
+            stats.css_max_nesting = stats.css_max_nesting.max(depth + 1);
+            depth + 1
+        }
+        _ => depth,
+    };
+    for child in node.children() {
+        collect_stats(&child, depth, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_blocks_and_nested_rule() {
+        let source = "/* @media print { a { b { } } } */
+.card {
+  color: black;
+  &:hover {
+    color: red;
+  }
+}
+
+@media (max-width: 600px) {
+  .card { padding: 0; }
+}
+
+@media print {
+  .card { display: none; }
+}
+";
+        let stats = css_stats(source.as_bytes());
+        assert_eq!(stats.css_conditions, 2);
+        assert_eq!(stats.css_max_nesting, 2);
+    }
+
+    #[test]
+    fn supports_and_flat_rules() {
+        let source = "@supports (display: grid) {
+  .grid { display: grid; }
+}
+p { margin: 0; }
+";
+        let stats = css_stats(source.as_bytes());
+        assert_eq!(stats.css_conditions, 1);
+        assert_eq!(stats.css_max_nesting, 1);
+    }
+}
@@ -1,11 +1,74 @@
-use std::path::PathBuf;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
 
 use crate::{
+    checker::Checker,
     dump::dump_node,
+    getter::Getter,
+    halstead::HalsteadType,
+    langs::{action, LANG},
     node::Node,
+    spaces::{FuncSpace, SpaceKind},
+    tools::fnv1a,
     traits::{Callback, ParserTrait},
 };
 
+/// A function belonging to a [`CloneGroup`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CloneInstance {
+    /// The path of the file containing the function
+    pub path: PathBuf,
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+}
+
+/// Functions sharing the same normalized token sequence.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CloneGroup {
+    /// The hash of the normalized token sequence, the same across runs
+    /// and platforms
+    pub hash: u64,
+    /// The number of tokens of each function
+    pub tokens: usize,
+    /// The functions of the group, in the order of the input files
+    pub functions: Vec<CloneInstance>,
+}
+
+// A token of a function body, without the names of its identifiers
+enum CloneToken<'a> {
+    Operator(u16),
+    Identifier,
+    Literal(&'a [u8]),
+}
+
+impl CloneToken<'_> {
+    // Appends the bytes of the token, prefixed by its variant, and the
+    // length of a literal, so that two sequences of tokens never have the
+    // same bytes
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Self::Operator(kind_id) => {
+                bytes.push(0);
+                bytes.extend_from_slice(&kind_id.to_le_bytes());
+            }
+            Self::Identifier => bytes.push(1),
+            Self::Literal(literal) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&(literal.len() as u64).to_le_bytes());
+                bytes.extend_from_slice(literal);
+            }
+        }
+    }
+}
+
 /// Finds the types of nodes specified in the input slice.
 pub fn find<'a, T: ParserTrait>(parser: &'a T, filters: &[String]) -> Option<Vec<Node<'a>>> {
     let filters = parser.get_filters(filters);
@@ -37,6 +100,428 @@ pub fn find<'a, T: ParserTrait>(parser: &'a T, filters: &[String]) -> Option<Vec
     Some(good)
 }
 
+/// Finds the first function space named `name` inside `space`.
+///
+/// Spaces are visited in source order and nested functions are searched
+/// too, so an inner function is found even when it is not a direct child.
+#[must_use]
+pub fn function_by_name<'a>(space: &'a FuncSpace, name: &str) -> Option<&'a FuncSpace> {
+    let mut stack = vec![space];
+
+    while let Some(space) = stack.pop() {
+        if is_named_function(space, name) {
+            return Some(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    None
+}
+
+/// Finds all the function spaces named `name` inside `space`.
+///
+/// Overloaded or redefined functions sharing the same name are all
+/// returned, in source order.
+#[must_use]
+pub fn functions_by_name<'a>(space: &'a FuncSpace, name: &str) -> Vec<&'a FuncSpace> {
+    let mut stack = vec![space];
+    let mut found = Vec::new();
+
+    while let Some(space) = stack.pop() {
+        if is_named_function(space, name) {
+            found.push(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    found
+}
+
+/// A metric of a single function, as compared by [`functions_over`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FunctionMetric {
+    /// The `Cyclomatic Complexity` of the function
+    Cyclomatic,
+    /// The `Cognitive Complexity` of the function
+    Cognitive,
+    /// The number of source lines of the function
+    Sloc,
+    /// The number of arguments of the function
+    Nargs,
+    /// The number of exit points of the function
+    Nexits,
+}
+
+impl FunctionMetric {
+    /// Returns the value of the metric for the function `space`.
+    ///
+    /// The complexities, the arguments and the exit points are the ones of
+    /// the function alone, without its nested functions, while the `SLOC`
+    /// spans the lines of its nested functions too.
+    #[must_use]
+    pub fn value(self, space: &FuncSpace) -> f64 {
+        let metrics = &space.metrics;
+        match self {
+            Self::Cyclomatic => metrics.cyclomatic.cyclomatic(),
+            Self::Cognitive => metrics.cognitive.cognitive(),
+            Self::Sloc => metrics.loc.sloc(),
+            Self::Nargs => metrics.nargs.fn_args(),
+            Self::Nexits => metrics.nexits.exit(),
+        }
+    }
+}
+
+/// Finds the function spaces inside `space` whose `metric` is strictly
+/// greater than `limit`.
+///
+/// Nested functions are checked too, and functions are returned in
+/// source order.
+#[must_use]
+pub fn functions_over(space: &FuncSpace, metric: FunctionMetric, limit: f64) -> Vec<&FuncSpace> {
+    let mut stack = vec![space];
+    let mut found = Vec::new();
+
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function && metric.value(space) > limit {
+            found.push(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    found
+}
+
+/// Finds the function spaces inside `space` longer than `threshold`
+/// physical lines, as given by [`FuncSpace::length`].
+///
+/// Nested functions are checked too. Functions are returned from the
+/// longest to the shortest, and those of the same length in source order.
+#[must_use]
+pub fn long_functions(space: &FuncSpace, threshold: usize) -> Vec<&FuncSpace> {
+    let mut stack = vec![space];
+    let mut found = Vec::new();
+
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function && space.length() > threshold {
+            found.push(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    found.sort_by_key(|space| std::cmp::Reverse(space.length()));
+    found
+}
+
+/// Finds the functions whose bodies are clones of each other.
+///
+/// Each function of `files`, given as a path, a code and its language,
+/// is reduced to the sequence of its operators and operands, classified
+/// as the `Halstead` metric does. Identifiers are replaced by a
+/// placeholder, so functions which only differ by their names and the
+/// names of their variables are clones, while literals are kept.
+///
+/// Only the functions with at least `min_tokens` tokens are considered,
+/// and only the groups with two or more functions are returned, in the
+/// order of their first function.
+#[must_use]
+pub fn clones(files: &[(&Path, &[u8], LANG)], min_tokens: usize) -> Vec<CloneGroup> {
+    let mut groups: Vec<CloneGroup> = Vec::new();
+    let mut group_by_hash = HashMap::new();
+
+    for (path, code, lang) in files {
+        let functions = action::<CloneTokensCode>(lang, code.to_vec(), path, None, ());
+        for (function, hash, tokens) in functions {
+            if tokens < min_tokens {
+                continue;
+            }
+            let function = CloneInstance {
+                path: path.to_path_buf(),
+                ..function
+            };
+            match group_by_hash.entry(hash) {
+                Entry::Occupied(entry) => groups[*entry.get()].functions.push(function),
+                Entry::Vacant(entry) => {
+                    entry.insert(groups.len());
+                    groups.push(CloneGroup {
+                        hash,
+                        tokens,
+                        functions: vec![function],
+                    });
+                }
+            }
+        }
+    }
+
+    groups.retain(|group| group.functions.len() > 1);
+    groups
+}
+
+struct CloneTokensCode {
+    _guard: (),
+}
+
+impl Callback for CloneTokensCode {
+    type Res = Vec<(CloneInstance, u64, usize)>;
+    type Cfg = ();
+
+    fn call<T: ParserTrait>(_cfg: Self::Cfg, parser: &T) -> Self::Res {
+        let code = parser.get_code();
+        let mut functions = Vec::new();
+        let mut stack = vec![parser.get_root()];
+        while let Some(node) = stack.pop() {
+            if T::Checker::is_func(&node) {
+                let (hash, tokens) = hash_tokens::<T>(&node, code);
+                functions.push((
+                    CloneInstance {
+                        path: PathBuf::new(),
+                        name: T::Getter::get_func_space_name(&node, code).map(ToString::to_string),
+                        start_line: node.start_row() + 1,
+                        end_line: node.end_row() + 1,
+                    },
+                    hash,
+                    tokens,
+                ));
+            }
+            let children: Vec<_> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+        }
+        functions
+    }
+}
+
+// Hashes the normalized tokens of a node in pre-order, returning the hash
+// with the number of tokens
+fn hash_tokens<T: ParserTrait>(node: &Node, code: &[u8]) -> (u64, usize) {
+    let mut bytes = Vec::new();
+    let mut tokens = 0;
+    let mut stack = vec![*node];
+    while let Some(node) = stack.pop() {
+        let token = match T::Getter::get_op_type(&node) {
+            HalsteadType::Operator => Some(CloneToken::Operator(node.kind_id())),
+            HalsteadType::Operand if node.kind().contains("identifier") => {
+                Some(CloneToken::Identifier)
+            }
+            HalsteadType::Operand => Some(CloneToken::Literal(
+                &code[node.start_byte()..node.end_byte()],
+            )),
+            HalsteadType::Unknown => None,
+        };
+        if let Some(token) = token {
+            token.encode(&mut bytes);
+            tokens += 1;
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev());
+    }
+    (fnv1a(&bytes), tokens)
+}
+
+/// Finds the innermost function space of `space` containing the byte at
+/// `byte_offset` in `code`.
+///
+/// `space` must be the root space computed from `code`. Since spaces
+/// are delimited by lines, the first function containing the line of
+/// the offset is returned when several functions share that line.
+#[must_use]
+pub fn enclosing_function<'a>(
+    space: &'a FuncSpace,
+    code: &[u8],
+    byte_offset: usize,
+) -> Option<&'a FuncSpace> {
+    let before = code.get(..byte_offset)?;
+    let line = before.iter().filter(|c| **c == b'\n').count() + 1;
+
+    let contains_line = |space: &&FuncSpace| (space.start_line..=space.end_line).contains(&line);
+
+    let mut enclosing = None;
+    let mut spaces = space.spaces.iter();
+    while let Some(space) = spaces.find(contains_line) {
+        if space.kind == SpaceKind::Function {
+            enclosing = Some(space);
+        }
+        spaces = space.spaces.iter();
+    }
+    enclosing
+}
+
+/// Finds the functions and the classes inside `space` without a doc
+/// comment.
+///
+/// `space` must be the root space computed from `code`, written in `lang`.
+/// A doc comment is a `///` or `/** */` comment right before the item in
+/// `Rust`, a docstring in `Python`, a `/** */` comment right before the
+/// item in the `JavaScript` family and in `Java`, and any comment right
+/// before the item in the other languages. Attributes may stand between an
+/// item and its comment.
+///
+/// When `public_only` is `true`, only the public items are returned: the
+/// ones declared `pub` in `Rust` or `public` in `Java`, the exported ones
+/// in the `JavaScript` family, and the ones whose name does not start with
+/// an underscore in `Python`, dunder methods excepted. The items of the
+/// other languages are all public.
+///
+/// Anonymous functions are never returned, and items are returned in
+/// source order.
+#[must_use]
+pub fn undocumented<'a>(
+    space: &'a FuncSpace,
+    code: &[u8],
+    lang: LANG,
+    public_only: bool,
+) -> Vec<&'a FuncSpace> {
+    let items = action::<UndocumentedCode>(&lang, code.to_vec(), Path::new(""), None, public_only);
+
+    let mut stack = vec![space];
+    let mut found = Vec::new();
+    while let Some(space) = stack.pop() {
+        // An item is matched on its whole span and its name, so a closure
+        // sharing the first line of an undocumented item is not returned
+        let is_undocumented = space
+            .name
+            .as_ref()
+            .is_some_and(|name| items.contains(&(space.start_line, space.end_line, name.clone())));
+        if is_documentable(space.kind) && is_undocumented {
+            found.push(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    found
+}
+
+struct UndocumentedCode {
+    _guard: (),
+}
+
+impl Callback for UndocumentedCode {
+    // The first and last lines and the names of the undocumented items
+    type Res = HashSet<(usize, usize, String)>;
+    type Cfg = bool;
+
+    fn call<T: ParserTrait>(public_only: Self::Cfg, parser: &T) -> Self::Res {
+        let code = parser.get_code();
+        let lang = parser.get_language();
+        let mut items = HashSet::new();
+        let mut stack = vec![parser.get_root()];
+        while let Some(node) = stack.pop() {
+            if is_documentable(T::Getter::get_space_kind(&node))
+                && let Some(name) = T::Getter::get_func_space_name(&node, code)
+                && name != "<anonymous>"
+                && (!public_only || is_public(&node, code, lang))
+                && !is_documented(&node, code, lang)
+            {
+                // The names of the spaces are normalized the same way
+                let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+                items.insert((node.start_row() + 1, node.end_row() + 1, name));
+            }
+            stack.extend(node.children());
+        }
+        items
+    }
+}
+
+#[inline]
+fn is_documentable(kind: SpaceKind) -> bool {
+    matches!(
+        kind,
+        SpaceKind::Function
+            | SpaceKind::Class
+            | SpaceKind::Struct
+            | SpaceKind::Trait
+            | SpaceKind::Interface
+    )
+}
+
+// Checks whether an item has a doc comment, right before it or, in
+// `Python`, as the first statement of its body
+fn is_documented(node: &Node, code: &[u8], lang: LANG) -> bool {
+    if lang == LANG::Python {
+        return node
+            .child_by_field_name("body")
+            .and_then(|body| {
+                body.children()
+                    .find(|child| child.is_named() && child.kind() != "comment")
+            })
+            .filter(|statement| statement.kind() == "expression_statement")
+            .and_then(|statement| statement.child(0))
+            .is_some_and(|expression| expression.kind() == "string");
+    }
+
+    // The comment of an exported or assigned function precedes the whole
+    // declaration
+    let mut item = *node;
+    while let Some(parent) = item.parent().filter(|parent| {
+        matches!(
+            parent.kind(),
+            "export_statement" | "variable_declarator" | "lexical_declaration"
+        )
+    }) {
+        item = parent;
+    }
+
+    let mut start_row = item.start_row();
+    let mut sibling = item.previous_sibling();
+    while let Some(previous) = sibling {
+        // Only the comments right above the item belong to it
+        if previous.end_row() + 1 < start_row {
+            return false;
+        }
+        match previous.kind() {
+            "attribute_item" => {}
+            kind if kind.contains("comment") => {
+                if previous
+                    .utf8_text(code)
+                    .is_some_and(|text| is_doc_comment(text, lang))
+                {
+                    return true;
+                }
+            }
+            _ => return false,
+        }
+        start_row = previous.start_row();
+        sibling = previous.previous_sibling();
+    }
+    false
+}
+
+#[inline]
+fn is_doc_comment(text: &str, lang: LANG) -> bool {
+    let is_block_doc = text.starts_with("/**") && !text.starts_with("/**/");
+    match lang {
+        LANG::Rust => is_block_doc || (text.starts_with("///") && !text.starts_with("////")),
+        LANG::Javascript | LANG::Typescript | LANG::Tsx | LANG::Java => is_block_doc,
+        _ => true,
+    }
+}
+
+// Checks whether an item is public, from its visibility modifiers or, in
+// `Python`, from its name
+fn is_public(node: &Node, code: &[u8], lang: LANG) -> bool {
+    match lang {
+        LANG::Rust => node.children().any(|child| {
+            child.kind() == "visibility_modifier" && child.utf8_text(code) == Some("pub")
+        }),
+        LANG::Python => node
+            .child_by_field_name("name")
+            .and_then(|name| name.utf8_text(code))
+            .is_some_and(|name| {
+                !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+            }),
+        LANG::Javascript | LANG::Typescript | LANG::Tsx => {
+            let hidden = node.children().any(|child| {
+                child.kind() == "accessibility_modifier" && child.utf8_text(code) != Some("public")
+            });
+            !hidden && node.ancestors().any(|ancestor| ancestor.kind() == "export_statement")
+        }
+        LANG::Java => node.children().any(|child| {
+            child.kind() == "modifiers"
+                && child.children().any(|modifier| modifier.kind() == "public")
+        }),
+        _ => true,
+    }
+}
+
+#[inline]
+fn is_named_function(space: &FuncSpace, name: &str) -> bool {
+    space.kind == SpaceKind::Function && space.name.as_deref() == Some(name)
+}
+
 /// Configuration options for finding different
 /// types of nodes in a code.
 #[derive(Debug)]
@@ -76,3 +561,153 @@ impl Callback for Find {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{get_function_spaces, PythonParser, LANG};
+
+    const SOURCE: &str = "def outer():
+    x = 1
+    def inner():
+        return 2
+    return inner
+";
+
+    #[test]
+    fn python_enclosing_function() {
+        let space = get_function_spaces(
+            &LANG::Python,
+            SOURCE.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let name = |offset| {
+            enclosing_function(&space, SOURCE.as_bytes(), offset)
+                .and_then(|function| function.name.as_deref())
+        };
+        let inner = SOURCE.find("return 2").unwrap();
+        assert_eq!(name(inner), Some("inner"));
+        assert_eq!(name(SOURCE.find("x = 1").unwrap()), Some("outer"));
+        assert_eq!(name(SOURCE.len() + 1), None);
+    }
+
+    #[test]
+    fn python_long_functions() {
+        // A 5-line function followed by a 120-line one
+        let body: String = (0..118).map(|line| format!("    x += {line}\n")).collect();
+        let source = format!(
+            "def short(x):\n    y = x\n    y += 1\n    y *= 2\n    return y\n\n\
+             def long(x):\n{body}    return x\n"
+        );
+        let space = get_function_spaces(&LANG::Python, source.into(), Path::new("foo.py"), None)
+            .unwrap();
+
+        let long = long_functions(&space, 100);
+        assert_eq!(long.len(), 1);
+        assert_eq!(long[0].name.as_deref(), Some("long"));
+        assert_eq!(long[0].length(), 120);
+        assert_eq!(space.spaces[0].length(), 5);
+        assert_eq!(space.function_length_max(), 120);
+        let json = serde_json::to_value(&space.metrics).unwrap();
+        assert_eq!(json["loc"]["function_length_max"], 120.);
+        assert!(long_functions(&space, 120).is_empty());
+    }
+
+    #[test]
+    fn python_renamed_clones() {
+        let source = "def total(items):
+    result = 0
+    for item in items:
+        if item > 0:
+            result += item
+    return result
+
+def accumulate(values):
+    acc = 0
+    for value in values:
+        if value > 0:
+            acc += value
+    return acc
+
+def positive(values):
+    acc = 0
+    for value in values:
+        if value > 1:
+            acc += value
+    return acc
+";
+        let groups = clones(&[(Path::new("foo.py"), source.as_bytes(), LANG::Python)], 10);
+
+        assert_eq!(groups.len(), 1);
+        let names: Vec<_> = groups[0]
+            .functions
+            .iter()
+            .map(|function| function.name.as_deref())
+            .collect();
+        assert_eq!(names, [Some("total"), Some("accumulate")]);
+        assert_eq!(groups[0].functions[1].start_line, 8);
+        assert!(clones(&[(Path::new("foo.py"), source.as_bytes(), LANG::Python)], 1000).is_empty());
+    }
+
+    #[test]
+    fn rust_undocumented() {
+        let source = "/// Doubles a number.
+#[inline]
+pub fn documented(x: u32) -> u32 {
+    x * 2
+}
+
+// Not a doc comment
+pub fn undocumented(x: u32) -> u32 {
+    x + 1
+}
+
+fn private(x: u32) -> u32 {
+    x
+}
+";
+        let space =
+            get_function_spaces(&LANG::Rust, source.into(), Path::new("foo.rs"), None).unwrap();
+
+        let names = |public_only| {
+            undocumented(&space, source.as_bytes(), LANG::Rust, public_only)
+                .into_iter()
+                .map(|function| function.name.as_deref())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(true), [Some("undocumented")]);
+        assert_eq!(names(false), [Some("undocumented"), Some("private")]);
+    }
+
+    #[test]
+    fn rust_undocumented_same_line_closure() {
+        let source = "pub fn apply(x: u32) -> u32 { let f = |y: u32| y + 1; f(x) }\n";
+        let space =
+            get_function_spaces(&LANG::Rust, source.into(), Path::new("foo.rs"), None).unwrap();
+        assert_eq!(space.spaces[0].spaces.len(), 1);
+
+        let found = undocumented(&space, source.as_bytes(), LANG::Rust, false);
+        let names: Vec<_> = found
+            .iter()
+            .map(|function| function.name.as_deref())
+            .collect();
+        assert_eq!(names, [Some("apply")]);
+    }
+
+    #[test]
+    fn python_descendant_for_byte_range() {
+        let parser = PythonParser::new(SOURCE.as_bytes().to_vec(), Path::new("foo.py"), None);
+        let root = parser.get_root();
+
+        let start = SOURCE.find("return 2").unwrap();
+        let node = root.descendant_for_byte_range(start, start + 6).unwrap();
+        assert_eq!(node.kind(), "return");
+        assert_eq!((node.start_byte(), node.end_byte()), (start, start + 6));
+        assert!(root.descendant_for_byte_range(start + 6, start).is_none());
+    }
+}
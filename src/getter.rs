@@ -1,11 +1,12 @@
 use crate::{
     analysis_context::{node_text, with_current_code},
     metrics::halstead::HalsteadType,
-    spaces::SpaceKind,
+    spaces::{SpaceKind, Supertypes},
     traits::Search,
-    CcommentCode, Cpp, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, Java,
-    JavaCode, Javascript, JavascriptCode, KotlinCode, LuaCode, MozjsCode, Node, PreprocCode,
-    Python, PythonCode, Rust, RustCode, Tsx, TsxCode, Typescript, TypescriptCode,
+    CcommentCode, Cpp, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode,
+    GraphqlCode, Java, JavaCode, Javascript, JavascriptCode, KotlinCode, LuaCode, MozjsCode, Node,
+    PerlCode, PreprocCode, Python, PythonCode, RCode, Rust, RustCode, Tsx, TsxCode, Typescript,
+    TypescriptCode,
 };
 
 macro_rules! get_operator {
@@ -44,6 +45,48 @@ pub trait Getter {
         SpaceKind::Unknown
     }
 
+    /// Returns the supertypes declared by a class or an interface, `None`
+    /// for any other node.
+    #[must_use]
+    fn get_supertypes(_node: &Node, _code: &[u8]) -> Option<Supertypes> {
+        None
+    }
+
+    /// Returns the signature of a named function, i.e. its name followed by
+    /// its parameters and by its return type when it is declared, `None`
+    /// for any other node.
+    ///
+    /// The signature is written as in the language, with its whitespace
+    /// collapsed, e.g. `f(a: i32) -> bool` for `Rust`.
+    #[must_use]
+    fn get_signature(node: &Node, code: &[u8]) -> Option<String> {
+        let name = function_name::<Self>(node, code)?;
+        let parameters = signature_text(&node.child_by_field_name("parameters")?, code)?;
+        let mut signature = format!("{name}{parameters}");
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            let return_type = signature_text(&return_type, code)?;
+            // Type annotations, as in `TypeScript`, include their colon
+            if return_type.starts_with(':') {
+                signature.push_str(&return_type);
+            } else {
+                signature.push_str(" -> ");
+                signature.push_str(&return_type);
+            }
+        } else if let Some(result) = node.child_by_field_name("result") {
+            signature.push(' ');
+            signature.push_str(&signature_text(&result, code)?);
+        }
+        Some(signature)
+    }
+
+    /// Returns the decorators applied to a function or a class, in order,
+    /// as written without their `@` and with their whitespace collapsed,
+    /// e.g. `staticmethod` or `app.route("/")`.
+    #[must_use]
+    fn get_decorators(_node: &Node, _code: &[u8]) -> Vec<String> {
+        Vec::new()
+    }
+
     #[must_use]
     fn get_op_type(_node: &Node) -> HalsteadType {
         HalsteadType::Unknown
@@ -55,6 +98,123 @@ pub trait Getter {
     }
 }
 
+// Returns the name of a function space, `None` for the other spaces and
+// for the anonymous functions
+fn function_name<'a, T: Getter + ?Sized>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+    if T::get_space_kind(node) != SpaceKind::Function {
+        return None;
+    }
+    T::get_func_space_name(node, code).filter(|name| *name != "<anonymous>")
+}
+
+// Returns the text of a node with its whitespace collapsed
+fn signature_text(node: &Node, code: &[u8]) -> Option<String> {
+    node.utf8_text(code)
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// Returns the signature of a function whose return type precedes its name,
+// as in `Java`, where `declarator` holds both the name and the parameters
+fn typed_signature(return_type: Option<Node>, declarator: &str, code: &[u8]) -> Option<String> {
+    match return_type {
+        Some(return_type) => Some(format!("{} {declarator}", signature_text(&return_type, code)?)),
+        None => Some(declarator.to_string()),
+    }
+}
+
+// Pushes the names of the types declared in `node`, looking through the
+// lists of types and leaving out their modifiers
+fn declared_types(node: &Node, code: &[u8], types: &mut Vec<String>) {
+    for child in node.children().filter(Node::is_named) {
+        match child.kind() {
+            "type_list" => declared_types(&child, code, types),
+            "type_arguments" | "access_specifier" | "virtual" | "keyword_argument"
+            | "comment" => {}
+            _ => {
+                if let Some(text) = child.utf8_text(code) {
+                    types.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+            }
+        }
+    }
+}
+
+// Returns the supertypes of the classes and interfaces of the `JavaScript`
+// family, where `TypeScript` splits the heritage of a class into clauses
+fn js_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+    if !matches!(node.kind(), "class" | "class_declaration" | "interface_declaration") {
+        return None;
+    }
+    let mut extends = Vec::new();
+    let mut implements = Vec::new();
+    for child in node.children() {
+        match child.kind() {
+            "class_heritage" => {
+                for clause in child.children().filter(Node::is_named) {
+                    match clause.kind() {
+                        "extends_clause" => declared_types(&clause, code, &mut extends),
+                        "implements_clause" => declared_types(&clause, code, &mut implements),
+                        // A `JavaScript` class extends a single expression
+                        _ => {
+                            if let Some(text) = clause.utf8_text(code) {
+                                extends.push(text.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            "extends_type_clause" => declared_types(&child, code, &mut extends),
+            _ => {}
+        }
+    }
+    Some(Supertypes::new(extends, implements))
+}
+
+// Pushes the supertypes of a `Kotlin` class, where the classes are called
+// through their constructors
+fn kotlin_delegation_specifiers(
+    node: &Node,
+    code: &[u8],
+    extends: &mut Vec<String>,
+    implements: &mut Vec<String>,
+) {
+    for child in node.children() {
+        match child.kind() {
+            "delegation_specifiers" => {
+                kotlin_delegation_specifiers(&child, code, extends, implements);
+            }
+            "delegation_specifier" => {
+                let invocation = child
+                    .children()
+                    .find(|specifier| specifier.kind() == "constructor_invocation");
+                let (types, specifier) = match invocation {
+                    Some(invocation) => (
+                        &mut *extends,
+                        invocation.children().find(|name| name.kind() == "user_type"),
+                    ),
+                    None => (&mut *implements, Some(child)),
+                };
+                if let Some(text) = specifier.and_then(|specifier| specifier.utf8_text(code)) {
+                    types.push(text.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Whether a `C#` type name follows the naming convention of the interfaces
+fn is_csharp_interface(name: &str) -> bool {
+    let mut chars = name.rsplit('.').next().unwrap_or(name).chars();
+    chars.next() == Some('I') && chars.next().is_some_and(char::is_uppercase)
+}
+
+// Whether a `Python` string is an f-string, i.e. has interpolations
+#[inline]
+fn is_python_f_string(node: &Node) -> bool {
+    node.kind() == "string" && node.children().any(|child| child.kind() == "interpolation")
+}
+
 impl Getter for PythonCode {
     fn get_space_kind(node: &Node) -> SpaceKind {
         match node.kind() {
@@ -65,6 +225,34 @@ impl Getter for PythonCode {
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        if node.kind() != "class_definition" {
+            return None;
+        }
+        let mut extends = Vec::new();
+        if let Some(superclasses) = node.child_by_field_name("superclasses") {
+            declared_types(&superclasses, code, &mut extends);
+        }
+        Some(Supertypes::new(extends, Vec::new()))
+    }
+
+    fn get_decorators(node: &Node, code: &[u8]) -> Vec<String> {
+        let Some(parent) = node
+            .parent()
+            .filter(|parent| parent.kind() == "decorated_definition")
+        else {
+            return Vec::new();
+        };
+        parent
+            .children()
+            .filter(|child| child.kind() == "decorator")
+            .filter_map(|decorator| {
+                let expression = decorator.children().find(Node::is_named)?;
+                signature_text(&expression, code)
+            })
+            .collect()
+    }
+
     fn get_op_type(node: &Node) -> HalsteadType {
         match node.kind() {
             "import_statement"
@@ -138,6 +326,12 @@ impl Getter for PythonCode {
             | "await"
             | "print_statement" => HalsteadType::Operator,
             "identifier" | "integer" | "float" | "true" | "false" | "none" => HalsteadType::Operand,
+            // The embedded expressions of an f-string are classified on their
+            // own, so only its literal parts are operands
+            "string" if is_python_f_string(node) => HalsteadType::Unknown,
+            "string_content" if node.parent().is_some_and(|parent| is_python_f_string(&parent)) => {
+                HalsteadType::Operand
+            }
             "string" => {
                 let mut operator = HalsteadType::Unknown;
                 // check if we've a documentation string or a multiline comment
@@ -163,6 +357,10 @@ impl Getter for MozjsCode {
         JavascriptCode::get_space_kind(node)
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        js_supertypes(node, code)
+    }
+
     fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
         JavascriptCode::get_func_space_name(node, code)
     }
@@ -191,6 +389,10 @@ impl Getter for JavascriptCode {
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        js_supertypes(node, code)
+    }
+
     fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
         if let Some(name) = node.child_by_field_name("name") {
             let code = &code[name.start_byte()..name.end_byte()];
@@ -341,6 +543,10 @@ impl Getter for TypescriptCode {
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        js_supertypes(node, code)
+    }
+
     fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
         if let Some(name) = node.child_by_field_name("name") {
             let code = &code[name.start_byte()..name.end_byte()];
@@ -492,6 +698,10 @@ impl Getter for TsxCode {
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        js_supertypes(node, code)
+    }
+
     fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
         if let Some(name) = node.child_by_field_name("name") {
             let code = &code[name.start_byte()..name.end_byte()];
@@ -629,6 +839,14 @@ impl Getter for TsxCode {
 
 impl Getter for RustCode {
     fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+        // a trait impl 'impl Trait for Type {...' is named 'Trait for Type',
+        // so that it is not mistaken for the inherent impl of the type
+        if let (Some(trait_node), Some(type_node)) = (
+            node.child_by_field_name("trait"),
+            node.child_by_field_name("type"),
+        ) {
+            return std::str::from_utf8(&code[trait_node.start_byte()..type_node.end_byte()]).ok();
+        }
         // we're in a function or in a class or an impl
         // for an impl: we've  'impl ... type {...'
         node.child_by_field_name("name")
@@ -644,6 +862,7 @@ impl Getter for RustCode {
             "function_item" | "closure_expression" => SpaceKind::Function,
             "trait_item" => SpaceKind::Trait,
             "impl_item" => SpaceKind::Impl,
+            "macro_definition" => SpaceKind::Macro,
             "source_file" => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
         }
@@ -730,6 +949,19 @@ impl Getter for CppCode {
         None
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        if !matches!(node.kind(), "class_specifier" | "struct_specifier") {
+            return None;
+        }
+        let mut extends = Vec::new();
+        for child in node.children() {
+            if child.kind() == "base_class_clause" {
+                declared_types(&child, code, &mut extends);
+            }
+        }
+        Some(Supertypes::new(extends, Vec::new()))
+    }
+
     fn get_space_kind(node: &Node) -> SpaceKind {
         match node.kind() {
             "function_definition" => SpaceKind::Function,
@@ -741,6 +973,12 @@ impl Getter for CppCode {
         }
     }
 
+    fn get_signature(node: &Node, code: &[u8]) -> Option<String> {
+        function_name::<Self>(node, code)?;
+        let declarator = signature_text(&node.child_by_field_name("declarator")?, code)?;
+        typed_signature(node.child_by_field_name("type"), &declarator, code)
+    }
+
     fn get_op_type(node: &Node) -> HalsteadType {
         use Cpp::{
             Break, Case, Catch, Continue, Default, Delete, Do, Else, False, FieldIdentifier, For,
@@ -816,16 +1054,44 @@ impl Getter for CcommentCode {
 impl Getter for JavaCode {
     fn get_space_kind(node: &Node) -> SpaceKind {
         match node.kind() {
-            "class_declaration" => SpaceKind::Class,
-            "method_declaration" | "constructor_declaration" | "lambda_expression" => {
-                SpaceKind::Function
-            }
+            "class_declaration" | "record_declaration" => SpaceKind::Class,
+            "method_declaration"
+            | "constructor_declaration"
+            | "compact_constructor_declaration"
+            | "lambda_expression" => SpaceKind::Function,
             "interface_declaration" => SpaceKind::Interface,
             "program" => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        if !matches!(
+            node.kind(),
+            "class_declaration" | "interface_declaration" | "record_declaration"
+        ) {
+            return None;
+        }
+        let mut extends = Vec::new();
+        let mut implements = Vec::new();
+        for child in node.children() {
+            match child.kind() {
+                "superclass" | "extends_interfaces" => {
+                    declared_types(&child, code, &mut extends);
+                }
+                "super_interfaces" => declared_types(&child, code, &mut implements),
+                _ => {}
+            }
+        }
+        Some(Supertypes::new(extends, implements))
+    }
+
+    fn get_signature(node: &Node, code: &[u8]) -> Option<String> {
+        let name = function_name::<Self>(node, code)?;
+        let parameters = signature_text(&node.child_by_field_name("parameters")?, code)?;
+        typed_signature(node.child_by_field_name("type"), &format!("{name}{parameters}"), code)
+    }
+
     fn get_op_type(node: &Node) -> HalsteadType {
         match node.kind() {
             // Operator: control flow
@@ -877,6 +1143,20 @@ impl Getter for KotlinCode {
         }
     }
 
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        if node.kind() != "class_declaration" {
+            return None;
+        }
+        let mut extends = Vec::new();
+        let mut implements = Vec::new();
+        kotlin_delegation_specifiers(node, code, &mut extends, &mut implements);
+        // The supertypes of an interface are all interfaces it extends
+        if node.children().any(|child| child.kind() == "interface") {
+            extends.append(&mut implements);
+        }
+        Some(Supertypes::new(extends, implements))
+    }
+
     fn get_op_type(node: &Node) -> HalsteadType {
         match node.kind() {
             // Keywords and control flow
@@ -1099,11 +1379,14 @@ impl Getter for ErlangCode {
         match node.kind() {
             "binary_op_expr" | "unary_op_expr" | "match_expr" | "catch_expr" | "+" | "-" | "*"
             | "/" | "%" | "div" | "rem" | "band" | "bor" | "bxor" | "bsl" | "bsr" | "and"
-            | "or" | "not" | "xor" | "orelse" | "andalso" | "==" | "/=" | "=:= " | "=/=" | "<"
-            | "<=" | ">" | ">=" | "++" | "--" | "!" | "catch" | "of" | "after" => {
-                HalsteadType::Operator
-            }
-            "atom" | "var" | "list" | "tuple" | "map_expr" => HalsteadType::Operand,
+            | "or" | "not" | "xor" | "orelse" | "andalso" | "==" | "/=" | "=:=" | "=/=" | "<"
+            | "=<" | "<=" | ">" | ">=" | "++" | "--" | "!" | "catch" | "of" | "after"
+            | "list_comprehension" | "binary_comprehension" | "map_comprehension" | "||"
+            | "<-" => HalsteadType::Operator,
+            // Like maps, records and binaries are operands as a whole, e.g.
+            // `#point{x = X}` or `<<1, 2>>`
+            "atom" | "var" | "list" | "tuple" | "map_expr" | "record_expr" | "record_update_expr"
+            | "record_field_expr" | "record_index_expr" | "binary" => HalsteadType::Operand,
             _ => HalsteadType::Unknown,
         }
     }
@@ -1142,10 +1425,9 @@ impl Getter for GleamCode {
     fn get_op_type(node: &Node) -> HalsteadType {
         match node.kind() {
             "binary_expression" | "boolean_negation" | "integer_negation" | "pipeline_echo"
-            | "case" | "let" | "+" | "-" | "*" | "/" | "%" | "++" | "--" | "<" | "<=" | ">"
-            | ">=" | "==" | "!=" | "&&" | "||" | "<-" | "->" | "if" | "else" => {
-                HalsteadType::Operator
-            }
+            | "case" | "let" | "use" | "+" | "-" | "*" | "/" | "%" | "++" | "--" | "<" | "<="
+            | ">" | ">=" | "==" | "!=" | "&&" | "||" | "<-" | "->" | "if" | "else" | "|>"
+            | "<>" | "<<" => HalsteadType::Operator,
             "identifier" | "integer" | "float" | "string" | "comment" => HalsteadType::Operand,
             _ => HalsteadType::Unknown,
         }
@@ -1154,6 +1436,7 @@ impl Getter for GleamCode {
     fn get_operator_id_as_str(id: u16) -> &'static str {
         let language: tree_sitter::Language = tree_sitter_gleam::LANGUAGE.into();
         match language.node_kind_for_id(id) {
+            Some("<<") => "<<>>",
             Some("(") => "()",
             Some("[") => "[]",
             Some("{") => "{}",
@@ -1252,6 +1535,50 @@ impl Getter for GoCode {
 
 // C# implementation
 impl Getter for CsharpCode {
+    fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+        let name = if node.kind() == "constructor_declaration" {
+            // Constructors are named after the type they construct
+            node.ancestors()
+                .find(|ancestor| {
+                    matches!(
+                        ancestor.kind(),
+                        "class_declaration" | "struct_declaration" | "record_declaration"
+                    )
+                })
+                .and_then(|declaration| declaration.child_by_field_name("name"))
+                .or_else(|| node.child_by_field_name("name"))
+        } else {
+            node.child_by_field_name("name")
+        };
+        name.map_or(Some("<anonymous>"), |name| {
+            std::str::from_utf8(&code[name.start_byte()..name.end_byte()]).ok()
+        })
+    }
+
+    fn get_supertypes(node: &Node, code: &[u8]) -> Option<Supertypes> {
+        let mut types = Vec::new();
+        for child in node.children() {
+            if child.kind() == "base_list" {
+                declared_types(&child, code, &mut types);
+            }
+        }
+        match node.kind() {
+            // Only the first base of a class can be a class, and interface
+            // names are conventionally prefixed with `I`
+            "class_declaration" | "record_declaration" => {
+                let extends = if types.first().is_some_and(|name| !is_csharp_interface(name)) {
+                    vec![types.remove(0)]
+                } else {
+                    Vec::new()
+                };
+                Some(Supertypes::new(extends, types))
+            }
+            "struct_declaration" => Some(Supertypes::new(Vec::new(), types)),
+            "interface_declaration" => Some(Supertypes::new(types, Vec::new())),
+            _ => None,
+        }
+    }
+
     fn get_space_kind(node: &Node) -> SpaceKind {
         match node.kind() {
             "compilation_unit" => SpaceKind::Unit,
@@ -1259,12 +1586,22 @@ impl Getter for CsharpCode {
             "interface_declaration" => SpaceKind::Interface,
             "method_declaration"
             | "constructor_declaration"
+            | "local_function_statement"
             | "lambda_expression"
             | "anonymous_method_expression" => SpaceKind::Function,
             _ => SpaceKind::Unknown,
         }
     }
 
+    fn get_signature(node: &Node, code: &[u8]) -> Option<String> {
+        let name = function_name::<Self>(node, code)?;
+        let parameters = signature_text(&node.child_by_field_name("parameters")?, code)?;
+        let return_type = node
+            .child_by_field_name("returns")
+            .or_else(|| node.child_by_field_name("type"));
+        typed_signature(return_type, &format!("{name}{parameters}"), code)
+    }
+
     fn get_op_type(node: &Node) -> HalsteadType {
         match node.kind() {
             // Keywords and control flow
@@ -1301,3 +1638,167 @@ impl Getter for CsharpCode {
         }
     }
 }
+
+// R implementation
+impl Getter for RCode {
+    fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+        if node.kind() != "function_definition" {
+            return Some("<anonymous>");
+        }
+        // R functions are anonymous values: the name comes from the
+        // enclosing assignment, e.g. `f <- function(x) x` or `function(x) x -> f`
+        let name = node.parent().and_then(|parent| {
+            if parent.kind() != "binary_operator" {
+                return None;
+            }
+            let operator = parent.child_by_field_name("operator")?;
+            let (value_field, target_field) = match operator.kind() {
+                "<-" | "<<-" | "=" | ":=" => ("rhs", "lhs"),
+                "->" | "->>" => ("lhs", "rhs"),
+                _ => return None,
+            };
+            let bound = parent.child_by_field_name(value_field)?;
+            if bound.id() != node.id() {
+                return None;
+            }
+            let target = parent.child_by_field_name(target_field)?;
+            node_text(&target, code)
+        });
+        name.or(Some("<anonymous>"))
+    }
+
+    fn get_space_kind(node: &Node) -> SpaceKind {
+        match node.kind() {
+            "program" => SpaceKind::Unit,
+            "function_definition" => SpaceKind::Function,
+            _ => SpaceKind::Unknown,
+        }
+    }
+
+    fn get_op_type(node: &Node) -> HalsteadType {
+        match node.kind() {
+            // Keywords and control flow
+            "if" | "else" | "for" | "while" | "repeat" | "in" | "function" | "\\" | "break"
+            | "next"
+            // Operators
+            | "<-" | "<<-" | "->" | "->>" | "=" | ":=" | "+" | "-" | "*" | "/" | "^" | "<"
+            | ">" | "<=" | ">=" | "==" | "!=" | "!" | "&" | "&&" | "|" | "||" | "~" | "?"
+            | ":" | "::" | ":::" | "$" | "@" | "|>" | "special"
+            // Delimiters
+            | "(" | "[" | "[[" | "{" | "," | ";"
+            => HalsteadType::Operator,
+            // Operands
+            "identifier" | "string" | "integer" | "float" | "complex" | "true" | "false"
+            | "null" | "inf" | "nan" | "na" | "dots" | "dot_dot_i"
+            => HalsteadType::Operand,
+            _ => HalsteadType::Unknown,
+        }
+    }
+
+    fn get_operator_id_as_str(id: u16) -> &'static str {
+        let language: tree_sitter::Language = tree_sitter_r::LANGUAGE.into();
+        match language.node_kind_for_id(id) {
+            Some("(") => "()",
+            Some("[") => "[]",
+            Some("[[") => "[[]]",
+            Some("{") => "{}",
+            Some(kind) => kind,
+            None => "unknown",
+        }
+    }
+}
+
+// Perl implementation
+impl Getter for PerlCode {
+    fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+        match node.kind() {
+            "subroutine_declaration_statement" | "method_declaration_statement" => node
+                .child_by_field_name("name")
+                .and_then(|name| node_text(&name, code))
+                .or(Some("<anonymous>")),
+            "anonymous_subroutine_expression" => Some("<anonymous>"),
+            _ => None,
+        }
+    }
+
+    fn get_space_kind(node: &Node) -> SpaceKind {
+        match node.kind() {
+            "source_file" => SpaceKind::Unit,
+            "subroutine_declaration_statement"
+            | "method_declaration_statement"
+            | "anonymous_subroutine_expression" => SpaceKind::Function,
+            _ => SpaceKind::Unknown,
+        }
+    }
+
+    fn get_op_type(node: &Node) -> HalsteadType {
+        match node.kind() {
+            // Keywords and control flow
+            "if" | "unless" | "elsif" | "else" | "while" | "until" | "for" | "foreach"
+            | "do" | "return" | "last" | "next" | "redo" | "sub" | "my" | "our" | "local"
+            | "package" | "use" | "require"
+            // Operators
+            | "=" | "+" | "-" | "*" | "/" | "%" | "**" | "." | "x" | "+=" | "-=" | "*="
+            | "/=" | ".=" | "||=" | "&&=" | "//=" | "==" | "!=" | "<" | ">" | "<=" | ">="
+            | "<=>" | "eq" | "ne" | "lt" | "gt" | "le" | "ge" | "cmp" | "=~" | "!~" | "&&"
+            | "||" | "//" | "!" | "and" | "or" | "not" | "xor" | "++" | "--" | "->" | "=>"
+            | "?" | "::"
+            // Delimiters
+            | "(" | "[" | "{" | "," | ";"
+            => HalsteadType::Operator,
+            // Operands
+            "scalar" | "array" | "hash" | "number" | "string_literal"
+            | "interpolated_string_literal" | "bareword"
+            => HalsteadType::Operand,
+            _ => HalsteadType::Unknown,
+        }
+    }
+
+    fn get_operator_id_as_str(id: u16) -> &'static str {
+        let language: tree_sitter::Language = tree_sitter_perl::LANGUAGE.into();
+        match language.node_kind_for_id(id) {
+            Some("(") => "()",
+            Some("[") => "[]",
+            Some("{") => "{}",
+            Some(kind) => kind,
+            None => "unknown",
+        }
+    }
+}
+
+// GraphQL implementation
+impl Getter for GraphqlCode {
+    fn get_space_kind(node: &Node) -> SpaceKind {
+        match node.kind() {
+            "source_file" => SpaceKind::Unit,
+            _ => SpaceKind::Unknown,
+        }
+    }
+
+    fn get_op_type(node: &Node) -> HalsteadType {
+        match node.kind() {
+            // Keywords
+            "type" | "interface" | "input" | "enum" | "union" | "scalar" | "schema" | "extend"
+            | "directive" | "implements" | "repeatable" | "on" | "query" | "mutation"
+            | "subscription" | "fragment"
+            // Punctuators
+            | "{" | "(" | "[" | ":" | "=" | "@" | "|" | "&" | "!" | "..." | "$"
+            => HalsteadType::Operator,
+            // Operands
+            "name" | "string_value" | "int_value" | "float_value" | "true" | "false" | "null"
+            => HalsteadType::Operand,
+            _ => HalsteadType::Unknown,
+        }
+    }
+
+    fn get_operator_id_as_str(id: u16) -> &'static str {
+        let language: tree_sitter::Language = tree_sitter_graphql::LANGUAGE.into();
+        match language.node_kind_for_id(id) {
+            Some("(") => "()",
+            Some("[") => "[]",
+            Some("{") => "{}",
+            Some(kind) => kind,
+            None => "unknown",
+        }
+    }
+}
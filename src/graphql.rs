@@ -0,0 +1,197 @@
+//! Structural metrics of `GraphQL` schemas.
+//!
+//! Schemas are parsed with the `tree-sitter-graphql` grammar of
+//! [`LANG::Graphql`], and the definitions are read from their nodes, so
+//! comments, strings and descriptions never count.
+
+use serde::Serialize;
+
+use crate::{
+    langs::LANG,
+    node::{Node, Tree},
+};
+
+/// The kind of a `GraphQL` type definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphqlTypeKind {
+    /// An object type, defined by `type`
+    Object,
+    /// An interface, defined by `interface`
+    Interface,
+    /// An input object type, defined by `input`
+    Input,
+    /// An enum, defined by `enum`
+    Enum,
+}
+
+impl GraphqlTypeKind {
+    // Returns the kind of a definition or of an extension node, with the
+    // keyword and the kind of the members it holds
+    fn from_node_kind(kind: &str) -> Option<(Self, &'static str, &'static str)> {
+        match kind {
+            "object_type_definition" | "object_type_extension" => {
+                Some((Self::Object, "type", "field_definition"))
+            }
+            "interface_type_definition" | "interface_type_extension" => {
+                Some((Self::Interface, "interface", "field_definition"))
+            }
+            "input_object_type_definition" | "input_object_type_extension" => {
+                Some((Self::Input, "input", "input_value_definition"))
+            }
+            "enum_type_definition" | "enum_type_extension" => {
+                Some((Self::Enum, "enum", "enum_value_definition"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A type definition of a `GraphQL` schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphqlType {
+    /// The name of the type
+    pub name: String,
+    /// The kind of the type
+    pub kind: GraphqlTypeKind,
+    /// The number of fields of the type, or of values of an enum
+    pub fields: usize,
+    /// The line of the keyword defining the type
+    pub line: usize,
+}
+
+/// The structural metrics of a `GraphQL` schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GraphqlStats {
+    /// The object, interface, input and enum definitions, in source order.
+    /// An `extend` definition is listed on its own
+    pub types: Vec<GraphqlType>,
+    /// The number of fields of all the types
+    pub fields: usize,
+    /// The number of directives applied, e.g. `@deprecated`, not counting
+    /// the directive definitions
+    pub directives: usize,
+}
+
+/// Computes the structural metrics of a `GraphQL` schema.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::graphql_stats;
+///
+/// let stats = graphql_stats(b"type Query { me: User @deprecated }");
+/// assert_eq!(stats.types.len(), 1);
+/// assert_eq!(stats.fields, 1);
+/// assert_eq!(stats.directives, 1);
+/// ```
+#[must_use]
+pub fn graphql_stats(source: &[u8]) -> GraphqlStats {
+    let mut stats = GraphqlStats::default();
+    if let Some(tree) = Tree::with_lang(source, &LANG::Graphql) {
+        collect_stats(&tree.get_root(), source, &mut stats);
+    }
+    stats
+}
+
+fn collect_stats(node: &Node, source: &[u8], stats: &mut GraphqlStats) {
+    // The directive definitions name their directive without a `directive` node
+    if node.kind() == "directive" && node.is_named() {
+        stats.directives += 1;
+    }
+    if let Some((kind, keyword, member)) = GraphqlTypeKind::from_node_kind(node.kind()) {
+        let name = node
+            .children()
+            .find(|child| child.kind() == "name")
+            .and_then(|name| name.utf8_text(source))
+            .unwrap_or_default();
+        let line = node
+            .children()
+            .find(|child| child.kind() == keyword && !child.is_named())
+            .map_or(node.start_row(), |keyword| keyword.start_row());
+        // The members are held by a `fields_definition`, an
+        // `input_fields_definition` or an `enum_values_definition`
+        let fields = node
+            .children()
+            .flat_map(|child| child.children())
+            .filter(|child| child.kind() == member)
+            .count();
+        stats.fields += fields;
+        stats.types.push(GraphqlType {
+            name: name.to_string(),
+            kind,
+            fields,
+            line: line + 1,
+        });
+    }
+    for child in node.children() {
+        collect_stats(&child, source, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_types_and_fields() {
+        let source = "\"\"\"
+A user of the service, e.g. { name: \"Ada\" }
+\"\"\"
+type User @key(fields: \"id\") {
+  id: ID!
+  # The display name, e.g. nickname: String
+  name(format: NameFormat = FULL): String @deprecated(reason: \"Use fullName\")
+  friends(first: Int = 10, after: String): [User!]!
+}
+
+directive @key(fields: String!) on OBJECT | INTERFACE
+
+type Query {
+  me: User
+  user(id: ID!): User
+}
+";
+        let stats = graphql_stats(source.as_bytes());
+        let types: Vec<_> = stats
+            .types
+            .iter()
+            .map(|ty| (ty.name.as_str(), ty.kind, ty.fields, ty.line))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ("User", GraphqlTypeKind::Object, 3, 4),
+                ("Query", GraphqlTypeKind::Object, 2, 13)
+            ]
+        );
+        assert_eq!(stats.fields, 5);
+        assert_eq!(stats.directives, 2);
+    }
+
+    #[test]
+    fn enums_inputs_and_interfaces() {
+        let source = "interface Node { id: ID! }
+enum Color { RED GREEN @deprecated BLUE }
+input Filter { color: Color = RED, tags: [String] = [\"a\"] }
+union Item = Filter | Color
+extend type Query { nodes(filter: Filter = { color: RED }): [Node] }
+";
+        let stats = graphql_stats(source.as_bytes());
+        let types: Vec<_> = stats
+            .types
+            .iter()
+            .map(|ty| (ty.name.as_str(), ty.kind, ty.fields))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ("Node", GraphqlTypeKind::Interface, 1),
+                ("Color", GraphqlTypeKind::Enum, 3),
+                ("Filter", GraphqlTypeKind::Input, 2),
+                ("Query", GraphqlTypeKind::Object, 1)
+            ]
+        );
+        assert_eq!(stats.directives, 1);
+    }
+}
@@ -0,0 +1,358 @@
+//! Structural and accessibility metrics of `HTML` documents, and analysis
+//! of their scripts.
+//!
+//! Documents are read with a lightweight tokenizer rather than a grammar:
+//! tags are matched by name, the elements left open are closed by the
+//! closing tag of an enclosing element, as browsers do for `<p>` or `<li>`,
+//! and the content of `<script>` and `<style>` elements is skipped.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Serialize;
+
+use crate::{get_function_spaces, CodeMetrics, FuncSpace, LineOffsetMap, SpaceKind, LANG};
+
+// The elements which never have content nor a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+// The elements whose content is not markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// The structural and accessibility metrics of an `HTML` document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct HtmlStats {
+    /// The number of elements
+    pub elements: usize,
+    /// The deepest nesting of elements, `1` for the elements at the top
+    /// level of the document
+    pub max_depth: usize,
+    /// The number of images, i.e. `<img>`, `<area>` and
+    /// `<input type="image">` elements, with neither an `alt` nor an
+    /// `aria-label` or `aria-labelledby` attribute
+    pub missing_alt: usize,
+    /// The number of `style` attributes
+    pub inline_styles: usize,
+}
+
+/// Computes the structural and accessibility metrics of an `HTML`
+/// document.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::html_stats;
+///
+/// let stats = html_stats(b"<ul><li>One</li><li style=\"color: red\">Two</ul>");
+/// assert_eq!(stats.elements, 3);
+/// assert_eq!(stats.max_depth, 2);
+/// assert_eq!(stats.inline_styles, 1);
+/// ```
+#[must_use]
+pub fn html_stats(source: &[u8]) -> HtmlStats {
+    let source = String::from_utf8_lossy(source);
+    let mut stats = HtmlStats::default();
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = source.as_ref();
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = tag_end(rest) else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(closing) = tag.strip_prefix('/') {
+            let name = closing.trim().to_ascii_lowercase();
+            if let Some(index) = open.iter().rposition(|element| *element == name) {
+                open.truncate(index);
+            }
+            continue;
+        }
+        // Doctypes and processing instructions
+        if tag.starts_with(['!', '?']) {
+            continue;
+        }
+        let name_end = tag
+            .find(|c: char| c.is_ascii_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+
+        let attributes = attributes(&tag[name_end..]);
+        stats.elements += 1;
+        stats.max_depth = stats.max_depth.max(open.len() + 1);
+        if attributes.iter().any(|(attribute, _)| attribute == "style") {
+            stats.inline_styles += 1;
+        }
+        if needs_alt(&name, &attributes) && !has_text_alternative(&attributes) {
+            stats.missing_alt += 1;
+        }
+
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let closing = format!("</{name}");
+            rest = find_ignore_case(rest, &closing).map_or("", |index| &rest[index..]);
+        } else if !VOID_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            open.push(name);
+        }
+    }
+    stats
+}
+
+/// Returns the function spaces of the scripts of an `HTML` document or of
+/// a `Vue` single file component.
+///
+/// The inline `JavaScript` scripts are analyzed together as a single code,
+/// as `TypeScript` when one of them has a `lang="ts"` attribute. The
+/// scripts of other types, such as `JSON` data, are skipped.
+///
+/// The metrics of the returned space cover all the scripts, while its
+/// lines are the ones of the whole document. The lines of its subspaces
+/// are the lines of the document, not of the scripts.
+#[must_use]
+pub fn get_html_spaces(source: &[u8], path: &Path) -> FuncSpace {
+    let source = String::from_utf8_lossy(source);
+    let mut code = String::new();
+    let mut map = LineOffsetMap::new();
+    let mut lang = LANG::Javascript;
+    let mut line = 1;
+    for (outer_line, script, is_typescript) in scripts(&source) {
+        if script.trim().is_empty() {
+            continue;
+        }
+        if is_typescript {
+            lang = LANG::Typescript;
+        }
+        map.push(line, outer_line);
+        code.push_str(script);
+        if !script.ends_with('\n') {
+            code.push('\n');
+        }
+        line += script.lines().count();
+    }
+
+    let mut space =
+        get_function_spaces(&lang, code.into_bytes(), path, None).unwrap_or_else(|| FuncSpace {
+            name: None,
+            start_line: 0,
+            end_line: 0,
+            kind: SpaceKind::Unit,
+            spaces: Vec::new(),
+            metrics: CodeMetrics::default(),
+            supertypes: None,
+            signature: None,
+            decorators: Vec::new(),
+            content_hash: None,
+            custom: BTreeMap::new(),
+        });
+    for subspace in &mut space.spaces {
+        map.map_space(subspace);
+    }
+    space.name = path.to_str().map(ToString::to_string);
+    space.start_line = 1;
+    space.end_line = source.lines().count();
+    space
+}
+
+// Returns the inline scripts of a document, with the line of the document
+// where they start and whether they are written in `TypeScript`
+fn scripts(source: &str) -> Vec<(usize, &str, bool)> {
+    let lowercase = source.to_ascii_lowercase();
+    let mut scripts = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = lowercase[offset..].find('<').map(|start| offset + start) {
+        if lowercase[start..].starts_with("<!--") {
+            offset = lowercase[start..]
+                .find("-->")
+                .map_or(source.len(), |end| start + end + 3);
+            continue;
+        }
+        let Some(end) = tag_end(&source[start..]).map(|end| start + end) else {
+            break;
+        };
+        offset = end + 1;
+
+        let tag = &source[start + 1..end];
+        let name_end = tag
+            .find(|c: char| c.is_ascii_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        if !tag[..name_end].eq_ignore_ascii_case("script") || tag.ends_with('/') {
+            continue;
+        }
+        let content_end = lowercase[offset..]
+            .find("</script")
+            .map_or(source.len(), |index| offset + index);
+
+        let attributes = attributes(&tag[name_end..]);
+        let value = |name: &str| {
+            attributes
+                .iter()
+                .find(|(attribute, _)| attribute == name)
+                .map(|(_, value)| value.to_ascii_lowercase())
+        };
+        let is_javascript = value("type").is_none_or(|ty| {
+            ty == "module" || ty.ends_with("javascript") || ty.ends_with("ecmascript")
+        });
+        let is_typescript = value("lang").is_some_and(|lang| lang == "ts" || lang == "typescript");
+        if is_javascript || is_typescript {
+            let line = source[..offset].matches('\n').count() + 1;
+            scripts.push((line, &source[offset..content_end], is_typescript));
+        }
+        offset = content_end;
+    }
+    scripts
+}
+
+// Returns the index of the `>` closing the tag at the start of `text`,
+// skipping the ones inside quoted attribute values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Returns the lowercase names and the values of the attributes of a tag
+fn attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_equal) = rest.strip_prefix('=') {
+            let after_equal = after_equal.trim_start();
+            let (text, remaining) = match after_equal.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let quoted = &after_equal[1..];
+                    let end = quoted.find(quote).unwrap_or(quoted.len());
+                    (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after_equal
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after_equal.len());
+                    after_equal.split_at(end)
+                }
+            };
+            value = text.to_string();
+            rest = remaining;
+        }
+        attributes.push((name, value));
+    }
+    attributes
+}
+
+// Checks whether an element is an image, which needs a text alternative
+fn needs_alt(name: &str, attributes: &[(String, String)]) -> bool {
+    match name {
+        "img" | "area" => true,
+        "input" => attributes
+            .iter()
+            .any(|(attribute, value)| attribute == "type" && value.eq_ignore_ascii_case("image")),
+        _ => false,
+    }
+}
+
+fn has_text_alternative(attributes: &[(String, String)]) -> bool {
+    attributes.iter().any(|(attribute, _)| {
+        matches!(attribute.as_str(), "alt" | "aria-label" | "aria-labelledby")
+    })
+}
+
+fn find_ignore_case(text: &str, pattern: &str) -> Option<usize> {
+    text.to_ascii_lowercase().find(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_divs_and_missing_alt() {
+        let source = "<!DOCTYPE html>
+<html>
+  <head>
+    <title>Gallery <b>not markup</b></title>
+    <style>div > p { color: blue; }</style>
+  </head>
+  <body>
+    <!-- <div><div><div></div></div></div> -->
+    <div>
+      <div style=\"margin: 0\">
+        <div>
+          <img src=\"cat.png\">
+          <img src=\"dog.png\" alt=\"A dog\">
+          <input type=\"image\" src=\"go.png\" aria-label=\"Go\"/>
+        </div>
+      </div>
+    </div>
+    <p>Done<br>
+  </body>
+</html>
+";
+        assert_eq!(
+            html_stats(source.as_bytes()),
+            HtmlStats {
+                elements: 13,
+                max_depth: 6,
+                missing_alt: 1,
+                inline_styles: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn script_lines_in_document() {
+        let source = "<!DOCTYPE html>
+<html>
+<head>
+  <title>Greeting</title>
+  <script type=\"application/json\">{\"name\": \"data\"}</script>
+</head>
+<body>
+  <p>Hello</p>
+  <!-- <script>function hidden() {}</script> -->
+  <script>
+    function greet(name) {
+      if (name) {
+        alert(name);
+      }
+    }
+  </script>
+</body>
+</html>
+";
+        let space = get_html_spaces(source.as_bytes(), Path::new("foo.html"));
+        assert_eq!((space.start_line, space.end_line), (1, 18));
+        assert_eq!(space.spaces.len(), 1);
+
+        let function = &space.spaces[0];
+        assert_eq!(function.name.as_deref(), Some("greet"));
+        assert_eq!((function.start_line, function.end_line), (11, 15));
+        assert_eq!(function.metrics.cyclomatic.cyclomatic(), 2.);
+    }
+}
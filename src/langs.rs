@@ -35,7 +35,7 @@ mk_langs!(
         JavascriptCode,
         JavascriptParser,
         tree_sitter_javascript,
-        [],
+        [js, mjs, cjs, jsx],
         []
     ),
     (
@@ -105,7 +105,7 @@ mk_langs!(
         TypescriptCode,
         TypescriptParser,
         tree_sitter_typescript,
-        [ts, jsw, jsmw],
+        [ts, mts, cts, jsw, jsmw],
         ["typescript"]
     ),
     // BEAM languages - Full RCA metrics support enabled!
@@ -171,11 +171,53 @@ mk_langs!(
         tree_sitter_c_sharp,
         [cs, csx],
         ["csharp"]
+    ),
+    // R language - statistical computing
+    (
+        R,
+        "The `R` language",
+        "r",
+        RCode,
+        RParser,
+        tree_sitter_r,
+        [r, R],
+        ["r", "ess-r"]
+    ),
+    // Perl language - legacy scripting
+    (
+        Perl,
+        "The `Perl` language",
+        "perl",
+        PerlCode,
+        PerlParser,
+        tree_sitter_perl,
+        [pl, pm],
+        ["perl", "cperl"]
+    ),
+    // GraphQL language - API schemas
+    (
+        Graphql,
+        "The `GraphQL` language",
+        "graphql",
+        GraphqlCode,
+        GraphqlParser,
+        tree_sitter_graphql,
+        [graphql, gql],
+        ["graphql"]
     ) /* Singularity custom parsers removed - using standard tree-sitter parsers only
        * - Ccomment: Use standard C/C++ parser for comment analysis
        * - Preproc: Use standard C/C++ parser for macro analysis */
 );
 
+/// Returns every supported language along with the ABI version of its
+/// tree-sitter grammar, see [`LANG::grammar_version`].
+#[must_use]
+pub fn grammar_versions() -> Vec<(LANG, usize)> {
+    LANG::into_enum_iter()
+        .map(|lang| (lang, lang.grammar_version()))
+        .collect()
+}
+
 // Compatibility structs for Singularity custom parsers - functionality delegated to standard parsers
 pub struct MozjsCode;
 pub struct PreprocCode;
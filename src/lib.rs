@@ -12,11 +12,14 @@
 //! - C#
 //! - CSS
 //! - Go
+//! - GraphQL
 //! - HTML
 //! - Java
 //! - JavaScript
 //! - The JavaScript used in Firefox internal
+//! - Perl
 //! - Python
+//! - R
 //! - Rust
 //! - Typescript
 //!
@@ -107,6 +110,11 @@ pub use crate::tools::*;
 mod concurrent_files;
 pub use crate::concurrent_files::*;
 
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "archive")]
+pub use crate::archive::*;
+
 mod traits;
 pub use crate::traits::*;
 
@@ -122,6 +130,26 @@ pub use crate::code_analyzer::*;
 mod comment_rm;
 pub use crate::comment_rm::*;
 
+mod notebook;
+pub use crate::notebook::*;
+
+mod plain_text;
+pub use crate::plain_text::*;
+
+mod css;
+pub use crate::css::*;
+
+mod graphql;
+pub use crate::graphql::*;
+
+mod html;
+pub use crate::html::*;
+
+mod line_offset_map;
+pub use crate::line_offset_map::*;
+
+pub mod ai;
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -173,4 +201,738 @@ mod tests {
             analyzer.analyze_language(LANG::Csharp, csharp_code, AnalyzeOptions::default());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_r_language_support() {
+        let analyzer = SingularityCodeAnalyzer::new();
+
+        // Test language string detection
+        assert_eq!(analyzer.language_from_str("r"), Some(LANG::R));
+        assert_eq!(analyzer.language_from_str("R"), Some(LANG::R));
+
+        // Test file extension detection
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("analysis.R")),
+            Some(LANG::R)
+        );
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("utils.r")),
+            Some(LANG::R)
+        );
+
+        // Test that assigned functions are named after their binding
+        let r_code = "square <- function(x) {\n    x * x\n}\n";
+        let result = analyzer
+            .analyze_language(LANG::R, r_code, AnalyzeOptions::default())
+            .unwrap();
+        let function = &result.root_space.spaces[0];
+        assert_eq!(function.kind, SpaceKind::Function);
+        assert_eq!(function.name.as_deref(), Some("square"));
+    }
+
+    #[test]
+    fn test_module_extensions() {
+        let analyzer = SingularityCodeAnalyzer::new();
+
+        for (file, lang) in [
+            ("index.mjs", LANG::Javascript),
+            ("index.cjs", LANG::Javascript),
+            ("index.mts", LANG::Typescript),
+            ("index.cts", LANG::Typescript),
+        ] {
+            assert_eq!(analyzer.detect_language_from_path(&PathBuf::from(file)), Some(lang));
+            assert_eq!(guess_language(b"export {};\n", file).0, Some(lang), "{file}");
+        }
+    }
+
+    #[test]
+    fn test_perl_language_support() {
+        let analyzer = SingularityCodeAnalyzer::new();
+
+        assert_eq!(analyzer.language_from_str("perl"), Some(LANG::Perl));
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("script.pl")),
+            Some(LANG::Perl)
+        );
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("Module.pm")),
+            Some(LANG::Perl)
+        );
+
+        // Test that subroutines are named after their declaration
+        let perl_code = "sub greet {\n    my ($name) = @_;\n    return \"Hello, $name\";\n}\n";
+        let result = analyzer
+            .analyze_language(LANG::Perl, perl_code, AnalyzeOptions::default())
+            .unwrap();
+        let function = &result.root_space.spaces[0];
+        assert_eq!(function.kind, SpaceKind::Function);
+        assert_eq!(function.name.as_deref(), Some("greet"));
+    }
+
+    #[test]
+    fn test_graphql_language_support() {
+        let analyzer = SingularityCodeAnalyzer::new();
+
+        assert_eq!(analyzer.language_from_str("graphql"), Some(LANG::Graphql));
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("schema.graphql")),
+            Some(LANG::Graphql)
+        );
+        assert_eq!(
+            analyzer.detect_language_from_path(&PathBuf::from("queries.gql")),
+            Some(LANG::Graphql)
+        );
+
+        // Test that comments and descriptions are comment lines
+        let graphql_code =
+            "# The entry point\ntype Query {\n  \"The current user\"\n  me: User\n}\n";
+        let result = analyzer
+            .analyze_language(LANG::Graphql, graphql_code, AnalyzeOptions::default())
+            .unwrap();
+        let loc = &result.root_space.metrics.loc;
+        assert_eq!(result.root_space.kind, SpaceKind::Unit);
+        assert_eq!((loc.sloc(), loc.ploc(), loc.cloc()), (5., 3., 2.));
+    }
+
+    #[test]
+    fn test_analyze_inner_function_by_name() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let python_code = "def outer(x):\n    def inner(y):\n        if y:\n            return 1\n        return 0\n    return inner(x)\n\ndef inner(z):\n    return z\n";
+
+        let first = analyzer
+            .analyze_function(LANG::Python, python_code, "inner", FunctionMatch::First)
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].start_line, 2);
+        assert_eq!(first[0].end_line, 5);
+        assert_eq!(first[0].metrics.cyclomatic.cyclomatic_sum(), 2.0);
+        assert_eq!(first[0].metrics.nexits.exit_sum(), 2.0);
+
+        let all = analyzer
+            .analyze_function(LANG::Python, python_code, "inner", FunctionMatch::All)
+            .unwrap();
+        let lines: Vec<usize> = all.iter().map(|space| space.start_line).collect();
+        assert_eq!(lines, [2, 8]);
+
+        assert!(matches!(
+            analyzer.analyze_function(LANG::Python, python_code, "missing", FunctionMatch::All),
+            Err(AnalyzerError::FunctionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_mixed_line_endings() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let path = std::env::temp_dir().join("test_mixed_line_endings.py");
+        write_file(&path, b"def f(a):\r\n    b = a\n\r\n    return b\n").unwrap();
+
+        let mixed = analyzer.analyze_file(&path).unwrap();
+        assert_eq!(mixed.eol, EolStyle::Mixed);
+        assert_eq!(mixed.flags, [FileFlags::MixedLineEndings]);
+
+        let lf = analyzer
+            .analyze_language(
+                LANG::Python,
+                "def f(a):\n    b = a\n\n    return b\n",
+                AnalyzeOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(lf.eol, EolStyle::Lf);
+        assert!(lf.flags.is_empty());
+
+        let (mixed_loc, lf_loc) = (&mixed.metrics().loc, &lf.metrics().loc);
+        assert_eq!(mixed_loc.sloc(), lf_loc.sloc());
+        assert_eq!(mixed_loc.ploc(), lf_loc.ploc());
+        assert_eq!(mixed_loc.blank(), lf_loc.blank());
+        assert_eq!(mixed_loc.ploc(), 3.0);
+        assert_eq!(mixed_loc.blank(), 1.0);
+    }
+
+    #[test]
+    fn test_analyze_notebook() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let path = std::env::temp_dir().join("test_analyze_notebook.ipynb");
+        write_file(
+            &path,
+            br#"{
+                "metadata": {"kernelspec": {"language": "python"}},
+                "cells": [{"cell_type": "code", "source": ["def f(x):\n", "    return x\n"]}]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(analyzer.detect_language_from_path(&path), Some(LANG::Python));
+        let result = analyzer.analyze_file(&path).unwrap();
+        assert_eq!(result.language, Some(LANG::Python));
+        assert_eq!(result.root_space.spaces.len(), 1);
+        assert_eq!(result.root_space.spaces[0].name.as_deref(), Some("cell 0"));
+        assert_eq!(result.metrics().nom.functions(), 1.0);
+
+        write_file(&path, b"def f(x):\n    return x\n").unwrap();
+        assert!(matches!(
+            analyzer.analyze_file(&path),
+            Err(AnalyzerError::Io(err)) if err.kind() == std::io::ErrorKind::InvalidData
+        ));
+    }
+
+    #[test]
+    fn test_loc_fallback() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let path = std::env::temp_dir().join("test_loc_fallback.txt");
+        write_file(&path, b"First line\n\n  second line\n\n\nthird line\n").unwrap();
+
+        assert!(matches!(
+            analyzer.analyze_file(&path),
+            Err(AnalyzerError::UnsupportedLanguage(_))
+        ));
+
+        let options = AnalyzeOptions {
+            loc_fallback: true,
+            ..AnalyzeOptions::default()
+        };
+        let result = analyzer.analyze_file_with_options(&path, options).unwrap();
+        assert_eq!(result.language, None);
+        assert!(result.root_space.spaces.is_empty());
+        let loc = &result.metrics().loc;
+        assert_eq!(loc.sloc(), 6.0);
+        assert_eq!(loc.ploc(), 3.0);
+        assert_eq!(loc.blank(), 3.0);
+        assert_eq!(loc.cloc(), 0.0);
+    }
+
+    #[test]
+    fn test_loc_fallback_comment_syntax() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let path = std::env::temp_dir().join("test_loc_fallback.asm");
+        write_file(&path, b"; Entry point\nstart:\n    mov ax, 1 ; one\n\n; Done\n").unwrap();
+
+        let mut options = AnalyzeOptions {
+            loc_fallback: true,
+            ..AnalyzeOptions::default()
+        };
+        options.comment_syntaxes.insert(
+            "asm".to_string(),
+            CommentSyntax {
+                line: vec![";".to_string()],
+                ..CommentSyntax::default()
+            },
+        );
+        let result = analyzer.analyze_file_with_options(&path, options).unwrap();
+        let loc = &result.metrics().loc;
+        assert_eq!(loc.sloc(), 5.0);
+        assert_eq!(loc.ploc(), 2.0);
+        assert_eq!(loc.cloc(), 3.0);
+        assert_eq!(loc.blank(), 1.0);
+    }
+
+    #[test]
+    fn test_preproc_defines() {
+        let code = "#ifdef FOO
+int foo() { return 1; }
+#endif
+int bar() { return 2; }
+";
+        let functions = |define: &str| {
+            let mut pr = PreprocResults::default();
+            pr.defines.insert(define.to_string(), String::new());
+            let space = get_function_spaces(
+                &LANG::Cpp,
+                code.as_bytes().to_vec(),
+                &PathBuf::from("foo.cpp"),
+                Some(std::sync::Arc::new(pr)),
+            )
+            .unwrap();
+            space
+                .spaces
+                .iter()
+                .filter_map(|space| space.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(functions("FOO"), ["foo", "bar"]);
+        assert_eq!(functions("BAR"), ["bar"]);
+    }
+
+    #[test]
+    fn test_count_anonymous_functions() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "def f(items):
+    if items:
+        return sorted(items, key=lambda x: 1 if x else 0)
+";
+
+        let counted = analyzer
+            .analyze_language(LANG::Python, code, AnalyzeOptions::default())
+            .unwrap();
+        let excluded = analyzer
+            .analyze_language(
+                LANG::Python,
+                code,
+                AnalyzeOptions {
+                    count_anonymous: false,
+                    ..AnalyzeOptions::default()
+                },
+            )
+            .unwrap();
+
+        // The lambda is a space of its own only when anonymous functions count
+        assert_eq!(counted.root_space.spaces[0].spaces.len(), 1);
+        assert!(excluded.root_space.spaces[0].spaces.is_empty());
+
+        let (counted, excluded) = (&counted.metrics().cognitive, &excluded.metrics().cognitive);
+        assert!(counted.cognitive_sum() > 0.0);
+        assert_eq!(counted.cognitive_sum(), excluded.cognitive_sum());
+        assert_eq!(counted.cognitive_average(), counted.cognitive_sum() / 2.0);
+        assert_eq!(excluded.cognitive_average(), excluded.cognitive_sum());
+    }
+
+    #[test]
+    fn test_exclude_imports() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "import os
+import sys
+from collections import OrderedDict
+from pathlib import Path
+import json
+
+def f(x):
+    return os.path.join(x, json.dumps(sys.argv))
+";
+
+        let counted = analyzer
+            .analyze_language(LANG::Python, code, AnalyzeOptions::default())
+            .unwrap();
+        let excluded = analyzer
+            .analyze_language(
+                LANG::Python,
+                code,
+                AnalyzeOptions {
+                    exclude_imports: true,
+                    ..AnalyzeOptions::default()
+                },
+            )
+            .unwrap();
+
+        let (counted, excluded) = (&counted.metrics().loc, &excluded.metrics().loc);
+        assert_eq!(counted.sloc() - excluded.sloc(), 5.);
+        assert_eq!(counted.ploc() - excluded.ploc(), 5.);
+        assert_eq!(counted.lloc() - excluded.lloc(), 5.);
+        assert_eq!(counted.blank(), excluded.blank());
+    }
+
+    #[test]
+    fn test_custom_metrics() {
+        #[derive(Debug, Default)]
+        struct TodoCount(usize);
+
+        impl custom::CustomMetric for TodoCount {
+            fn name(&self) -> &str {
+                "todos"
+            }
+
+            fn visit(&mut self, node: &Node, code: &[u8]) {
+                if node.kind() == "identifier"
+                    && &code[node.start_byte()..node.end_byte()] == b"TODO"
+                {
+                    self.0 += 1;
+                }
+            }
+
+            fn finalize(self: Box<Self>) -> serde_json::Value {
+                self.0.into()
+            }
+        }
+
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "TODO = None\n\ndef f(a):\n    return a or TODO\n\nprint(TODO)\n";
+        let result = analyzer
+            .analyze_language(
+                LANG::Python,
+                code,
+                AnalyzeOptions {
+                    custom_metrics: vec![custom::CustomMetricFactory::new(|| {
+                        Box::new(TodoCount::default())
+                    })],
+                    ..AnalyzeOptions::default()
+                },
+            )
+            .expect("analysis should succeed");
+
+        let json = serde_json::to_value(&result.root_space).unwrap();
+        assert_eq!(json["custom"]["todos"], 3);
+        assert!(result.root_space.spaces[0].custom.is_empty());
+
+        let plain = analyzer
+            .analyze_language(LANG::Python, code, AnalyzeOptions::default())
+            .expect("analysis should succeed");
+        let json = serde_json::to_value(&plain.root_space).unwrap();
+        assert!(json.get("custom").is_none());
+    }
+
+    #[test]
+    fn test_class_granularity() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "class Parser:
+    def parse(self, text):
+        if text:
+            return text.split()
+        return []
+
+    def reset(self):
+        self.items = []
+
+def helper(x):
+    return x
+";
+        let analyze = |granularity| {
+            analyzer
+                .analyze_language(
+                    LANG::Python,
+                    code,
+                    AnalyzeOptions {
+                        granularity,
+                        ..AnalyzeOptions::default()
+                    },
+                )
+                .expect("analysis should succeed")
+                .root_space
+        };
+
+        let functions = analyze(Granularity::Function);
+        let classes = analyze(Granularity::Class);
+        assert_eq!(functions.spaces.len(), 2);
+        assert_eq!(functions.spaces[0].spaces.len(), 2);
+
+        assert_eq!(classes.spaces.len(), 1);
+        let class = &classes.spaces[0];
+        assert_eq!(class.name.as_deref(), Some("Parser"));
+        assert!(class.spaces.is_empty());
+        // The class itself, `parse` with a branch and `reset`
+        assert_eq!(class.metrics.cyclomatic.cyclomatic_sum(), 4.);
+        assert_eq!(
+            class.metrics.cyclomatic.cyclomatic_sum(),
+            functions.spaces[0].metrics.cyclomatic.cyclomatic_sum()
+        );
+
+        let file = analyze(Granularity::File);
+        assert!(file.spaces.is_empty());
+        assert_eq!(
+            file.metrics.cyclomatic.cyclomatic_sum(),
+            functions.metrics.cyclomatic.cyclomatic_sum()
+        );
+    }
+
+    #[test]
+    fn test_float_precision() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let result = analyzer
+            .analyze_language(
+                LANG::Python,
+                "a = b + c\n",
+                AnalyzeOptions {
+                    float_precision: Some(2),
+                    ..AnalyzeOptions::default()
+                },
+            )
+            .expect("analysis should succeed");
+
+        let full = result.metrics().halstead.volume();
+        let json = result.to_json().unwrap();
+        let volume = json["metrics"]["halstead"]["volume"].as_f64().unwrap();
+        assert_ne!(volume, full);
+        assert_eq!(volume, (full * 100.).round() / 100.);
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "@register(\"tokenizer\")
+class Parser:
+    @cached(size=10)
+    def parse(self, text):
+        if text:
+            return text.split()
+        return []
+";
+        let analyze = |anonymize| {
+            analyzer
+                .analyze_language(
+                    LANG::Python,
+                    code,
+                    AnalyzeOptions {
+                        anonymize,
+                        ..AnalyzeOptions::default()
+                    },
+                )
+                .expect("analysis should succeed")
+                .root_space
+        };
+
+        let plain = analyze(false);
+        let first = analyze(true);
+        let second = analyze(true);
+
+        let class = &first.spaces[0];
+        let method = &class.spaces[0];
+        assert_eq!(plain.spaces[0].spaces[0].name.as_deref(), Some("parse"));
+        assert!(class.name.as_deref().is_some_and(|name| name.starts_with("class_")));
+        assert!(method.name.as_deref().is_some_and(|name| name.starts_with("fn_")));
+        assert_eq!(method.name, second.spaces[0].spaces[0].name);
+        assert_eq!(first.name, plain.name);
+        assert_eq!(plain.spaces[0].spaces[0].decorators(), ["cached(size=10)"]);
+        assert!(class.decorators().is_empty());
+        assert!(method.decorators().is_empty());
+        assert_eq!(
+            serde_json::to_value(&first.metrics).unwrap(),
+            serde_json::to_value(&plain.metrics).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&method.metrics).unwrap(),
+            serde_json::to_value(&plain.spaces[0].spaces[0].metrics).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tree_sitter_language_query() {
+        use tree_sitter::{Query, QueryCursor, StreamingIterator};
+
+        let code = "fn add(a: i32, b: i32) -> i32 { a + b }\nstruct Point;\n";
+        let language = LANG::Rust.tree_sitter_language();
+        let query = Query::new(&language, "(function_item name: (identifier) @name)").unwrap();
+
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&query, tree.root_node(), code.as_bytes());
+        let mut names = Vec::new();
+        while let Some(found) = matches.next() {
+            for capture in found.captures {
+                names.push(capture.node.utf8_text(code.as_bytes()).unwrap());
+            }
+        }
+        assert_eq!(names, ["add"]);
+    }
+
+    #[test]
+    fn test_grammar_versions() {
+        let versions = grammar_versions();
+        assert_eq!(versions.len(), LANG::into_enum_iter().count());
+        for (lang, version) in versions {
+            assert!(version > 0, "{lang:?}");
+            assert_eq!(version, lang.grammar_version());
+        }
+    }
+
+    #[test]
+    fn test_function_spaces_from_tree() {
+        let code = "def f(a, b):\n    if a and b:\n        return 1\n    return 0\n";
+        let path = PathBuf::from("foo.py");
+        let options = MetricsOptions::default();
+
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&LANG::Python.tree_sitter_language()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+
+        let from_tree = get_function_spaces_from_tree(
+            &LANG::Python,
+            code.as_bytes().to_vec(),
+            tree.clone(),
+            &path,
+            &options,
+        )
+        .unwrap();
+        let parsed =
+            get_function_spaces_with_options(&LANG::Python, code.into(), &path, None, &options)
+                .unwrap();
+        assert_eq!(
+            serde_json::to_value(&from_tree).unwrap(),
+            serde_json::to_value(&parsed).unwrap()
+        );
+
+        let mismatch =
+            get_function_spaces_from_tree(&LANG::Rust, code.into(), tree, &path, &options);
+        assert!(matches!(mismatch, Err(AnalyzerError::LanguageMismatch(LANG::Rust))));
+    }
+
+    #[test]
+    fn test_per_file_timeout() {
+        use std::fmt::Write;
+        use std::time::Duration;
+
+        let analyzer = SingularityCodeAnalyzer::new();
+        let mut code = String::new();
+        for i in 0..2000 {
+            let body = "    if a and b:\n        return a + b\n    return b\n";
+            writeln!(code, "def f{i}(a, b):\n{body}").unwrap();
+        }
+
+        let timed_out = analyzer.analyze_language(
+            LANG::Python,
+            &code,
+            AnalyzeOptions {
+                per_file_timeout: Some(Duration::from_nanos(1)),
+                ..AnalyzeOptions::default()
+            },
+        );
+        assert!(matches!(timed_out, Err(AnalyzerError::Timeout(_))));
+
+        let analyzed = analyzer.analyze_language(
+            LANG::Python,
+            &code,
+            AnalyzeOptions {
+                per_file_timeout: Some(Duration::from_secs(600)),
+                ..AnalyzeOptions::default()
+            },
+        );
+        assert_eq!(analyzed.unwrap().root_space.spaces.len(), 2000);
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "def f(a):\n    return a +\n\ndef g(b):\n    return b\n";
+        let analyze = |error_policy| {
+            analyzer.analyze_language(
+                LANG::Python,
+                code,
+                AnalyzeOptions {
+                    error_policy,
+                    ..AnalyzeOptions::default()
+                },
+            )
+        };
+        let has_g = |result: &AnalyzerResult| {
+            result
+                .root_space
+                .spaces
+                .iter()
+                .any(|space| space.name.as_deref() == Some("g"))
+        };
+
+        let ignored = analyze(ErrorPolicy::Ignore).unwrap();
+        assert!(ignored.syntax_errors.is_none());
+        assert!(has_g(&ignored));
+
+        let annotated = analyze(ErrorPolicy::Annotate).unwrap();
+        let errors = annotated.syntax_errors.as_ref().unwrap();
+        assert!(errors.error_count > 0);
+        assert_eq!(errors.error_count, errors.spans.len());
+        let error_count = errors.error_count;
+        assert!(errors.spans.iter().all(|span| span.start_line <= 2));
+        // The metrics are still computed on the parseable code
+        assert!(has_g(&annotated));
+
+        let failed = analyze(ErrorPolicy::Fail);
+        assert!(matches!(
+            failed,
+            Err(AnalyzerError::ParseError { language: LANG::Python, ref errors })
+                if errors.len() == error_count
+        ));
+
+        let valid = analyzer
+            .analyze_language(
+                LANG::Python,
+                "def g(b):\n    return b\n",
+                AnalyzeOptions {
+                    error_policy: ErrorPolicy::Fail,
+                    ..AnalyzeOptions::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(valid.syntax_errors, Some(SyntaxErrors::default()));
+    }
+
+    #[test]
+    fn test_exclude_tests() {
+        let analyzer = SingularityCodeAnalyzer::new();
+        let code = "fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        if double(2) != 4 {
+            panic!();
+        }
+    }
+}
+";
+
+        let analyze = |exclude_tests| {
+            analyzer
+                .analyze_language(
+                    LANG::Rust,
+                    code,
+                    AnalyzeOptions {
+                        exclude_tests,
+                        ..AnalyzeOptions::default()
+                    },
+                )
+                .unwrap()
+        };
+        let counted = analyze(None);
+        let excluded = analyze(Some(TestFilter::default()));
+
+        assert_eq!(counted.metrics().nom.functions_sum(), 2.);
+        assert_eq!(excluded.metrics().nom.functions_sum(), 1.);
+        let names: Vec<_> = excluded
+            .root_space
+            .spaces
+            .iter()
+            .map(|space| space.name.as_deref())
+            .collect();
+        assert_eq!(names, [Some("double")]);
+        assert_eq!(excluded.metrics().cyclomatic.cyclomatic_sum(), 2.);
+        assert!(counted.metrics().cyclomatic.cyclomatic_sum() > 2.);
+    }
+
+    #[test]
+    fn test_analyze_untrusted_random_bytes() {
+        // Code fragments and invalid UTF-8 sequences, so the inputs reach
+        // the getters and the metrics beyond the error recovery
+        const FRAGMENTS: &[&[u8]] = &[
+            b"{", b"}", b"(", b")", b"[", b"]", b"\"", b"'", b"`", b"\n", b"    ", b";", b":",
+            b"if ", b"else", b"fn ", b"def ", b"class ", b"function ", b"return", b"&&", b"!",
+            b"/*", b"//", b"#", b"-- ", b"=>", b"->", b"x", b"0x", b"\xff", b"\xc3", b"\xe2\x82",
+        ];
+
+        let analyzer = SingularityCodeAnalyzer::new();
+        // Deterministic xorshift, so a failure can be reproduced
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for language in analyzer.supported_languages() {
+            for _ in 0..40 {
+                let mut source = Vec::new();
+                for _ in 0..next() % 64 {
+                    let value = next();
+                    if value % 3 == 0 {
+                        source.extend(value.to_le_bytes());
+                    } else {
+                        let index = usize::try_from(value % FRAGMENTS.len() as u64).unwrap();
+                        source.extend(FRAGMENTS[index]);
+                    }
+                }
+
+                let result =
+                    analyzer.analyze_untrusted(language, &source, AnalyzeOptions::default());
+                assert!(
+                    !matches!(result, Err(AnalyzerError::Panicked { .. })),
+                    "{language:?} panicked on {source:?}: {:?}",
+                    result.err()
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,75 @@
+//! Mapping of the lines of a code embedded in a host file, such as the
+//! scripts of an `HTML` page or the cells of a notebook, to the lines of
+//! the host file.
+
+use crate::spaces::FuncSpace;
+
+/// Maps the lines of an embedded code to the lines of its host file.
+///
+/// The embedded code is made of chunks of consecutive lines, e.g. one for
+/// each script of a page, and each chunk is added to the map, in order,
+/// with the line where it starts in the embedded code and the line where
+/// it starts in the host file. Lines start from `1`.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::LineOffsetMap;
+///
+/// let mut map = LineOffsetMap::new();
+/// // Two scripts of 3 lines, starting at the lines 10 and 20 of a page
+/// map.push(1, 10);
+/// map.push(4, 20);
+///
+/// assert_eq!(map.map_line(2), 11);
+/// assert_eq!(map.map_line(5), 21);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineOffsetMap {
+    // The first line of each chunk in the embedded code and in the host
+    // file, in increasing order
+    chunks: Vec<(usize, usize)>,
+}
+
+impl LineOffsetMap {
+    /// Creates an empty map, which keeps the lines unchanged.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chunk starting at `inner_line` in the embedded code and at
+    /// `outer_line` in the host file.
+    ///
+    /// Chunks must be added in the order of the embedded code: a chunk
+    /// starting before the last added one replaces the chunks after it.
+    pub fn push(&mut self, inner_line: usize, outer_line: usize) {
+        let index = self
+            .chunks
+            .partition_point(|(inner, _)| *inner < inner_line);
+        self.chunks.truncate(index);
+        self.chunks.push((inner_line, outer_line));
+    }
+
+    /// Returns the line of the host file for a line of the embedded code.
+    ///
+    /// The lines before the first chunk are returned unchanged.
+    #[must_use]
+    pub fn map_line(&self, line: usize) -> usize {
+        let index = self.chunks.partition_point(|(inner, _)| *inner <= line);
+        match index.checked_sub(1).map(|index| self.chunks[index]) {
+            Some((inner, outer)) => outer + (line - inner),
+            None => line,
+        }
+    }
+
+    /// Maps the lines of a space computed from the embedded code, and of
+    /// all its subspaces, to the lines of the host file.
+    pub fn map_space(&self, space: &mut FuncSpace) {
+        space.start_line = self.map_line(space.start_line);
+        space.end_line = self.map_line(space.end_line);
+        for subspace in &mut space.spaces {
+            self.map_space(subspace);
+        }
+    }
+}
@@ -107,6 +107,48 @@ macro_rules! mk_lang {
                 }
             }
 
+            /// Returns the tree-sitter grammar used to parse a language.
+            ///
+            /// The grammar is the same one used by the parsers of this crate,
+            /// so it can be used to build tree-sitter `Query`s and parsers
+            /// compatible with the crate.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use singularity_code_analysis::LANG;
+            ///
+            /// let query = tree_sitter::Query::new(
+            ///     &LANG::Rust.tree_sitter_language(),
+            ///     "(function_item name: (identifier) @name)",
+            /// );
+            /// assert!(query.is_ok());
+            /// ```
+            #[must_use]
+            pub fn tree_sitter_language(&self) -> Language {
+                self.get_ts_language()
+            }
+
+            /// Returns the ABI version of the tree-sitter grammar used to
+            /// parse a language, i.e. the version of the tree-sitter
+            /// generator which produced its parser.
+            ///
+            /// A grammar generated for another ABI may have renamed some
+            /// node kinds, so this version helps to diagnose the metrics
+            /// which change after a grammar update.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use singularity_code_analysis::LANG;
+            ///
+            /// assert!(LANG::Rust.grammar_version() > 0);
+            /// ```
+            #[must_use]
+            pub fn grammar_version(&self) -> usize {
+                self.get_ts_language().abi_version()
+            }
+
             // Returns a tree-sitter language.
             // This function is only used to construct a parser.
             pub(crate) fn get_ts_language(&self) -> Language {
@@ -186,13 +228,78 @@ macro_rules! mk_action {
             match lang {
                 $(
                     LANG::$camel => {
-                        let parser = $parser::new(source, &path, pr);
+                        let parser = $parser::try_new(source, &path, pr)?;
                         metrics(&parser, &path)
                     },
                 )*
             }
         }
 
+        /// Returns all function spaces data of a code, computed according
+        /// to the given [`MetricsOptions`].
+        ///
+        /// [`MetricsOptions`]: struct.MetricsOptions.html
+        #[inline]
+        pub fn get_function_spaces_with_options(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, options: &MetricsOptions) -> Option<FuncSpace> {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::try_new(source, &path, pr)?;
+                        metrics_with_options(&parser, &path, options)
+                    },
+                )*
+            }
+        }
+
+        /// Returns all function spaces data of a code, computed according
+        /// to the given [`MetricsOptions`], along with the given custom metrics.
+        ///
+        /// [`MetricsOptions`]: struct.MetricsOptions.html
+        #[inline]
+        pub fn get_function_spaces_with_custom(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, options: &MetricsOptions, custom: Vec<Box<dyn custom::CustomMetric>>) -> Option<FuncSpace> {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::try_new(source, &path, pr)?;
+                        metrics_with_custom(&parser, &path, options, custom)
+                    },
+                )*
+            }
+        }
+
+        /// Returns all function spaces data of a code already parsed by the
+        /// caller, computed according to the given [`MetricsOptions`].
+        ///
+        /// `tree` must have been parsed from `source` with the grammar
+        /// returned by [`LANG::tree_sitter_language`], so editor integrations
+        /// which already keep a tree up to date avoid parsing the code again.
+        /// The code is analyzed as is, so the `C++` macros are not expanded.
+        ///
+        /// # Errors
+        /// Returns [`AnalyzerError::LanguageMismatch`] if `tree` was parsed
+        /// with another grammar, or [`AnalyzerError::AnalysisFailed`] if the
+        /// metrics could not be computed.
+        ///
+        /// [`MetricsOptions`]: struct.MetricsOptions.html
+        pub fn get_function_spaces_from_tree(lang: &LANG, source: Vec<u8>, tree: tree_sitter::Tree, path: &Path, options: &MetricsOptions) -> Result<FuncSpace, AnalyzerError> {
+            if *tree.language() != lang.get_ts_language() {
+                return Err(AnalyzerError::LanguageMismatch(*lang));
+            }
+            let tree = Tree::from_ts_tree(tree);
+            let space = match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::from_tree(source, tree);
+                        metrics_with_options(&parser, &path, options)
+                    },
+                )*
+            };
+            space.ok_or_else(|| AnalyzerError::AnalysisFailed {
+                language: *lang,
+                reason: "metric pipeline returned no data".to_string(),
+            })
+        }
+
         /// Returns all operators and operands of each space in a code.
         ///
         /// # Examples
@@ -218,7 +325,7 @@ macro_rules! mk_action {
             match lang {
                 $(
                     LANG::$camel => {
-                        let parser = $parser::new(source, &path, pr);
+                        let parser = $parser::try_new(source, &path, pr)?;
                         operands_and_operators(&parser, &path)
                     },
                 )*
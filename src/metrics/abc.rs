@@ -1,12 +1,9 @@
 use std::{convert::TryFrom, fmt};
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 #[allow(clippy::wildcard_imports)]
-use crate::{checker::Checker, macros::implement_metric_trait, node::Node, *};
+use crate::{checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct, *};
 
 /// The `ABC` metric.
 ///
@@ -71,7 +68,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("abc", 13)?;
+        let mut st = MetricStruct::new(serializer, "abc", 13)?;
         st.serialize_field("assignments", &self.assignments_sum())?;
         st.serialize_field("branches", &self.branches_sum())?;
         st.serialize_field("conditions", &self.conditions_sum())?;
@@ -133,6 +130,12 @@ impl Stats {
         self.space_count += other.space_count;
     }
 
+    // Sets the number of spaces the averages are computed over
+    #[inline]
+    pub(crate) fn set_space_count(&mut self, space_count: usize) {
+        self.space_count = space_count;
+    }
+
     #[inline]
     #[allow(clippy::cast_precision_loss)]
     fn space_count_as_f64(&self) -> f64 {
@@ -288,9 +291,9 @@ fn java_inspect_container(container_node: &Node, conditions: &mut f64) {
     let mut node_kind = node.kind_id().into();
 
     // Initializes the flag to true if the container is known to contain a boolean value
-    let mut has_boolean_content = match node.parent().expect("TODO: Add context for why this shouldn't fail").kind_id().into() {
-        BinaryExpression | IfStatement | WhileStatement | DoStatement | ForStatement => true,
-        TernaryExpression => node
+    let mut has_boolean_content = match node.parent().map(|parent| Java::from(parent.kind_id())) {
+        Some(BinaryExpression | IfStatement | WhileStatement | DoStatement | ForStatement) => true,
+        Some(TernaryExpression) => node
             .previous_sibling()
             .is_none_or(|prev_node| !matches!(prev_node.kind_id().into(), QMARK | COLON)),
         _ => false,
@@ -302,7 +305,9 @@ fn java_inspect_container(container_node: &Node, conditions: &mut f64) {
         // The child node of index 0 contains the unary expression operator (we look for the `!` operator)
         let is_parenthesised_exp = matches!(node_kind, ParenthesizedExpression);
         let is_not_operator = matches!(node_kind, UnaryExpression)
-            && matches!(node.child(0).unwrap().kind_id().into(), BANG);
+            && node
+                .child(0)
+                .is_some_and(|operator| matches!(operator.kind_id().into(), BANG));
 
         // Stops the exploration if the node is neither
         // a parenthesized expression nor a `Not` operator
@@ -321,7 +326,11 @@ fn java_inspect_container(container_node: &Node, conditions: &mut f64) {
         // always store their expressions in the children nodes of index one
         // https://github.com/tree-sitter/tree-sitter-java/blob/master/src/grammar.json#L2472
         // https://github.com/tree-sitter/tree-sitter-java/blob/master/src/grammar.json#L2150
-        node = node.child(1).expect("TODO: Add context for why this shouldn't fail");
+        // The expression can be missing from malformed code
+        let Some(child) = node.child(1) else {
+            break;
+        };
+        node = child;
         node_kind = node.kind_id().into();
 
         // Stops the exploration when the content is found
@@ -386,7 +395,10 @@ implement_metric_trait!(
     GleamCode,
     LuaCode,
     GoCode,
-    CsharpCode
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
 );
 
 // Fitzpatrick, Jerry (1997). "Applying the ABC metric to C, C++ and Java". C++ Report.
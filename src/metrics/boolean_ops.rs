@@ -0,0 +1,280 @@
+use std::fmt;
+
+use serde::{ser::Serializer, Serialize};
+
+use crate::{
+    checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct,
+    CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode,
+    JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode,
+    RCode, RustCode, TsxCode, TypescriptCode,
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// The `BooleanOps` metric.
+///
+/// This metric counts the logical operators of a space, i.e. the
+/// conjunctions such as `&&`, `and` or `andalso`, the disjunctions such as
+/// `||`, `or` or `orelse`, and the negations such as `!` or `not`.
+/// Bitwise operators are not counted.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    and_ops: usize,
+    or_ops: usize,
+    not_ops: usize,
+    and_ops_sum: usize,
+    or_ops_sum: usize,
+    not_ops_sum: usize,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = MetricStruct::new(serializer, "boolean_ops", 4)?;
+        st.serialize_field("and", &self.and_ops_sum())?;
+        st.serialize_field("or", &self.or_ops_sum())?;
+        st.serialize_field("not", &self.not_ops_sum())?;
+        st.serialize_field("total", &self.total())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "and: {}, or: {}, not: {}, total: {}",
+            self.and_ops_sum(),
+            self.or_ops_sum(),
+            self.not_ops_sum(),
+            self.total()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `BooleanOps` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.and_ops_sum += other.and_ops_sum;
+        self.or_ops_sum += other.or_ops_sum;
+        self.not_ops_sum += other.not_ops_sum;
+    }
+
+    /// Returns the number of conjunctions in a space.
+    #[inline]
+    #[must_use]
+    pub fn and_ops(&self) -> f64 {
+        usize_to_f64(self.and_ops)
+    }
+
+    /// Returns the number of disjunctions in a space.
+    #[inline]
+    #[must_use]
+    pub fn or_ops(&self) -> f64 {
+        usize_to_f64(self.or_ops)
+    }
+
+    /// Returns the number of negations in a space.
+    #[inline]
+    #[must_use]
+    pub fn not_ops(&self) -> f64 {
+        usize_to_f64(self.not_ops)
+    }
+
+    /// Returns the number of logical operators in a space.
+    #[inline]
+    #[must_use]
+    pub fn boolean_ops(&self) -> f64 {
+        self.and_ops() + self.or_ops() + self.not_ops()
+    }
+
+    /// Returns the number of conjunctions in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn and_ops_sum(&self) -> f64 {
+        usize_to_f64(self.and_ops_sum)
+    }
+
+    /// Returns the number of disjunctions in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn or_ops_sum(&self) -> f64 {
+        usize_to_f64(self.or_ops_sum)
+    }
+
+    /// Returns the number of negations in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn not_ops_sum(&self) -> f64 {
+        usize_to_f64(self.not_ops_sum)
+    }
+
+    /// Returns the number of logical operators in a space and its
+    /// subspaces.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.and_ops_sum() + self.or_ops_sum() + self.not_ops_sum()
+    }
+
+    #[inline]
+    pub(crate) fn compute_sum(&mut self) {
+        self.and_ops_sum += self.and_ops;
+        self.or_ops_sum += self.or_ops;
+        self.not_ops_sum += self.not_ops;
+    }
+}
+
+pub trait BooleanOps
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats) {
+        count_operator(node, stats);
+    }
+}
+
+fn count_operator(node: &Node, stats: &mut Stats) {
+    let counter = match node.kind() {
+        "&&" | "and" | "andalso" => &mut stats.and_ops,
+        "||" | "or" | "orelse" => &mut stats.or_ops,
+        "!" | "not" => &mut stats.not_ops,
+        _ => return,
+    };
+    // The same tokens are also macro invocations, e.g. `println!`,
+    // non-null assertions, e.g. `x!`, or comparisons, e.g. `not in`
+    if node
+        .parent()
+        .is_some_and(|parent| is_logical_expression(parent.kind()))
+    {
+        *counter += 1;
+    }
+}
+
+// Checks if a node kind is an expression made of a logical operator in
+// one of the grammars
+fn is_logical_expression(kind: &str) -> bool {
+    const PARTS: &[&str] = &[
+        "binary",
+        "boolean_operator",
+        "conjunction",
+        "disjunction",
+        "let_chain",
+        "logical",
+        "not_operator",
+        "prefix",
+        "unary",
+    ];
+    !kind.contains("postfix") && PARTS.iter().any(|part| kind.contains(part))
+}
+
+impl BooleanOps for ErlangCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        // `!` sends a message
+        if node.kind() != "!" {
+            count_operator(node, stats);
+        }
+    }
+}
+
+implement_metric_trait!(
+    [BooleanOps],
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    RustCode,
+    CppCode,
+    PreprocCode,
+    CcommentCode,
+    JavaCode,
+    KotlinCode,
+    ElixirCode,
+    GleamCode,
+    LuaCode,
+    GoCode,
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tools::{check_func_space, check_metrics},
+        ParserEngineRust, PythonParser,
+    };
+
+    #[test]
+    fn rust_boolean_ops() {
+        check_metrics::<ParserEngineRust>(
+            "fn f(a: bool, b: bool, c: bool, d: bool) -> bool {
+                 a && b && c && d || a || b
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.boolean_ops,
+                    @r###"
+                    {
+                      "and": 3.0,
+                      "or": 2.0,
+                      "not": 0.0,
+                      "total": 5.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_macros_are_not_negations() {
+        check_func_space::<ParserEngineRust, _>(
+            "fn f(a: bool) {
+                 if !a {
+                     println!(\"{}\", a);
+                 }
+             }",
+            "foo.rs",
+            |space| {
+                let function = &space.spaces[0].metrics.boolean_ops;
+                assert_eq!(function.not_ops(), 1.);
+                assert_eq!(function.boolean_ops(), 1.);
+            },
+        );
+    }
+
+    #[test]
+    fn python_boolean_ops() {
+        check_metrics::<PythonParser>(
+            "def f(a, b, c):
+    if a and b and c or not a:
+        return a not in b
+    return b or c
+",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.boolean_ops,
+                    @r###"
+                    {
+                      "and": 2.0,
+                      "or": 2.0,
+                      "not": 1.0,
+                      "total": 5.0
+                    }"###
+                );
+            },
+        );
+    }
+}
@@ -1,15 +1,13 @@
 use std::{collections::HashMap, fmt};
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use num_traits::ToPrimitive;
 
 #[allow(clippy::wildcard_imports)]
 use crate::{
-    analysis_context::node_text_equals_any, checker::Checker, macros::implement_metric_trait, *,
+    analysis_context::node_text_equals_any, checker::Checker, macros::implement_metric_trait,
+    output::MetricStruct, *,
 };
 
 use crate::{
@@ -42,6 +40,18 @@ use crate::{
 //
 // Current status: Recursion does NOT contribute to cognitive complexity scores.
 
+/// Configuration of the `Cognitive Complexity` metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CognitiveConfig {
+    /// The highest nesting bonus added by a nested structure (default
+    /// `None`, unbounded).
+    ///
+    /// A structure nested at level `n` adds `1 + n`, so deeply nested code
+    /// quickly dominates the score. When set, each structure adds at most
+    /// `1 + max_nesting_bonus`.
+    pub max_nesting_bonus: Option<usize>,
+}
+
 /// The `Cognitive Complexity` metric.
 #[derive(Debug, Clone)]
 pub struct Stats {
@@ -52,6 +62,10 @@ pub struct Stats {
     nesting: usize,
     total_space_functions: usize,
     boolean_seq: BoolSequence,
+    macro_bodies: bool,
+    go_error_check_increment: Option<usize>,
+    rust_try_increment: usize,
+    config: CognitiveConfig,
 }
 
 impl Default for Stats {
@@ -64,6 +78,10 @@ impl Default for Stats {
             nesting: 0,
             total_space_functions: 1,
             boolean_seq: BoolSequence::default(),
+            macro_bodies: false,
+            go_error_check_increment: None,
+            rust_try_increment: 0,
+            config: CognitiveConfig::default(),
         }
     }
 }
@@ -73,7 +91,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("cognitive", 4)?;
+        let mut st = MetricStruct::new(serializer, "cognitive", 4)?;
         st.serialize_field("sum", &self.cognitive_sum())?;
         // For files with no functions, average should be null
         if self.total_space_functions <= 1 && self.structural_sum == 0 {
@@ -169,6 +187,30 @@ impl Stats {
     pub(crate) fn finalize(&mut self, total_space_functions: usize) {
         self.total_space_functions = total_space_functions;
     }
+
+    // Enables the heuristic analysis of the `Rust` macro arguments
+    #[inline]
+    pub(crate) fn set_macro_bodies(&mut self, macro_bodies: bool) {
+        self.macro_bodies = macro_bodies;
+    }
+
+    // Sets the increment of the `Go` error checks, `None` counting them as
+    // any other `if` statement
+    #[inline]
+    pub(crate) fn set_go_error_check_increment(&mut self, increment: Option<usize>) {
+        self.go_error_check_increment = increment;
+    }
+
+    // Sets the increment of each `Rust` `?` operator, `0` ignoring them
+    #[inline]
+    pub(crate) fn set_rust_try_increment(&mut self, increment: usize) {
+        self.rust_try_increment = increment;
+    }
+
+    #[inline]
+    pub(crate) fn set_config(&mut self, config: CognitiveConfig) {
+        self.config = config;
+    }
 }
 
 pub trait Cognitive
@@ -229,7 +271,11 @@ impl BoolSequence {
 
 #[inline]
 fn increment(stats: &mut Stats) {
-    stats.structural += stats.nesting + 1;
+    let nesting = match stats.config.max_nesting_bonus {
+        Some(max_nesting_bonus) => stats.nesting.min(max_nesting_bonus),
+        None => stats.nesting,
+    };
+    stats.structural += nesting + 1;
 }
 
 #[inline]
@@ -237,6 +283,18 @@ fn increment_by_one(stats: &mut Stats) {
     stats.structural += 1;
 }
 
+// Counts the boolean operator of a binary expression whose operator is
+// its `operator` field, a sequence of the same operator counting once
+fn compute_boolean_operator(node: &Node, stats: &mut Stats, operators: &[&str]) {
+    if let Some(operator) = node.child_by_field_name("operator")
+        && operators.contains(&operator.kind())
+    {
+        stats.structural = stats
+            .boolean_seq
+            .eval_based_on_prev(operator.kind_id(), stats.structural);
+    }
+}
+
 fn get_nesting_from_map(
     node: &Node,
     nesting_map: &HashMap<usize, (usize, usize, usize)>,
@@ -272,6 +330,63 @@ fn increase_nesting(stats: &mut Stats, nesting: &mut usize, depth: usize, lambda
     *nesting += 1;
 }
 
+// Rust macros whose arguments are usually plain conditions
+const RUST_CONDITION_MACROS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "debug_assert",
+    "debug_assert_eq",
+    "debug_assert_ne",
+    "if_chain",
+    "matches",
+];
+
+// Counts the `if`, `&&` and `||` tokens in the arguments of a known macro.
+//
+// This is a heuristic: the arguments are an unparsed token tree, so each
+// `if` is counted as a flat increment and the sequences of boolean
+// operators are taken in source order, regardless of their precedence.
+fn compute_rust_macro_body(node: &Node, stats: &mut Stats) {
+    let is_known = node.child_by_field_name("macro").is_some_and(|name| {
+        let name = if name.kind_id() == Rust::ScopedIdentifier {
+            name.child_by_field_name("name")
+        } else {
+            Some(name)
+        };
+        name.is_some_and(|name| node_text_equals_any(&name, RUST_CONDITION_MACROS))
+    });
+    if !is_known {
+        return;
+    }
+    let Some(body) = node.children().find(|child| child.kind() == "token_tree") else {
+        return;
+    };
+
+    stats.boolean_seq.reset();
+    let mut stack = vec![body];
+    while let Some(token) = stack.pop() {
+        if token.child_count() > 0 {
+            let children: Vec<_> = token.children().collect();
+            stack.extend(children.into_iter().rev());
+        } else if node_text_equals_any(&token, &["if"]) {
+            increment_by_one(stats);
+            stats.boolean_seq.reset();
+        } else if node_text_equals_any(&token, &["&&"]) {
+            stats.structural = stats
+                .boolean_seq
+                .eval_based_on_prev(Rust::AMPAMP as u16, stats.structural);
+        } else if node_text_equals_any(&token, &["||"]) {
+            stats.structural = stats
+                .boolean_seq
+                .eval_based_on_prev(Rust::PIPEPIPE as u16, stats.structural);
+        } else if node_text_equals_any(&token, &[","]) {
+            stats.boolean_seq.reset();
+        }
+    }
+    stats.boolean_seq.reset();
+}
+
 fn elixir_call_matches(node: &Node, keywords: &[&str]) -> bool {
     if node.kind_id() != Elixir::Call {
         return false;
@@ -294,6 +409,7 @@ impl Cognitive for PythonCode {
             Python::IfStatement
             | Python::ForStatement
             | Python::WhileStatement
+            | Python::MatchStatement
             | Python::ConditionalExpression => {
                 increase_nesting(stats, &mut nesting, depth, lambda);
             }
@@ -370,10 +486,15 @@ impl Cognitive for RustCode {
         // To properly account for complexity in macros, we would need to:
         // 1. Expand macros during parsing (requires full Rust compiler integration)
         // 2. Analyze the expanded code rather than the macro invocation
-        // Current behavior: Macro invocations are ignored, only explicit code is analyzed.
+        // Current behavior: Macro invocations are ignored, only explicit code is analyzed,
+        // unless `MetricsOptions::rust_macro_bodies` enables a token-based heuristic for
+        // a few well-known macros.
         let (mut nesting, mut depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
         match node.kind_id().into() {
+            Rust::MacroInvocation if stats.macro_bodies => {
+                compute_rust_macro_body(node, stats);
+            }
             Rust::IfExpression => {
                 // Check if a node is not an else-if
                 if !Self::is_else_if(node) {
@@ -383,8 +504,24 @@ impl Cognitive for RustCode {
             Rust::ForExpression | Rust::WhileExpression | Rust::MatchExpression => {
                 increase_nesting(stats,&mut nesting, depth, lambda);
             }
+            // `let ... else` is a branch: its `else` block is nested
+            Rust::LetDeclaration if node.child_by_field_name("alternative").is_some() => {
+                increase_nesting(stats, &mut nesting, depth, lambda);
+            }
             Rust::Else /*else-if also */ => {
-                increment_by_one(stats);
+                // The `else` of a `let ... else` is counted with its declaration
+                if node.parent().is_none_or(|parent| parent.kind() != "let_declaration") {
+                    increment_by_one(stats);
+                }
+            }
+            // `if let A = a && let B = b` chains are a sequence of `&&`
+            Rust::LetChain => {
+                compute_booleans::<language_rust::Rust>(
+                    node,
+                    stats,
+                    Rust::AMPAMP,
+                    Rust::PIPEPIPE,
+                );
             }
             Rust::BreakExpression | Rust::ContinueExpression => {
                 if let Some(label_child) = node.child(1) {
@@ -393,6 +530,14 @@ impl Cognitive for RustCode {
                     }
                 }
             }
+            // Error propagation is a flat increment, regardless of nesting
+            Rust::TryExpression => {
+                stats.structural += stats.rust_try_increment;
+            }
+            Rust::MatchCodePattern => {
+                // Each arm guard starts a new sequence of boolean operators
+                stats.boolean_seq.reset();
+            }
             Rust::UnaryExpression => {
                 stats.boolean_seq.not_operator(node.kind_id());
             }
@@ -674,7 +819,7 @@ impl Cognitive for GleamCode {
                 increase_nesting(stats, &mut nesting, depth, lambda);
             }
             Gleam::CaseClause => {
-                if let Some(prev) = node.previous_named_sibling() && Into::<Gleam>::into(prev.kind_id()) == Gleam::CaseClause {
+                if node.named_sibling_index() > 0 {
                     increment(stats);
                 } else {
                     increment_by_one(stats);
@@ -701,36 +846,38 @@ impl Cognitive for KotlinCode {
     ) {
         let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
+        // Each statement and each condition of an `if`/`else if` chain start
+        // a new sequence of boolean operators
+        if node.kind() == "if_expression"
+            || node.parent().is_some_and(|parent| {
+                matches!(parent.kind(), "block" | "source_file" | "lambda_literal")
+            })
+        {
+            stats.boolean_seq.reset();
+        }
+
         match node.kind() {
             "if_expression" => {
-                // Check if this is part of an else-if chain
-                if let Some(parent) = node.parent() {
-                    if parent.kind() == "if_expression" {
-                        // This is an else-if, only increment by one
-                        increment_by_one(stats);
-                    } else {
-                        increase_nesting(stats, &mut nesting, depth, lambda);
-                    }
-                } else {
+                // Check if a node is not an else-if
+                if !Self::is_else_if(node) {
                     increase_nesting(stats, &mut nesting, depth, lambda);
                 }
             }
+            "else" => {
+                // else-if also, the `else` of a `when` entry is not a branch
+                if node.parent().is_some_and(|parent| parent.kind() == "if_expression") {
+                    increment_by_one(stats);
+                }
+            }
             "when_expression" | "for_statement" | "while_statement" | "do_while_statement"
             | "try_expression" | "catch_block" => {
                 increase_nesting(stats, &mut nesting, depth, lambda);
             }
+            "unary_expression" => {
+                stats.boolean_seq.not_operator(node.kind_id());
+            }
             "binary_expression" => {
-                // Handle && and || operators
-                if let Some(operator) = node.child_by_field_name("operator") {
-                    match operator.kind() {
-                        "&&" | "||" => {
-                            stats.boolean_seq.reset();
-                            // In Kotlin, just increment by 1 for boolean operators
-                            increment_by_one(stats);
-                        }
-                        _ => {}
-                    }
-                }
+                compute_boolean_operator(node, stats, &["&&", "||"]);
             }
             "lambda_literal" | "anonymous_function" => {
                 lambda += 1;
@@ -752,6 +899,11 @@ impl Cognitive for LuaCode {
     ) {
         let (mut nesting, depth, lambda) = get_nesting_from_map(node, nesting_map);
 
+        // Each statement starts a new sequence of boolean operators
+        if node.kind().ends_with("_statement") || node.kind() == "variable_declaration" {
+            stats.boolean_seq.reset();
+        }
+
         match node.kind() {
             "if_statement" | "while_statement" | "repeat_statement" | "for_statement" => {
                 increase_nesting(stats, &mut nesting, depth, lambda);
@@ -759,17 +911,12 @@ impl Cognitive for LuaCode {
             "elseif_statement" | "else_statement" => {
                 increment_by_one(stats);
             }
+            "unary_expression" => {
+                stats.boolean_seq.not_operator(node.kind_id());
+            }
             "binary_expression" => {
                 // Lua uses 'and'/'or' for boolean operators
-                if let Some(operator) = node.child_by_field_name("operator") {
-                    match operator.kind() {
-                        "and" | "or" => {
-                            stats.boolean_seq.reset();
-                            increment_by_one(stats);
-                        }
-                        _ => {}
-                    }
-                }
+                compute_boolean_operator(node, stats, &["and", "or"]);
             }
             "function_declaration" | "function_definition" => {
                 nesting = 0;
@@ -780,6 +927,44 @@ impl Cognitive for LuaCode {
     }
 }
 
+// Checks if a `Go` if statement is an error check, i.e. it compares an
+// identifier to `nil`, has no else branch and its body only returns, as in
+// `if err != nil { return err }`
+fn is_go_error_check(node: &Node) -> bool {
+    let nil_check = node.child_by_field_name("condition").is_some_and(|condition| {
+        condition.kind() == "binary_expression"
+            && condition
+                .child_by_field_name("operator")
+                .is_some_and(|operator| operator.kind() == "!=")
+            && condition
+                .child_by_field_name("left")
+                .is_some_and(|left| left.kind() == "identifier")
+            && condition
+                .child_by_field_name("right")
+                .is_some_and(|right| right.kind() == "nil")
+    });
+    if !nil_check || node.child_by_field_name("alternative").is_some() {
+        return false;
+    }
+
+    let Some(block) = node.child_by_field_name("consequence") else {
+        return false;
+    };
+    let statements = |parent: Node| -> Vec<_> {
+        parent
+            .children()
+            .filter(|child| child.is_named() && child.kind() != "comment")
+            .collect()
+    };
+    let mut body = statements(block);
+    if let [list] = body[..] {
+        if list.kind() == "statement_list" {
+            body = statements(list);
+        }
+    }
+    matches!(body[..], [statement] if statement.kind() == "return_statement")
+}
+
 impl Cognitive for GoCode {
     fn compute(
         node: &Node,
@@ -788,28 +973,34 @@ impl Cognitive for GoCode {
     ) {
         let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
+        // Each statement starts a new sequence of boolean operators
+        if node.kind().ends_with("_statement") || node.kind() == "short_var_declaration" {
+            stats.boolean_seq.reset();
+        }
+
         match node.kind() {
-            "if_statement"
-            | "for_statement"
-            | "switch_statement"
-            | "select_statement"
-            | "type_switch_statement" => {
+            "if_statement" => {
+                let guard_increment = stats
+                    .go_error_check_increment
+                    .filter(|_| is_go_error_check(node));
+                if let Some(increment) = guard_increment {
+                    stats.structural += increment;
+                    nesting += 1;
+                } else {
+                    increase_nesting(stats, &mut nesting, depth, lambda);
+                }
+            }
+            "for_statement" | "switch_statement" | "select_statement" | "type_switch_statement" => {
                 increase_nesting(stats, &mut nesting, depth, lambda);
             }
             "func_literal" => {
                 lambda += 1;
             }
+            "unary_expression" => {
+                stats.boolean_seq.not_operator(node.kind_id());
+            }
             "binary_expression" => {
-                // Handle && and || operators
-                if let Some(operator) = node.child_by_field_name("operator") {
-                    match operator.kind() {
-                        "&&" | "||" => {
-                            stats.boolean_seq.reset();
-                            increment_by_one(stats);
-                        }
-                        _ => {}
-                    }
-                }
+                compute_boolean_operator(node, stats, &["&&", "||"]);
             }
             "function_declaration" | "method_declaration" => {
                 nesting = 0;
@@ -828,6 +1019,11 @@ impl Cognitive for CsharpCode {
     ) {
         let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
+        // Each statement starts a new sequence of boolean operators
+        if node.kind().ends_with("_statement") {
+            stats.boolean_seq.reset();
+        }
+
         match node.kind() {
             "if_statement" => {
                 // Check if this is an else-if
@@ -852,17 +1048,11 @@ impl Cognitive for CsharpCode {
                 // Ternary operator in C#
                 increase_nesting(stats, &mut nesting, depth, lambda);
             }
+            "prefix_unary_expression" => {
+                stats.boolean_seq.not_operator(node.kind_id());
+            }
             "binary_expression" => {
-                // Handle && and || operators
-                if let Some(operator) = node.child_by_field_name("operator") {
-                    match operator.kind() {
-                        "&&" | "||" => {
-                            stats.boolean_seq.reset();
-                            increment_by_one(stats);
-                        }
-                        _ => {}
-                    }
-                }
+                compute_boolean_operator(node, stats, &["&&", "||"]);
             }
             "lambda_expression" | "anonymous_method_expression" | "anonymous_function" => {
                 lambda += 1;
@@ -876,9 +1066,114 @@ impl Cognitive for CsharpCode {
     }
 }
 
+impl Cognitive for RCode {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+    ) {
+        let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
+
+        // Each expression of a body starts a new sequence of boolean operators
+        if node.kind().ends_with("_statement")
+            || node
+                .parent()
+                .is_some_and(|parent| matches!(parent.kind(), "program" | "braced_expression"))
+        {
+            stats.boolean_seq.reset();
+        }
+
+        match node.kind() {
+            "if_statement" => {
+                if !Self::is_else_if(node) {
+                    increase_nesting(stats, &mut nesting, depth, lambda);
+                }
+            }
+            "for_statement" | "while_statement" | "repeat_statement" => {
+                increase_nesting(stats, &mut nesting, depth, lambda);
+            }
+            "else" /* else-if also */ => {
+                increment_by_one(stats);
+            }
+            "unary_operator" => {
+                if node
+                    .child_by_field_name("operator")
+                    .is_some_and(|operator| operator.kind() == "!")
+                {
+                    stats.boolean_seq.not_operator(node.kind_id());
+                }
+            }
+            "binary_operator" => {
+                // R has both scalar (`&&`, `||`) and vectorised (`&`, `|`) boolean operators
+                compute_boolean_operator(node, stats, &["&&", "||", "&", "|"]);
+            }
+            "function_definition" => {
+                if Self::is_closure(node) {
+                    lambda += 1;
+                } else {
+                    nesting = 0;
+                }
+            }
+            _ => {}
+        }
+        nesting_map.insert(node.id(), (nesting, depth, lambda));
+    }
+}
+
+impl Cognitive for PerlCode {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+    ) {
+        let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
+
+        // Each statement starts a new sequence of boolean operators
+        if node.kind().ends_with("_statement") {
+            stats.boolean_seq.reset();
+        }
+
+        match node.kind() {
+            "conditional_statement" | "loop_statement" | "for_statement"
+            | "cstyle_for_statement" => {
+                increase_nesting(stats, &mut nesting, depth, lambda);
+            }
+            "postfix_conditional_expression" | "postfix_loop_expression"
+            | "postfix_for_expression" => {
+                // A postfix statement modifier is a branch without a block,
+                // so it does not increase the nesting of its operands
+                stats.nesting = nesting + depth + lambda;
+                increment(stats);
+            }
+            "elsif" | "else" if node.is_named() => {
+                increment_by_one(stats);
+            }
+            "!" | "not" if !node.is_named() => {
+                stats.boolean_seq.not_operator(node.kind_id());
+            }
+            // The operators are matched as tokens, whatever the precedence of
+            // the expression they belong to
+            "&&" | "||" | "and" | "or" if !node.is_named() => {
+                stats.structural = stats
+                    .boolean_seq
+                    .eval_based_on_prev(node.kind_id(), stats.structural);
+            }
+            "anonymous_subroutine_expression" => {
+                lambda += 1;
+            }
+            "subroutine_declaration_statement" | "method_declaration_statement" => {
+                nesting = 0;
+            }
+            _ => {}
+        }
+        nesting_map.insert(node.id(), (nesting, depth, lambda));
+    }
+}
+
 // PreprocCode and CcommentCode are for preprocessor directives and comments
-// They don't have control flow, so empty implementations are appropriate
-implement_metric_trait!(Cognitive, PreprocCode, CcommentCode);
+// They don't have control flow, so empty implementations are appropriate,
+// as for GraphQL schemas
+implement_metric_trait!(Cognitive, PreprocCode, CcommentCode, GraphqlCode);
 
 #[cfg(test)]
 mod tests {
@@ -974,6 +1269,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn python_match_statement() {
+        check_metrics::<PythonParser>(
+            "def f(command, force):
+                match command:  # +1
+                    case \"start\":
+                        if force:  # +2 (nesting = 1)
+                            return 1
+                    case _:
+                        return 0",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.cognitive,
+                    @r#"
+                {
+                  "sum": 3.0,
+                  "average": 3.0,
+                  "min": 0.0,
+                  "max": 3.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
     #[test]
     fn python_expression_statement() {
         // Boolean expressions containing `And` and `Or` operators were not
@@ -2118,6 +2440,206 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rust_let_else() {
+        check_metrics::<ParserEngineRust>(
+            "fn first(opt: Option<u32>, flag: bool) -> u32 {
+                 let Some(x) = opt else { // +1
+                     return 0;
+                 };
+                 if flag { // +1
+                     let Some(y) = x.checked_add(1) else { // +2 (nesting = 1)
+                         return x;
+                     };
+                     return y;
+                 }
+                 x
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.cognitive,
+                    @r###"
+                    {
+                      "sum": 4.0,
+                      "average": 4.0,
+                      "min": 0.0,
+                      "max": 4.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_if_let_chains() {
+        check_metrics::<ParserEngineRust>(
+            "fn pick(a: Option<u32>, b: Option<u32>) -> u32 {
+                 if let Some(x) = a && let Some(y) = b && x > y { // +2 (+1 &&)
+                     x
+                 } else if let Some(x) = a { // +1
+                     x
+                 } else if let Some(y) = b { // +1
+                     y
+                 } else { // +1
+                     0
+                 }
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.cognitive,
+                    @r###"
+                    {
+                      "sum": 5.0,
+                      "average": 5.0,
+                      "min": 0.0,
+                      "max": 5.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_match_guards() {
+        check_metrics::<ParserEngineRust>(
+            "fn classify(x: i32, y: i32) -> i32 {
+                 match x { // +1
+                     0 if y > 0 && y < 10 => 1, // +1
+                     1 if y > 10 && y < 100 => 2, // +1
+                     _ => 3,
+                 }
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.cognitive,
+                    @r###"
+                    {
+                      "sum": 3.0,
+                      "average": 3.0,
+                      "min": 0.0,
+                      "max": 3.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_macro_bodies() {
+        let path = std::path::Path::new("foo.rs");
+        let source = "fn f(x: E, cond: bool, other: bool) -> bool {
+                          matches!(x, E::A if cond && other) // +2 under the opt-in
+                      }";
+        let parser = ParserEngineRust::new(source.as_bytes().to_vec(), path, None);
+
+        let ignored = metrics(&parser, path).unwrap();
+        assert_eq!(ignored.metrics.cognitive.cognitive_sum(), 0.);
+
+        let options = MetricsOptions {
+            rust_macro_bodies: true,
+            ..MetricsOptions::default()
+        };
+        let counted = metrics_with_options(&parser, path, &options).unwrap();
+        insta::assert_json_snapshot!(
+            counted.metrics.cognitive,
+            @r###"
+            {
+              "sum": 2.0,
+              "average": 2.0,
+              "min": 0.0,
+              "max": 2.0
+            }"###
+        );
+    }
+
+    #[test]
+    fn go_error_checks() {
+        let path = std::path::Path::new("foo.go");
+        let source = "func load(path string) (*Config, error) {
+                          data, err := os.ReadFile(path)
+                          if err != nil { // +1, error check
+                              return nil, err
+                          }
+                          var config Config
+                          if err := json.Unmarshal(data, &config); err != nil { // +1, error check
+                              return nil, err
+                          }
+                          if config.Debug { // +1
+                              log.Println(\"debug\")
+                          }
+                          if err = config.Validate(); err != nil { // +1, error check
+                              // The configuration is invalid
+                              return nil, err
+                          }
+                          return &config, nil
+                      }";
+        let parser = GoParser::new(source.as_bytes().to_vec(), path, None);
+
+        let default = metrics(&parser, path).unwrap();
+        assert_eq!(default.metrics.cognitive.cognitive_sum(), 4.);
+
+        let options = MetricsOptions {
+            go_error_check_increment: Some(0),
+            ..MetricsOptions::default()
+        };
+        let reduced = metrics_with_options(&parser, path, &options).unwrap();
+        assert_eq!(reduced.metrics.cognitive.cognitive_sum(), 1.);
+    }
+
+    #[test]
+    fn rust_try_operators() {
+        let path = std::path::Path::new("foo.rs");
+        let source = "fn load(path: &Path) -> Result<Config, Error> {
+                          let data = fs::read_to_string(path)?; // +2 when enabled
+                          let config: Config = toml::from_str(&data)?; // +2 when enabled
+                          if config.debug { // +1
+                              config.validate()?; // +2 when enabled
+                          }
+                          Ok(config)
+                      }";
+        let parser = ParserEngineRust::new(source.as_bytes().to_vec(), path, None);
+
+        let default = metrics(&parser, path).unwrap();
+        assert_eq!(default.metrics.cognitive.cognitive_sum(), 1.);
+
+        let options = MetricsOptions {
+            rust_try_increment: 2,
+            ..MetricsOptions::default()
+        };
+        let increased = metrics_with_options(&parser, path, &options).unwrap();
+        assert_eq!(increased.metrics.cognitive.cognitive_sum(), 7.);
+    }
+
+    #[test]
+    fn python_capped_nesting_bonus() {
+        let path = std::path::Path::new("foo.py");
+        let source = "def f(a):
+    if a:  # +1
+        for b in a:  # +2
+            while b:  # +3
+                if b:  # +4 (+3 when capped)
+                    for c in b:  # +5 (+3 when capped)
+                        if c:  # +6 (+3 when capped)
+                            return c
+";
+        let parser = PythonParser::new(source.as_bytes().to_vec(), path, None);
+
+        let unbounded = metrics(&parser, path).unwrap();
+        assert_eq!(unbounded.metrics.cognitive.cognitive_sum(), 21.);
+
+        let options = MetricsOptions {
+            cognitive: CognitiveConfig {
+                max_nesting_bonus: Some(2),
+            },
+            ..MetricsOptions::default()
+        };
+        let capped = metrics_with_options(&parser, path, &options).unwrap();
+        assert_eq!(capped.metrics.cognitive.cognitive_sum(), 15.);
+    }
+
     #[test]
     fn typescript_if_else_if_else() {
         check_metrics::<TypescriptParser>(
@@ -2530,6 +3052,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn csharp_boolean_sequences_across_statements() {
+        check_metrics::<CsharpParser>(
+            "class X {
+                public bool f(bool a, bool b, bool c, bool d) {
+                    if (a && b && c) return true; // +2 (+1 &&)
+                    if (c && d) return false; // +2 (+1 &&)
+                    return a || b; // +1
+                }
+            }",
+            "foo.cs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.cognitive,
+                    @r###"
+                    {
+                      "sum": 5.0,
+                      "average": 5.0,
+                      "min": 0.0,
+                      "max": 5.0
+                    }"###
+                );
+            },
+        );
+    }
+
     #[test]
     fn csharp_no_cognitive() {
         check_metrics::<CsharpParser>(
@@ -2582,6 +3130,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn kotlin_else_if() {
+        check_metrics::<KotlinParser>(
+            "fun sign(a: Int, b: Boolean): Int {
+                if (a > 0) {            // +1
+                    return 1
+                } else if (a < 0) {     // +1
+                    if (b) {            // +2 (nesting = 1)
+                        return -2
+                    }
+                    return -1
+                } else {                // +1
+                    return 0
+                }
+            }",
+            "foo.kt",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 5.0,
+                  "average": 5.0,
+                  "min": 0.0,
+                  "max": 5.0
+                }
+                "#);
+            },
+        );
+    }
+
     #[test]
     fn kotlin_switch_expression() {
         check_metrics::<KotlinParser>(
@@ -3034,4 +3611,167 @@ mod tests {
             },
         );
     }
+
+    // ========== R LANGUAGE TESTS ==========
+
+    #[test]
+    fn r_simple_function() {
+        check_metrics::<RParser>(
+            "f <- function(a, b) {
+                if (a && b) { # +2 (+1 &&)
+                    return(1)
+                }
+                if (a || b) { # +2 (+1 ||)
+                    return(2)
+                }
+                0
+            }",
+            "foo.R",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 4.0,
+                  "average": 4.0,
+                  "min": 0.0,
+                  "max": 4.0
+                }
+                "#);
+            },
+        );
+    }
+
+    #[test]
+    fn r_nested_loops() {
+        check_metrics::<RParser>(
+            "g <- function(n) {
+                for (i in seq_len(n)) { # +1
+                    if (i > 2) {        # +2 (nesting = 1)
+                        next
+                    } else {            # +1
+                        repeat {        # +3 (nesting = 2)
+                            break
+                        }
+                    }
+                }
+            }",
+            "foo.R",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 7.0,
+                  "average": 7.0,
+                  "min": 0.0,
+                  "max": 7.0
+                }
+                "#);
+            },
+        );
+    }
+
+    #[test]
+    fn r_boolean_sequences() {
+        check_metrics::<RParser>(
+            "h <- function(a, b, c) {
+                if (a && b && c) { # +2 (+1 sequence of &&)
+                    return(1)
+                }
+                if (a && b || c) { # +3 (+1 &&, +1 ||)
+                    return(2)
+                }
+                0
+            }",
+            "foo.R",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 5.0,
+                  "average": 5.0,
+                  "min": 0.0,
+                  "max": 5.0
+                }
+                "#);
+            },
+        );
+    }
+
+    // ========== PERL LANGUAGE TESTS ==========
+
+    #[test]
+    fn perl_conditional_chain() {
+        check_metrics::<PerlParser>(
+            "sub classify {
+                my ($n) = @_;
+                if ($n < 0) { # +1
+                    return 'negative';
+                } elsif ($n == 0) { # +1
+                    return 'zero';
+                } else { # +1
+                    while ($n > 100) { # +2 (nesting = 1)
+                        $n /= 10;
+                    }
+                }
+                return 'positive';
+            }",
+            "foo.pl",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 5.0,
+                  "average": 5.0,
+                  "min": 0.0,
+                  "max": 5.0
+                }
+                "#);
+            },
+        );
+    }
+
+    #[test]
+    fn perl_postfix_conditionals() {
+        check_metrics::<PerlParser>(
+            "sub process {
+                my ($x) = @_;
+                return unless defined $x; # +1
+                foreach my $i (1 .. $x) { # +1
+                    next if $i % 2; # +2 (nesting = 1)
+                    print $i if $i > 3 && $x; # +3 (+2 nesting, +1 &&)
+                }
+            }",
+            "foo.pl",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 7.0,
+                  "average": 7.0,
+                  "min": 0.0,
+                  "max": 7.0
+                }
+                "#);
+            },
+        );
+    }
+
+    #[test]
+    fn perl_boolean_sequences() {
+        check_metrics::<PerlParser>(
+            "sub check {
+                my ($x, $y, $z) = @_;
+                return 1 if $x && $y && $z; # +2 (+1 sequence of &&)
+                return 2 if $x && $y || $z; # +3 (+1 &&, +1 ||)
+                return 3 unless $x or $y; # +2 (+1 or)
+                return 0;
+            }",
+            "foo.pl",
+            |metric| {
+                insta::assert_json_snapshot!(metric.cognitive, @r#"
+                {
+                  "sum": 7.0,
+                  "average": 7.0,
+                  "min": 0.0,
+                  "max": 7.0
+                }
+                "#);
+            },
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use std::{fmt, sync::Arc};
+
+use crate::node::Node;
+
+/// A metric defined outside of this crate.
+///
+/// Custom metrics are passed to the analyzer through
+/// [`AnalyzeOptions::custom_metrics`](crate::AnalyzeOptions::custom_metrics),
+/// as [`CustomMetricFactory`] creating a new instance for each analysis.
+/// Each metric visits all the nodes of a code during the same traversal
+/// which computes the built-in metrics, and its result is then stored in
+/// the `custom` map of the root space, under the metric name.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{custom::CustomMetric, Node};
+///
+/// #[derive(Debug, Default)]
+/// struct Todos(usize);
+///
+/// impl CustomMetric for Todos {
+///     fn name(&self) -> &str {
+///         "todos"
+///     }
+///
+///     fn visit(&mut self, node: &Node, code: &[u8]) {
+///         if node.kind() == "identifier"
+///             && &code[node.start_byte()..node.end_byte()] == b"TODO"
+///         {
+///             self.0 += 1;
+///         }
+///     }
+///
+///     fn finalize(self: Box<Self>) -> serde_json::Value {
+///         self.0.into()
+///     }
+/// }
+/// ```
+pub trait CustomMetric: fmt::Debug {
+    /// Returns the key of the metric in the `custom` map of a space.
+    fn name(&self) -> &str;
+
+    /// Visits a node of the analyzed code.
+    ///
+    /// Nodes are visited in pre-order, and `code` is the whole code the
+    /// byte offsets of `node` refer to.
+    fn visit(&mut self, node: &Node, code: &[u8]);
+
+    /// Consumes the metric once all nodes have been visited and returns
+    /// its serialized result.
+    fn finalize(self: Box<Self>) -> serde_json::Value;
+}
+
+/// Creates a new instance of a custom metric for each analyzed code.
+///
+/// A factory is shared rather than copied when cloned, so the options
+/// holding it can be cloned and reused across analyses.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{custom::{CustomMetric, CustomMetricFactory}, Node};
+///
+/// #[derive(Debug, Default)]
+/// struct Nodes(usize);
+///
+/// impl CustomMetric for Nodes {
+///     fn name(&self) -> &str {
+///         "nodes"
+///     }
+///
+///     fn visit(&mut self, _node: &Node, _code: &[u8]) {
+///         self.0 += 1;
+///     }
+///
+///     fn finalize(self: Box<Self>) -> serde_json::Value {
+///         self.0.into()
+///     }
+/// }
+///
+/// let factory = CustomMetricFactory::new(|| Box::new(Nodes::default()));
+/// assert_eq!(factory.create().name(), "nodes");
+/// ```
+#[derive(Clone)]
+pub struct CustomMetricFactory(Arc<dyn Fn() -> Box<dyn CustomMetric> + Send + Sync>);
+
+impl CustomMetricFactory {
+    /// Creates a factory calling `create` for each analyzed code.
+    pub fn new<F>(create: F) -> Self
+    where
+        F: Fn() -> Box<dyn CustomMetric> + Send + Sync + 'static,
+    {
+        Self(Arc::new(create))
+    }
+
+    /// Returns a new instance of the custom metric.
+    #[must_use]
+    pub fn create(&self) -> Box<dyn CustomMetric> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for CustomMetricFactory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CustomMetricFactory").finish_non_exhaustive()
+    }
+}
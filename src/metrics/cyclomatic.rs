@@ -1,18 +1,39 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
     analysis_context::node_text_equals_any, checker::Checker, macros::implement_metric_trait,
-    node::Node, CcommentCode, Cpp, CppCode, CsharpCode, Elixir, ElixirCode, Erlang, ErlangCode,
-    Gleam, GleamCode, GoCode, Java, JavaCode, Javascript, JavascriptCode, KotlinCode, LuaCode,
-    Mozjs, MozjsCode, PreprocCode, Python, PythonCode, Rust, RustCode, Tsx, TsxCode, Typescript,
-    TypescriptCode,
+    node::Node, output::MetricStruct, CcommentCode, Cpp, CppCode, CsharpCode, Elixir, ElixirCode,
+    Erlang, ErlangCode, Gleam, GleamCode, GoCode, GraphqlCode, Java, JavaCode, Javascript,
+    JavascriptCode, KotlinCode, LuaCode, Mozjs, MozjsCode, PerlCode, PreprocCode, Python,
+    PythonCode, RCode, Rust, RustCode, Tsx, TsxCode, Typescript, TypescriptCode,
 };
 
+/// Configuration of the `Cyclomatic` metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CyclomaticConfig {
+    /// Whether conditional expressions, e.g. `a ? b : c` or `a if c else b`,
+    /// count as branches (default `true`).
+    pub count_ternary: bool,
+    /// Whether the `Kotlin` elvis operators `?:`, which branch when their
+    /// left operand is `null`, count as branches (default `false`).
+    pub count_elvis: bool,
+    /// Whether the `Kotlin` safe calls `?.`, which skip the call when their
+    /// receiver is `null`, count as branches (default `false`).
+    pub count_safe_calls: bool,
+}
+
+impl Default for CyclomaticConfig {
+    fn default() -> Self {
+        Self {
+            count_ternary: true,
+            count_elvis: false,
+            count_safe_calls: false,
+        }
+    }
+}
+
 /// The `Cyclomatic` metric.
 #[derive(Debug, Clone)]
 pub struct Stats {
@@ -21,6 +42,7 @@ pub struct Stats {
     n: f64,
     cyclomatic_max: f64,
     cyclomatic_min: f64,
+    config: CyclomaticConfig,
 }
 
 impl Default for Stats {
@@ -31,6 +53,7 @@ impl Default for Stats {
             n: 1.0,
             cyclomatic_max: 0.,
             cyclomatic_min: f64::MAX,
+            config: CyclomaticConfig::default(),
         }
     }
 }
@@ -40,7 +63,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("cyclomatic", 4)?;
+        let mut st = MetricStruct::new(serializer, "cyclomatic", 4)?;
         st.serialize_field("sum", &self.cyclomatic_sum())?;
         st.serialize_field("average", &self.cyclomatic_average())?;
         st.serialize_field("min", &self.cyclomatic_min())?;
@@ -106,12 +129,33 @@ impl Stats {
     pub(crate) fn compute_sum(&mut self) {
         self.cyclomatic_sum += self.cyclomatic;
     }
+    // Sets the number of spaces the average is computed over
+    #[inline]
+    pub(crate) fn set_space_count(&mut self, space_count: usize) {
+        #[allow(clippy::cast_precision_loss)]
+        {
+            self.n = space_count as f64;
+        }
+    }
     #[inline]
     pub(crate) fn compute_minmax(&mut self) {
         self.cyclomatic_max = self.cyclomatic_max.max(self.cyclomatic);
         self.cyclomatic_min = self.cyclomatic_min.min(self.cyclomatic);
         self.compute_sum();
     }
+
+    #[inline]
+    pub(crate) fn set_config(&mut self, config: CyclomaticConfig) {
+        self.config = config;
+    }
+
+    // Counts a conditional expression, unless disabled by the configuration
+    #[inline]
+    fn add_ternary(&mut self) {
+        if self.config.count_ternary {
+            self.cyclomatic += 1.;
+        }
+    }
 }
 
 pub trait Cyclomatic
@@ -124,6 +168,15 @@ where
 impl Cyclomatic for PythonCode {
     fn compute(node: &Node, stats: &mut Stats) {
         match node.kind_id().into() {
+            // The `if` of a conditional expression, `a if c else b`
+            Python::If
+                if node
+                    .parent()
+                    .is_some_and(|parent| parent.kind_id() == Python::ConditionalExpression) =>
+            {
+                stats.add_ternary();
+            }
+            // `If` is also the keyword of case guards
             Python::If
             | Python::Elif
             | Python::For
@@ -131,20 +184,23 @@ impl Cyclomatic for PythonCode {
             | Python::Except
             | Python::With
             | Python::Assert
+            | Python::CaseClause
             | Python::And
             | Python::Or => {
                 stats.cyclomatic += 1.;
             }
             Python::Else => {
-                if node.has_ancestors(
-                    |node| {
-                        matches!(
-                            node.kind_id().into(),
-                            Python::ForStatement | Python::WhileStatement
-                        )
-                    },
-                    |node| node.kind_id() == Python::ElseClause,
-                ) {
+                let mut ancestors = node.ancestors().peekable();
+                ancestors.next_if(|parent| {
+                    matches!(
+                        parent.kind_id().into(),
+                        Python::ForStatement | Python::WhileStatement
+                    )
+                });
+                if ancestors
+                    .next()
+                    .is_some_and(|parent| parent.kind_id() == Python::ElseClause)
+                {
                     stats.cyclomatic += 1.;
                 }
             }
@@ -161,11 +217,13 @@ impl Cyclomatic for MozjsCode {
             | Mozjs::While
             | Mozjs::Case
             | Mozjs::Catch
-            | Mozjs::TernaryExpression
             | Mozjs::AMPAMP
             | Mozjs::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Mozjs::TernaryExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -179,11 +237,13 @@ impl Cyclomatic for JavascriptCode {
             | Javascript::While
             | Javascript::Case
             | Javascript::Catch
-            | Javascript::TernaryExpression
             | Javascript::AMPAMP
             | Javascript::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Javascript::TernaryExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -197,11 +257,13 @@ impl Cyclomatic for TypescriptCode {
             | Typescript::While
             | Typescript::Case
             | Typescript::Catch
-            | Typescript::TernaryExpression
             | Typescript::AMPAMP
             | Typescript::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Typescript::TernaryExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -215,11 +277,13 @@ impl Cyclomatic for TsxCode {
             | Tsx::While
             | Tsx::Case
             | Tsx::Catch
-            | Tsx::TernaryExpression
             | Tsx::AMPAMP
             | Tsx::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Tsx::TernaryExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -228,6 +292,7 @@ impl Cyclomatic for TsxCode {
 impl Cyclomatic for RustCode {
     fn compute(node: &Node, stats: &mut Stats) {
         match node.kind_id().into() {
+            // `If` is also the keyword of match arm guards
             Rust::If
             | Rust::For
             | Rust::While
@@ -239,6 +304,15 @@ impl Cyclomatic for RustCode {
             | Rust::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            // Each rule of a `macro_rules!` after the first one is an
+            // alternative way to expand the macro
+            Rust::MacroRule
+                if node
+                    .previous_named_sibling()
+                    .is_some_and(|sibling| sibling.kind_id() == Rust::MacroRule) =>
+            {
+                stats.cyclomatic += 1.;
+            }
             _ => {}
         }
     }
@@ -252,11 +326,13 @@ impl Cyclomatic for CppCode {
             | Cpp::While
             | Cpp::Case
             | Cpp::Catch
-            | Cpp::ConditionalExpression
             | Cpp::AMPAMP
             | Cpp::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Cpp::ConditionalExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -282,6 +358,14 @@ impl Cyclomatic for ElixirCode {
             Elixir::StabClause | Elixir::ElseBlock => {
                 stats.cyclomatic += 1.;
             }
+            // Guards in function heads and `case` clauses (`x when x > 0`)
+            _ if node.kind() == "binary_operator"
+                && node
+                    .child_by_field_name("operator")
+                    .is_some_and(|operator| operator.kind() == "when") =>
+            {
+                stats.cyclomatic += 1.;
+            }
             _ => {}
         }
     }
@@ -333,11 +417,13 @@ impl Cyclomatic for JavaCode {
             | Java::While
             | Java::Case
             | Java::Catch
-            | Java::TernaryExpression
             | Java::AMPAMP
             | Java::PIPEPIPE => {
                 stats.cyclomatic += 1.;
             }
+            Java::TernaryExpression => {
+                stats.add_ternary();
+            }
             _ => {}
         }
     }
@@ -362,6 +448,12 @@ impl Cyclomatic for KotlinCode {
                     }
                 }
             }
+            "?:" if stats.config.count_elvis => {
+                stats.cyclomatic += 1.;
+            }
+            "?." if stats.config.count_safe_calls => {
+                stats.cyclomatic += 1.;
+            }
             _ => {}
         }
     }
@@ -427,10 +519,12 @@ impl Cyclomatic for CsharpCode {
             | "while_statement"
             | "do_statement"
             | "try_statement"
-            | "catch_clause"
-            | "conditional_expression" => {
+            | "catch_clause" => {
                 stats.cyclomatic += 1.;
             }
+            "conditional_expression" => {
+                stats.add_ternary();
+            }
             "switch_section" | "switch_expression_arm" => {
                 // Each case in switch adds to complexity
                 stats.cyclomatic += 1.;
@@ -448,13 +542,53 @@ impl Cyclomatic for CsharpCode {
     }
 }
 
-implement_metric_trait!(Cyclomatic, PreprocCode, CcommentCode);
+impl Cyclomatic for RCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        match node.kind() {
+            // `else if` is an `if_statement` nested in the alternative branch,
+            // so it is counted here as well
+            "if_statement" | "for_statement" | "while_statement" | "repeat_statement" => {
+                stats.cyclomatic += 1.;
+            }
+            "binary_operator" => {
+                // R has both scalar (`&&`, `||`) and vectorised (`&`, `|`) boolean operators
+                if let Some(operator) = node.child_by_field_name("operator") {
+                    if matches!(operator.kind(), "&&" | "||" | "&" | "|") {
+                        stats.cyclomatic += 1.;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Perl keywords and operators which open a new path
+const PERL_BRANCH_TOKENS: &[&str] = &[
+    "if", "elsif", "unless", "while", "until", "for", "foreach", "&&", "||", "and", "or",
+];
+
+impl Cyclomatic for PerlCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        // Keywords are matched as tokens, so the postfix forms `do_x() if $cond`
+        // and `do_x() for @list` are counted as branches too
+        if !node.is_named() && PERL_BRANCH_TOKENS.contains(&node.kind()) {
+            stats.cyclomatic += 1.;
+        }
+    }
+}
+
+implement_metric_trait!(Cyclomatic, PreprocCode, CcommentCode, GraphqlCode);
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use super::CyclomaticConfig;
     use crate::{
-        tools::check_metrics, CppParser, CsharpParser, GoParser, JavaParser, KotlinParser,
-        LuaParser, ParserEngineRust, PythonParser,
+        metrics, metrics_with_options, tools::check_metrics, CppParser, CsharpParser,
+        ElixirParser, GoParser, JavaParser, JavascriptParser, KotlinParser, LuaParser,
+        MetricsOptions, ParserEngineRust, ParserTrait, PerlParser, PythonParser, RParser,
     };
 
     #[test]
@@ -506,6 +640,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn python_match_cases() {
+        check_metrics::<PythonParser>(
+            "def f(command): # +2 (+1 unit space)
+                match command:
+                    case \"start\":  # +1
+                        return 1
+                    case \"stop\":  # +1
+                        return 2
+                    case _:  # +1
+                        return 0",
+            "foo.py",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r###"
+                    {
+                      "sum": 5.0,
+                      "average": 2.5,
+                      "min": 1.0,
+                      "max": 4.0
+                    }"###
+                );
+            },
+        );
+    }
+
     #[test]
     fn rust_1_level_nesting() {
         check_metrics::<ParserEngineRust>(
@@ -534,6 +696,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rust_match_guards() {
+        check_metrics::<ParserEngineRust>(
+            "fn classify(x: i32, y: i32) -> i32 { // +2 (+1 unit space)
+                 match x {
+                     0 if y > 0 && y < 10 => 1, // +3
+                     1 if y > 10 && y < 100 => 2, // +3
+                     _ => 3, // +1
+                 }
+             }",
+            "foo.rs",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r###"
+                    {
+                      "sum": 9.0,
+                      "average": 4.5,
+                      "min": 1.0,
+                      "max": 8.0
+                    }"###
+                );
+            },
+        );
+    }
+
     #[test]
     fn c_switch() {
         check_metrics::<CppParser>(
@@ -1337,6 +1526,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn javascript_ternary_not_counted() {
+        let path = Path::new("foo.js");
+        let source = "function sign(x, y) {
+                          const a = x > 0 ? 1 : -1;
+                          const b = y > 0 ? 1 : -1;
+                          return a * b;
+                      }";
+        let parser = JavascriptParser::new(source.as_bytes().to_vec(), path, None);
+        let function = |space: crate::FuncSpace| space.spaces[0].metrics.cyclomatic.cyclomatic();
+
+        let counted = metrics(&parser, path).unwrap();
+        assert_eq!(function(counted), 3.);
+
+        let options = MetricsOptions {
+            cyclomatic: CyclomaticConfig {
+                count_ternary: false,
+                ..CyclomaticConfig::default()
+            },
+            ..MetricsOptions::default()
+        };
+        let ignored = metrics_with_options(&parser, path, &options).unwrap();
+        assert_eq!(function(ignored), 1.);
+    }
+
+    #[test]
+    fn kotlin_elvis_counted() {
+        let path = Path::new("foo.kt");
+        let source = "fun fullName(user: User?): String {
+                          val first = user?.first ?: \"\"
+                          val last = user?.last ?: \"\"
+                          return first + last
+                      }";
+        let parser = KotlinParser::new(source.as_bytes().to_vec(), path, None);
+        let function = |space: crate::FuncSpace| space.spaces[0].metrics.cyclomatic.cyclomatic();
+        let with_config = |cyclomatic| {
+            let options = MetricsOptions {
+                cyclomatic,
+                ..MetricsOptions::default()
+            };
+            function(metrics_with_options(&parser, path, &options).unwrap())
+        };
+
+        assert_eq!(function(metrics(&parser, path).unwrap()), 1.);
+        let elvis = CyclomaticConfig {
+            count_elvis: true,
+            ..CyclomaticConfig::default()
+        };
+        assert_eq!(with_config(elvis), 3.);
+        let safe_calls = CyclomaticConfig {
+            count_safe_calls: true,
+            ..elvis
+        };
+        assert_eq!(with_config(safe_calls), 5.);
+    }
+
+    #[test]
+    fn python_conditional_expression_not_counted() {
+        let path = Path::new("foo.py");
+        let source = "def f(x):\n    if x:\n        return 1 if x > 1 else 2\n    return 0\n";
+        let parser = PythonParser::new(source.as_bytes().to_vec(), path, None);
+        let options = MetricsOptions {
+            cyclomatic: CyclomaticConfig {
+                count_ternary: false,
+                ..CyclomaticConfig::default()
+            },
+            ..MetricsOptions::default()
+        };
+
+        let counted = metrics(&parser, path).unwrap();
+        let ignored = metrics_with_options(&parser, path, &options).unwrap();
+        assert_eq!(counted.spaces[0].metrics.cyclomatic.cyclomatic(), 3.);
+        assert_eq!(ignored.spaces[0].metrics.cyclomatic.cyclomatic(), 2.);
+    }
+
     #[test]
     fn csharp_cyclomatic_loops() {
         check_metrics::<CsharpParser>(
@@ -1439,4 +1703,189 @@ mod tests {
             },
         );
     }
+
+    // ==================== R Tests ====================
+
+    #[test]
+    fn r_simple_function() {
+        check_metrics::<RParser>(
+            "f <- function(a, b) { # +2 (+1 unit space)
+                if (a && b) { # +2 (+1 &&)
+                    return(1)
+                }
+                0
+            }",
+            "foo.R",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 4.0,
+                  "average": 2.0,
+                  "min": 1.0,
+                  "max": 3.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn r_loops() {
+        check_metrics::<RParser>(
+            "g <- function(n) { # +2 (+1 unit space)
+                total <- 0
+                for (i in seq_len(n)) { # +1
+                    if (i %% 2 == 0 | i > 10) { # +2 (+1 |)
+                        next
+                    } else if (i > 5) { # +1
+                        total <- total + 1
+                    }
+                    total <- total + i
+                }
+                while (total > 100) { # +1
+                    total <- total - 1
+                }
+                repeat { # +1
+                    break
+                }
+                total
+            }",
+            "foo.R",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 8.0,
+                  "average": 4.0,
+                  "min": 1.0,
+                  "max": 7.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn elixir_guarded_clauses() {
+        check_metrics::<ElixirParser>(
+            "def classify(x) when x > 0 do # +1
+                :positive
+            end
+            def classify(x) when x < 0 do # +1
+                :negative
+            end
+            def classify(_x) do
+                :zero
+            end",
+            "foo.ex",
+            |metric| {
+                // nspace = 4 (3 do blocks and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 6.0,
+                  "average": 1.5,
+                  "min": 1.0,
+                  "max": 3.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn elixir_case_guards() {
+        check_metrics::<ElixirParser>(
+            "def sign(x) do # +2 (+1 do block space)
+                case x do # +1 (+1 do block space)
+                    n when n > 0 -> :positive # +2 (+1 when)
+                    n when n < 0 -> :negative # +2 (+1 when)
+                    _ -> :zero # +1
+                end
+            end",
+            "foo.ex",
+            |metric| {
+                // nspace = 3 (2 do blocks and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 9.0,
+                  "average": 3.0,
+                  "min": 1.0,
+                  "max": 6.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    // ==================== Perl Tests ====================
+
+    #[test]
+    fn perl_simple_function() {
+        check_metrics::<PerlParser>(
+            "sub check { # +2 (+1 unit space)
+                my ($a, $b) = @_;
+                if ($a && $b) { # +2 (+1 &&)
+                    return 1;
+                } elsif ($a or $b) { # +2 (+1 or)
+                    return 2;
+                }
+                return 0;
+            }",
+            "foo.pl",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 6.0,
+                  "average": 3.0,
+                  "min": 1.0,
+                  "max": 5.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn perl_postfix_conditionals() {
+        check_metrics::<PerlParser>(
+            "sub log_all { # +2 (+1 unit space)
+                my @items = @_;
+                return unless @items; # +1
+                print \"$_\\n\" for @items; # +1
+                warn \"many\" if @items > 10; # +1
+            }",
+            "foo.pl",
+            |metric| {
+                // nspace = 2 (func and unit)
+                insta::assert_json_snapshot!(
+                    metric.cyclomatic,
+                    @r#"
+                {
+                  "sum": 5.0,
+                  "average": 2.5,
+                  "min": 1.0,
+                  "max": 4.0
+                }
+                "#
+                );
+            },
+        );
+    }
 }
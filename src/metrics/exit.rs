@@ -1,14 +1,12 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
-    analysis_context, checker::Checker, macros::implement_metric_trait, node::Node, CcommentCode,
-    CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode,
-    KotlinCode, LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    analysis_context, checker::Checker, macros::implement_metric_trait, node::Node,
+    output::MetricStruct, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode,
+    GoCode, GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode,
+    PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 /// The `NExit` metric.
@@ -41,7 +39,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("nexits", 4)?;
+        let mut st = MetricStruct::new(serializer, "nexits", 4)?;
         st.serialize_field("sum", &self.exit_sum())?;
         st.serialize_field("average", &self.exit_average())?;
         st.serialize_field("min", &self.exit_min())?;
@@ -128,11 +126,97 @@ impl Stats {
     }
 }
 
+/// The kinds of exit points found in a function/method.
+///
+/// While the `NExit` metric counts the exit points, this set tells apart
+/// the mechanisms used to leave a function. Like the other metrics, the
+/// kinds of a space include the ones of its subspaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ExitKinds {
+    /// A `return` statement or expression
+    pub normal_return: bool,
+    /// A thrown or raised exception, or a panic
+    pub exception: bool,
+    /// A `break` or a `continue` to a label
+    pub labeled_break: bool,
+    /// A `goto` statement
+    pub goto: bool,
+    /// A call terminating the process, such as `exit` or `System.exit`
+    pub process_exit: bool,
+}
+
+impl ExitKinds {
+    /// Merges a second set of exit kinds into the first one
+    pub fn merge(&mut self, other: &ExitKinds) {
+        self.normal_return |= other.normal_return;
+        self.exception |= other.exception;
+        self.labeled_break |= other.labeled_break;
+        self.goto |= other.goto;
+        self.process_exit |= other.process_exit;
+    }
+
+    /// Returns the number of distinct exit kinds
+    #[must_use]
+    pub fn count(&self) -> usize {
+        [
+            self.normal_return,
+            self.exception,
+            self.labeled_break,
+            self.goto,
+            self.process_exit,
+        ]
+        .into_iter()
+        .filter(|kind| *kind)
+        .count()
+    }
+}
+
 pub trait Exit
 where
     Self: Checker,
 {
     fn compute(node: &Node, stats: &mut Stats);
+
+    /// Records the kinds of the exit points found in `node`.
+    ///
+    /// The default implementation recognizes the statements shared by
+    /// most grammars, languages exiting through calls override it.
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+    }
+}
+
+// Records the exit kinds of the statements shared by most grammars
+fn compute_common_kinds(node: &Node, kinds: &mut ExitKinds) {
+    match node.kind() {
+        "return_statement" | "return_expression" => kinds.normal_return = true,
+        "throw_statement" | "throw_expression" | "raise_statement" => kinds.exception = true,
+        "goto_statement" => kinds.goto = true,
+        "break_statement" | "break_expression" | "continue_statement" | "continue_expression"
+            if has_label(node) =>
+        {
+            kinds.labeled_break = true;
+        }
+        _ => {}
+    }
+}
+
+// Whether a `break` or a `continue` jumps to a label
+fn has_label(node: &Node) -> bool {
+    node.child_by_field_name("label").is_some()
+        || node.children().any(|child| {
+            matches!(child.kind(), "label" | "label_name" | "statement_identifier")
+                // `Java` labels are plain identifiers
+                || (node.kind().ends_with("_statement") && child.kind() == "identifier")
+        })
+}
+
+// Whether the function called by `node`, found in its `field`, is one of
+// `names`
+#[inline]
+fn callee_matches_any(node: &Node, field: &str, names: &[&str]) -> bool {
+    node.child_by_field_name(field)
+        .is_some_and(|callee| analysis_context::node_text_equals_any(&callee, names))
 }
 
 #[inline]
@@ -163,12 +247,31 @@ fn call_matches_any(node: &Node, names: &[&str]) -> bool {
     }
 }
 
+// Whether the text of `node` starts with any of `prefixes`
+fn node_text_starts_with_any(node: &Node, prefixes: &[&str]) -> bool {
+    analysis_context::with_current_code(|code| {
+        code.get(node.start_byte()..node.end_byte()).is_some_and(|text| {
+            prefixes.iter().any(|prefix| text.starts_with(prefix.as_bytes()))
+        })
+    })
+    .unwrap_or(false)
+}
+
 impl Exit for PythonCode {
     fn compute(node: &Node, stats: &mut Stats) {
         if node.kind() == "return_statement" {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call"
+            && callee_matches_any(node, "function", &["exit", "quit", "sys.exit", "os._exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for MozjsCode {
@@ -177,6 +280,15 @@ impl Exit for MozjsCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression"
+            && callee_matches_any(node, "function", &["process.exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for JavascriptCode {
@@ -185,6 +297,15 @@ impl Exit for JavascriptCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression"
+            && callee_matches_any(node, "function", &["process.exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for TypescriptCode {
@@ -193,6 +314,15 @@ impl Exit for TypescriptCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression"
+            && callee_matches_any(node, "function", &["process.exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for TsxCode {
@@ -201,6 +331,15 @@ impl Exit for TsxCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression"
+            && callee_matches_any(node, "function", &["process.exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for RustCode {
@@ -211,6 +350,31 @@ impl Exit for RustCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        match node.kind() {
+            "macro_invocation"
+                if callee_matches_any(
+                    node,
+                    "macro",
+                    &["panic", "unreachable", "todo", "unimplemented"],
+                ) =>
+            {
+                kinds.exception = true;
+            }
+            "call_expression"
+                if callee_matches_any(
+                    node,
+                    "function",
+                    &["std::process::exit", "process::exit", "std::process::abort"],
+                ) =>
+            {
+                kinds.process_exit = true;
+            }
+            _ => {}
+        }
+    }
 }
 
 impl Exit for CppCode {
@@ -219,6 +383,19 @@ impl Exit for CppCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression"
+            && callee_matches_any(
+                node,
+                "function",
+                &["exit", "_Exit", "quick_exit", "abort", "std::exit", "std::abort"],
+            )
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for JavaCode {
@@ -227,6 +404,16 @@ impl Exit for JavaCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "method_invocation"
+            && callee_matches_any(node, "object", &["System"])
+            && callee_matches_any(node, "name", &["exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for ElixirCode {
@@ -235,6 +422,14 @@ impl Exit for ElixirCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        if call_matches_any(node, &["raise", "throw"]) {
+            kinds.exception = true;
+        } else if call_matches_any(node, &["exit", "halt"]) {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for ErlangCode {
@@ -243,6 +438,14 @@ impl Exit for ErlangCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        if call_matches_any(node, &["throw", "error"]) {
+            kinds.exception = true;
+        } else if call_matches_any(node, &["exit", "halt"]) {
+            kinds.process_exit = true;
+        }
+    }
 }
 
 impl Exit for GleamCode {
@@ -251,6 +454,12 @@ impl Exit for GleamCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        if matches!(node.kind(), "panic" | "todo") {
+            kinds.exception = true;
+        }
+    }
 }
 
 impl Exit for LuaCode {
@@ -259,6 +468,17 @@ impl Exit for LuaCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "function_call" {
+            if callee_matches_any(node, "name", &["error"]) {
+                kinds.exception = true;
+            } else if callee_matches_any(node, "name", &["os.exit"]) {
+                kinds.process_exit = true;
+            }
+        }
+    }
 }
 
 impl Exit for GoCode {
@@ -267,6 +487,17 @@ impl Exit for GoCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "call_expression" {
+            if callee_matches_any(node, "function", &["panic"]) {
+                kinds.exception = true;
+            } else if callee_matches_any(node, "function", &["os.Exit"]) {
+                kinds.process_exit = true;
+            }
+        }
+    }
 }
 
 impl Exit for KotlinCode {
@@ -278,6 +509,33 @@ impl Exit for KotlinCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        match node.kind() {
+            // `return`, `throw`, `break` and `continue` share a single node
+            "jump_expression" => {
+                if node_text_starts_with_any(node, &["return"]) {
+                    kinds.normal_return = true;
+                } else if node_text_starts_with_any(node, &["throw"]) {
+                    kinds.exception = true;
+                } else if node_text_starts_with_any(node, &["break@", "continue@"]) {
+                    kinds.labeled_break = true;
+                }
+            }
+            "call_expression"
+                if node.child(0).is_some_and(|callee| {
+                    analysis_context::node_text_equals_any(
+                        &callee,
+                        &["exitProcess", "System.exit"],
+                    )
+                }) =>
+            {
+                kinds.process_exit = true;
+            }
+            _ => {}
+        }
+    }
 }
 
 impl Exit for CsharpCode {
@@ -294,12 +552,49 @@ impl Exit for CsharpCode {
             stats.exit += 1;
         }
     }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        compute_common_kinds(node, kinds);
+        if node.kind() == "invocation_expression"
+            && callee_matches_any(node, "function", &["Environment.Exit"])
+        {
+            kinds.process_exit = true;
+        }
+    }
 }
 
-implement_metric_trait!(Exit, PreprocCode, CcommentCode);
+impl Exit for RCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        // `return` and `stop` are ordinary function calls in R
+        if call_matches_any(node, &["return", "stop", "quit", "q"]) {
+            stats.exit += 1;
+        }
+    }
+
+    fn compute_kinds(node: &Node, kinds: &mut ExitKinds) {
+        if call_matches_any(node, &["return"]) {
+            kinds.normal_return = true;
+        } else if call_matches_any(node, &["stop"]) {
+            kinds.exception = true;
+        } else if call_matches_any(node, &["quit", "q"]) {
+            kinds.process_exit = true;
+        }
+    }
+}
+
+impl Exit for PerlCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        if node.kind() == "return_expression" {
+            stats.exit += 1;
+        }
+    }
+}
+
+implement_metric_trait!(Exit, PreprocCode, CcommentCode, GraphqlCode);
 
 #[cfg(test)]
 mod tests {
+    use super::ExitKinds;
     use crate::{
         tools::check_metrics, CppParser, CsharpParser, ElixirParser, ErlangParser, GleamParser,
         GoParser, JavaParser, JavascriptParser, KotlinParser, LuaParser, ParserEngineRust,
@@ -1454,4 +1749,58 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn javascript_return_and_throw_kinds() {
+        check_metrics::<JavascriptParser>(
+            "function f(a) {
+                 if (a < 0) {
+                     throw new Error('negative');
+                 }
+                 return a;
+             }",
+            "foo.js",
+            |metric| {
+                assert_eq!(
+                    metric.exit_kinds,
+                    ExitKinds {
+                        normal_return: true,
+                        exception: true,
+                        ..ExitKinds::default()
+                    }
+                );
+                assert_eq!(metric.exit_kinds.count(), 2);
+            },
+        );
+    }
+
+    #[test]
+    fn java_labeled_break_and_exit_kinds() {
+        check_metrics::<JavaParser>(
+            "class A {
+                 void f(int[][] rows) {
+                     outer:
+                     for (int[] row : rows) {
+                         for (int cell : row) {
+                             if (cell < 0) {
+                                 break outer;
+                             }
+                         }
+                     }
+                     System.exit(1);
+                 }
+             }",
+            "foo.java",
+            |metric| {
+                assert_eq!(
+                    metric.exit_kinds,
+                    ExitKinds {
+                        labeled_break: true,
+                        process_exit: true,
+                        ..ExitKinds::default()
+                    }
+                );
+            },
+        );
+    }
 }
@@ -1,14 +1,12 @@
 use std::{collections::HashMap, fmt};
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
-    checker::Checker, getter::Getter, node::Node, CcommentCode, CppCode, CsharpCode, ElixirCode,
-    ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode,
-    PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    analysis_context::key_style, checker::Checker, getter::Getter, node::Node,
+    output::MetricStruct, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode,
+    GoCode, GraphqlCode, JavaCode, JavascriptCode, KeyStyle, KotlinCode, LuaCode, MozjsCode,
+    PerlCode, PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 /// The `Halstead` metric suite.
@@ -66,11 +64,20 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("halstead", 14)?;
-        st.serialize_field("n1", &self.u_operators())?;
-        st.serialize_field("N1", &self.operators())?;
-        st.serialize_field("n2", &self.u_operands())?;
-        st.serialize_field("N2", &self.operands())?;
+        let [n1, big_n1, n2, big_n2] = match key_style() {
+            KeyStyle::Conventional => ["n1", "N1", "n2", "N2"],
+            KeyStyle::SnakeCase => [
+                "distinct_operators",
+                "total_operators",
+                "distinct_operands",
+                "total_operands",
+            ],
+        };
+        let mut st = MetricStruct::new(serializer, "halstead", 14)?;
+        st.serialize_field(n1, &self.u_operators())?;
+        st.serialize_field(big_n1, &self.operators())?;
+        st.serialize_field(n2, &self.u_operands())?;
+        st.serialize_field(big_n2, &self.operands())?;
         st.serialize_field("length", &self.length())?;
         st.serialize_field("estimated_program_length", &self.estimated_program_length())?;
         st.serialize_field("purity_ratio", &self.purity_ratio())?;
@@ -169,18 +176,35 @@ impl Stats {
     }
 
     /// Returns the calculated estimated program length
+    ///
+    /// It is the sum of the `n * log2(n)` terms of the unique operators and
+    /// operands, where a single unique operator or operand contributes `0`
+    /// since it carries no information.
+    ///
+    /// If there are no operators or no operands, its value is `NAN`.
     #[inline]
     #[must_use]
     pub fn estimated_program_length(&self) -> f64 {
+        if self.u_operators == 0 || self.u_operands == 0 {
+            return f64::NAN;
+        }
         self.u_operators() * self.u_operators().log2()
             + self.u_operands() * self.u_operands().log2()
     }
 
     /// Returns the purity ratio
+    ///
+    /// If the estimated program length is zero, i.e. there is a single
+    /// unique operator and a single unique operand, the length cannot be
+    /// estimated and its value is `NAN`.
     #[inline]
     #[must_use]
     pub fn purity_ratio(&self) -> f64 {
-        self.estimated_program_length() / self.length()
+        let estimated_program_length = self.estimated_program_length();
+        if estimated_program_length == 0. {
+            return f64::NAN;
+        }
+        estimated_program_length / self.length()
     }
 
     /// Returns the program vocabulary
@@ -201,17 +225,28 @@ impl Stats {
     }
 
     /// Returns the estimated difficulty required to program
+    ///
+    /// If there are no operators or no operands, its value is `NAN`.
     #[inline]
     #[must_use]
     pub fn difficulty(&self) -> f64 {
+        if self.u_operators == 0 || self.u_operands == 0 {
+            return f64::NAN;
+        }
         self.u_operators() / 2. * self.operands() / self.u_operands()
     }
 
     /// Returns the estimated level of difficulty required to program
+    ///
+    /// If the difficulty is zero or not finite, its value is `NAN`.
     #[inline]
     #[must_use]
     pub fn level(&self) -> f64 {
-        1. / self.difficulty()
+        let difficulty = self.difficulty();
+        if difficulty == 0. || !difficulty.is_finite() {
+            return f64::NAN;
+        }
+        1. / difficulty
     }
 
     /// Returns the estimated effort required to program
@@ -406,14 +441,59 @@ impl Halstead for CsharpCode {
     }
 }
 
+impl Halstead for RCode {
+    fn compute<'a>(node: &Node<'a>, code: &'a [u8], halstead_maps: &mut HalsteadMaps<'a>) {
+        compute_halstead::<Self>(node, code, halstead_maps);
+    }
+}
+
+impl Halstead for PerlCode {
+    fn compute<'a>(node: &Node<'a>, code: &'a [u8], halstead_maps: &mut HalsteadMaps<'a>) {
+        compute_halstead::<Self>(node, code, halstead_maps);
+    }
+}
+
+impl Halstead for GraphqlCode {
+    fn compute<'a>(node: &Node<'a>, code: &'a [u8], halstead_maps: &mut HalsteadMaps<'a>) {
+        compute_halstead::<Self>(node, code, halstead_maps);
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use crate::{
-        tools::check_metrics, CppParser, CsharpParser, ElixirParser, GleamParser, GoParser,
-        JavaParser, JavascriptParser, KotlinParser, LuaParser, MozjsParser, ParserEngineRust,
-        PythonParser, TsxParser, TypescriptParser,
+        get_ops, tools::check_metrics, with_key_style, CppParser, CsharpParser, ElixirParser,
+        GleamParser, GoParser, JavaParser, JavascriptParser, KeyStyle, KotlinParser, LuaParser,
+        MozjsParser, ParserEngineRust, PythonParser, TsxParser, TypescriptParser, LANG,
     };
 
+    #[test]
+    fn python_snake_case_keys() {
+        check_metrics::<PythonParser>("a = b + c", "foo.py", |metric| {
+            let conventional = serde_json::to_value(&metric.halstead).unwrap();
+            let snake_case =
+                with_key_style(KeyStyle::SnakeCase, || serde_json::to_value(&metric.halstead))
+                    .unwrap();
+
+            let renamed = [
+                ("n1", "distinct_operators"),
+                ("N1", "total_operators"),
+                ("n2", "distinct_operands"),
+                ("N2", "total_operands"),
+            ];
+            for (key, snake_case_key) in renamed {
+                assert!(conventional.get(snake_case_key).is_none());
+                assert!(snake_case.get(key).is_none());
+                assert_eq!(snake_case[snake_case_key], conventional[key]);
+            }
+            assert_eq!(snake_case["volume"], conventional["volume"]);
+            // The default is restored once the closure returns
+            assert_eq!(serde_json::to_value(&metric.halstead).unwrap(), conventional);
+        });
+    }
+
     #[test]
     fn python_operators_and_operands() {
         check_metrics::<PythonParser>(
@@ -711,6 +791,86 @@ mod tests {
         });
     }
 
+    #[test]
+    fn python_operands_only() {
+        check_metrics::<PythonParser>("a\nb\nc\n", "foo.py", |metric| {
+            // No operators: difficulty and level are undefined
+            insta::assert_json_snapshot!(
+                metric.halstead,
+                @r###"
+                    {
+                      "n1": 0.0,
+                      "N1": 0.0,
+                      "n2": 3.0,
+                      "N2": 3.0,
+                      "length": 3.0,
+                      "estimated_program_length": null,
+                      "purity_ratio": null,
+                      "vocabulary": 3.0,
+                      "volume": 4.754887502163468,
+                      "difficulty": null,
+                      "level": null,
+                      "effort": null,
+                      "time": null,
+                      "bugs": null
+                    }"###
+            );
+        });
+    }
+
+    #[test]
+    fn python_single_unique_operator_and_operand() {
+        check_metrics::<PythonParser>("x = x", "foo.py", |metric| {
+            // Both `n * log2(n)` terms are 0, so the purity ratio is undefined
+            insta::assert_json_snapshot!(
+                metric.halstead,
+                @r###"
+                    {
+                      "n1": 1.0,
+                      "N1": 1.0,
+                      "n2": 1.0,
+                      "N2": 2.0,
+                      "length": 3.0,
+                      "estimated_program_length": 0.0,
+                      "purity_ratio": null,
+                      "vocabulary": 2.0,
+                      "volume": 3.0,
+                      "difficulty": 1.0,
+                      "level": 1.0,
+                      "effort": 3.0,
+                      "time": 0.16666666666666666,
+                      "bugs": 0.0006933612743506347
+                    }"###
+            );
+        });
+    }
+
+    #[test]
+    fn python_single_unique_operator() {
+        check_metrics::<PythonParser>("x = y", "foo.py", |metric| {
+            // Only the operands contribute to the estimated length: 2 * log2(2)
+            assert_eq!(metric.halstead.estimated_program_length(), 2.);
+            assert_eq!(metric.halstead.purity_ratio(), 2. / 3.);
+        });
+    }
+
+    #[test]
+    fn python_f_string_expressions() {
+        check_metrics::<PythonParser>("x = f\"{a * 2 + b}\"", "foo.py", |metric| {
+            // Operators: =, *, +
+            // Operands: x, a, 2, b, and not the whole f-string
+            assert_eq!(metric.halstead.u_operators(), 3.);
+            assert_eq!(metric.halstead.operators(), 3.);
+            assert_eq!(metric.halstead.u_operands(), 4.);
+            assert_eq!(metric.halstead.operands(), 4.);
+        });
+        check_metrics::<PythonParser>("x = f\"total: {a * b}\"", "foo.py", |metric| {
+            // The literal part of the f-string is an operand
+            assert_eq!(metric.halstead.operators(), 2.);
+            assert_eq!(metric.halstead.operands(), 4.);
+        });
+    }
+
     #[test]
     fn python_check_metrics() {
         check_metrics::<PythonParser>(
@@ -1401,6 +1561,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn erlang_records_and_comprehensions() {
+        let code = "xs(Points) -> [P#point.x || P <- Points, P#point.y > 0].
+origin() -> #point{x = 0, y = 0}.
+bytes() -> << <<B>> || <<B>> <= <<1, 2>> >>.
+";
+        let ops = get_ops(&LANG::Erlang, code.as_bytes().to_vec(), Path::new("foo.erl"), None)
+            .unwrap();
+        let (mut operands, mut operators) = (Vec::new(), Vec::new());
+        let mut stack = vec![&ops];
+        while let Some(space) = stack.pop() {
+            operands.extend(space.operands.iter().map(String::as_str));
+            operators.extend(space.operators.iter().map(String::as_str));
+            stack.extend(&space.spaces);
+        }
+
+        // The operands are the keys of `n2` and `N2`
+        for operand in ["P#point.x", "P#point.y", "#point{x = 0, y = 0}", "<<1, 2>>"] {
+            assert!(operands.contains(&operand), "{operand} is not an operand");
+        }
+        for operator in ["list_comprehension", "binary_comprehension", "||", "<-", "<="] {
+            assert!(operators.contains(&operator), "{operator} is not an operator");
+        }
+    }
+
     #[test]
     fn elixir_halstead_simple() {
         check_metrics::<ElixirParser>("x = 1 + 2", "foo.ex", |metric| {
@@ -1579,6 +1764,34 @@ mod tests {
         });
     }
 
+    #[test]
+    fn gleam_halstead_pipeline() {
+        // Two `|>` operators in two binary expressions
+        check_metrics::<GleamParser>("pub fn main(x) { x |> f |> g }", "foo.gleam", |metric| {
+            insta::assert_json_snapshot!(
+                metric.halstead,
+                @r#"
+            {
+              "n1": 2.0,
+              "N1": 4.0,
+              "n2": 4.0,
+              "N2": 5.0,
+              "length": 9.0,
+              "estimated_program_length": 10.0,
+              "purity_ratio": 1.1111111111111112,
+              "vocabulary": 6.0,
+              "volume": 23.264662506490403,
+              "difficulty": 1.25,
+              "level": 0.8,
+              "effort": 29.080828133113002,
+              "time": 1.6156015629507223,
+              "bugs": 0.0031522205195724964
+            }
+            "#
+            );
+        });
+    }
+
     #[test]
     fn gleam_halstead_moderate() {
         check_metrics::<GleamParser>(
@@ -0,0 +1,228 @@
+use std::fmt;
+
+use serde::{ser::Serializer, Serialize};
+
+use crate::{
+    checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct,
+    CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode,
+    JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode,
+    RCode, RustCode, TsxCode, TypescriptCode,
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// The `Jsx` metric.
+///
+/// This metric counts the `JSX` elements of a space and measures
+/// how deeply they are nested. It is only computed for code which
+/// can contain `JSX`, i.e. `JavaScript` and `Tsx`.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    elements: usize,
+    max_depth: usize,
+    elements_sum: usize,
+    max_depth_max: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = MetricStruct::new(serializer, "jsx", 2)?;
+        st.serialize_field("jsx_elements", &self.elements_sum())?;
+        st.serialize_field("jsx_max_depth", &self.max_depth_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "jsx_elements: {}, jsx_max_depth: {}",
+            self.elements_sum(),
+            self.max_depth_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Jsx` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.elements_sum += other.elements_sum;
+        self.max_depth_max = self.max_depth_max.max(other.max_depth_max);
+        self.is_enabled |= other.is_enabled;
+    }
+
+    /// Returns the number of `JSX` elements in a space.
+    #[inline]
+    #[must_use]
+    pub fn elements(&self) -> f64 {
+        usize_to_f64(self.elements)
+    }
+
+    /// Returns the deepest nesting of `JSX` elements in a space.
+    #[inline]
+    #[must_use]
+    pub fn max_depth(&self) -> f64 {
+        usize_to_f64(self.max_depth)
+    }
+
+    /// Returns the number of `JSX` elements in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn elements_sum(&self) -> f64 {
+        usize_to_f64(self.elements_sum)
+    }
+
+    /// Returns the deepest nesting of `JSX` elements
+    /// in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn max_depth_max(&self) -> f64 {
+        usize_to_f64(self.max_depth_max)
+    }
+
+    #[inline]
+    pub(crate) fn compute_sum(&mut self) {
+        self.elements_sum += self.elements;
+        self.max_depth_max = self.max_depth_max.max(self.max_depth);
+    }
+
+    // Checks if the `Jsx` metric is disabled
+    #[inline]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Jsx
+where
+    Self: Checker,
+{
+    fn compute(_node: &Node, _stats: &mut Stats) {}
+}
+
+#[inline]
+fn is_jsx_element(node: &Node) -> bool {
+    matches!(node.kind(), "jsx_element" | "jsx_self_closing_element")
+}
+
+fn compute_jsx<T: Checker>(node: &Node, stats: &mut Stats) {
+    stats.is_enabled = true;
+
+    if is_jsx_element(node) {
+        stats.elements += 1;
+        // Only the elements inside the space of the node are counted
+        let depth = 1 + node
+            .ancestors()
+            .take_while(|ancestor| !T::is_func(ancestor) && !T::is_func_space(ancestor))
+            .filter(is_jsx_element)
+            .count();
+        stats.max_depth = stats.max_depth.max(depth);
+    }
+}
+
+impl Jsx for JavascriptCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        compute_jsx::<Self>(node, stats);
+    }
+}
+
+impl Jsx for TsxCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        compute_jsx::<Self>(node, stats);
+    }
+}
+
+implement_metric_trait!(
+    [Jsx],
+    PythonCode,
+    MozjsCode,
+    TypescriptCode,
+    RustCode,
+    CppCode,
+    PreprocCode,
+    CcommentCode,
+    JavaCode,
+    KotlinCode,
+    ElixirCode,
+    ErlangCode,
+    GleamCode,
+    LuaCode,
+    GoCode,
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{tools::check_metrics, PythonParser, TsxParser};
+
+    #[test]
+    fn tsx_nested_elements() {
+        check_metrics::<TsxParser>(
+            "function List({ items }: Props) {
+                 return (
+                     <div className=\"list\">
+                         <h1>Items</h1>
+                         <ul>
+                             <li><span>first</span></li>
+                             <li><br /></li>
+                         </ul>
+                     </div>
+                 );
+             }",
+            "foo.tsx",
+            |metric| {
+                // div > ul > li > span
+                insta::assert_json_snapshot!(
+                    metric.jsx,
+                    @r###"
+                    {
+                      "jsx_elements": 7.0,
+                      "jsx_max_depth": 4.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn tsx_elements_in_callbacks() {
+        check_metrics::<TsxParser>(
+            "function List({ items }: Props) {
+                 return <ul>{items.map((item) => <li>{item}</li>)}</ul>;
+             }",
+            "foo.tsx",
+            |metric| {
+                // The `li` element belongs to the arrow function space
+                insta::assert_json_snapshot!(
+                    metric.jsx,
+                    @r###"
+                    {
+                      "jsx_elements": 2.0,
+                      "jsx_max_depth": 1.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_jsx_disabled() {
+        check_metrics::<PythonParser>("def f():\n    pass", "foo.py", |metric| {
+            assert!(metric.jsx.is_disabled());
+        });
+    }
+}
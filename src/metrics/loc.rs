@@ -1,17 +1,15 @@
 use std::{collections::HashSet, fmt};
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
     checker::Checker, language_cpp::Cpp, language_java::Java, language_javascript::Javascript,
     language_mozjs::Mozjs, language_python::Python, language_rust::Rust, language_tsx::Tsx,
-    language_typescript::Typescript, macros::implement_metric_trait, node::Node, CcommentCode,
-    CppCode, CppParser, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode,
-    JavaParser, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode,
-    TsxCode, TypescriptCode,
+    language_typescript::Typescript, macros::implement_metric_trait, node::Node,
+    output::MetricStruct, plain_text::CommentSyntax, CcommentCode, CppCode, CppParser, CsharpCode,
+    ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode, JavaCode, JavaParser, JavascriptCode,
+    KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode, RCode, RustCode, TsxCode,
+    TypescriptCode,
 };
 
 #[inline]
@@ -33,6 +31,8 @@ pub struct Sloc {
     sloc_max: usize,
     /// Accumulated sum of SLOC values across merged scopes for proper averaging.
     sloc_sum: usize,
+    /// Number of lines excluded from the metric, e.g. imports.
+    excluded: usize,
 }
 
 impl Default for Sloc {
@@ -44,6 +44,7 @@ impl Default for Sloc {
             sloc_min: usize::MAX,
             sloc_max: 0,
             sloc_sum: 0,
+            excluded: 0,
         }
     }
 }
@@ -51,11 +52,12 @@ impl Default for Sloc {
 impl Sloc {
     #[inline]
     fn current_lines(&self) -> usize {
-        if self.unit {
+        let lines = if self.unit {
             self.end - self.start
         } else {
             (self.end - self.start) + 1
-        }
+        };
+        lines.saturating_sub(self.excluded)
     }
 
     #[inline]
@@ -97,6 +99,15 @@ impl Sloc {
             self.sloc_sum = current;
         }
     }
+
+    // Appends the lines of a code following this one, e.g. of another file
+    #[inline]
+    pub(crate) fn append(&mut self, other: &Sloc) {
+        self.end += other.current_lines();
+        self.sloc_sum += other.sloc_sum;
+        self.sloc_min = self.sloc_min.min(other.sloc_min);
+        self.sloc_max = self.sloc_max.max(other.sloc_max);
+    }
 }
 
 /// The `PLoc` metric suite.
@@ -104,6 +115,7 @@ impl Sloc {
 #[derive(Debug, Clone)]
 pub struct Ploc {
     lines: HashSet<usize>,
+    excluded: HashSet<usize>,
     ploc_min: usize,
     ploc_max: usize,
 }
@@ -112,6 +124,7 @@ impl Default for Ploc {
     fn default() -> Self {
         Self {
             lines: HashSet::default(),
+            excluded: HashSet::default(),
             ploc_min: usize::MAX,
             ploc_max: 0,
         }
@@ -121,7 +134,11 @@ impl Default for Ploc {
 impl Ploc {
     #[inline]
     fn line_count(&self) -> usize {
-        self.lines.len()
+        if self.excluded.is_empty() {
+            self.lines.len()
+        } else {
+            self.lines.difference(&self.excluded).count()
+        }
     }
 
     #[inline]
@@ -152,6 +169,7 @@ impl Ploc {
         for &line in &other.lines {
             self.lines.insert(line);
         }
+        self.excluded.extend(&other.excluded);
 
         let other_ploc = other.line_count();
         self.ploc_min = self.ploc_min.min(other_ploc);
@@ -166,6 +184,24 @@ impl Ploc {
             self.ploc_max = self.ploc_max.max(current);
         }
     }
+
+    // Appends the lines of a code following this one, e.g. of another file,
+    // its line numbers being shifted after the ones of this code
+    #[inline]
+    pub(crate) fn append(&mut self, other: &Ploc) {
+        let offset = self
+            .lines
+            .iter()
+            .chain(&self.excluded)
+            .max()
+            .map_or(0, |line| line + 1);
+        self.lines
+            .extend(other.lines.iter().map(|line| line + offset));
+        self.excluded
+            .extend(other.excluded.iter().map(|line| line + offset));
+        self.ploc_min = self.ploc_min.min(other.ploc_min);
+        self.ploc_max = self.ploc_max.max(other.ploc_max);
+    }
 }
 
 /// The `CLoc` metric suite.
@@ -238,6 +274,15 @@ impl Cloc {
             self.cloc_max = self.cloc_max.max(current);
         }
     }
+
+    // Appends the comments of a code following this one, e.g. of another file
+    #[inline]
+    pub(crate) fn append(&mut self, other: &Cloc) {
+        self.only_comment_lines += other.only_comment_lines;
+        self.code_comment_lines += other.code_comment_lines;
+        self.cloc_min = self.cloc_min.min(other.cloc_min);
+        self.cloc_max = self.cloc_max.max(other.cloc_max);
+    }
 }
 
 /// The `LLoc` metric suite.
@@ -245,6 +290,7 @@ impl Cloc {
 #[derive(Debug, Clone)]
 pub struct Lloc {
     logical_lines: usize,
+    excluded: usize,
     lloc_min: usize,
     lloc_max: usize,
 }
@@ -253,6 +299,7 @@ impl Default for Lloc {
     fn default() -> Self {
         Self {
             logical_lines: 0,
+            excluded: 0,
             lloc_min: usize::MAX,
             lloc_max: 0,
         }
@@ -262,7 +309,7 @@ impl Default for Lloc {
 impl Lloc {
     #[inline]
     fn line_count(&self) -> usize {
-        self.logical_lines
+        self.logical_lines.saturating_sub(self.excluded)
     }
 
     #[inline]
@@ -291,6 +338,7 @@ impl Lloc {
     pub fn merge(&mut self, other: &Lloc) {
         // Merge lloc lines
         self.logical_lines += other.logical_lines;
+        self.excluded += other.excluded;
         let other_lloc = other.line_count();
         self.lloc_min = self.lloc_min.min(other_lloc);
         self.lloc_max = self.lloc_max.max(other_lloc);
@@ -304,6 +352,16 @@ impl Lloc {
             self.lloc_max = self.lloc_max.max(current);
         }
     }
+
+    // Appends the statements of a code following this one, e.g. of another
+    // file
+    #[inline]
+    pub(crate) fn append(&mut self, other: &Lloc) {
+        self.logical_lines += other.logical_lines;
+        self.excluded += other.excluded;
+        self.lloc_min = self.lloc_min.min(other.lloc_min);
+        self.lloc_max = self.lloc_max.max(other.lloc_max);
+    }
 }
 
 /// The `Loc` metric suite.
@@ -316,6 +374,8 @@ pub struct Stats {
     space_count: usize,
     blank_min: usize,
     blank_max: usize,
+    function_length_max: usize,
+    exclude_imports: bool,
 }
 
 impl Default for Stats {
@@ -328,6 +388,8 @@ impl Default for Stats {
             space_count: 1,
             blank_min: usize::MAX,
             blank_max: 0,
+            function_length_max: 0,
+            exclude_imports: false,
         }
     }
 }
@@ -337,7 +399,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("loc", 20)?;
+        let mut st = MetricStruct::new(serializer, "loc", 22)?;
         st.serialize_field("sloc", &self.sloc())?;
         st.serialize_field("ploc", &self.ploc())?;
         st.serialize_field("lloc", &self.lloc())?;
@@ -358,6 +420,8 @@ impl Serialize for Stats {
         st.serialize_field("lloc_max", &self.lloc_max())?;
         st.serialize_field("blank_min", &self.blank_min())?;
         st.serialize_field("blank_max", &self.blank_max())?;
+        st.serialize_field("function_length_max", &self.function_length_max())?;
+        st.serialize_field("comment_ratio", &self.comment_ratio())?;
         st.end()
     }
 }
@@ -366,7 +430,7 @@ impl fmt::Display for Stats {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
       f,
-      "sloc: {}, ploc: {}, lloc: {}, cloc: {}, blank: {}, sloc_average: {}, ploc_average: {}, lloc_average: {}, cloc_average: {}, blank_average: {}, sloc_min: {}, sloc_max: {}, cloc_min: {}, cloc_max: {}, ploc_min: {}, ploc_max: {}, lloc_min: {}, lloc_max: {}, blank_min: {}, blank_max: {}",
+      "sloc: {}, ploc: {}, lloc: {}, cloc: {}, blank: {}, sloc_average: {}, ploc_average: {}, lloc_average: {}, cloc_average: {}, blank_average: {}, sloc_min: {}, sloc_max: {}, cloc_min: {}, cloc_max: {}, ploc_min: {}, ploc_max: {}, lloc_min: {}, lloc_max: {}, blank_min: {}, blank_max: {}, function_length_max: {}, comment_ratio: {}",
       self.sloc(),
       self.ploc(),
       self.lloc(),
@@ -387,6 +451,8 @@ impl fmt::Display for Stats {
       self.lloc_max(),
       self.blank_min(),
       self.blank_max(),
+      self.function_length_max(),
+      self.comment_ratio(),
     )
     }
 }
@@ -397,6 +463,12 @@ impl Stats {
         usize_to_f64(self.space_count.max(1))
     }
 
+    // Sets the number of spaces the averages are computed over
+    #[inline]
+    pub(crate) fn set_space_count(&mut self, space_count: usize) {
+        self.space_count = space_count;
+    }
+
     #[inline]
     fn blank_lines(&self) -> usize {
         let sloc = self.sloc.current_lines();
@@ -419,6 +491,7 @@ impl Stats {
 
         self.blank_min = self.blank_min.min(other.blank_lines());
         self.blank_max = self.blank_max.max(other.blank_lines());
+        self.function_length_max = self.function_length_max.max(other.function_length_max);
     }
 
     /// The `Sloc` metric.
@@ -581,6 +654,56 @@ impl Stats {
         usize_to_f64(self.blank_max)
     }
 
+    /// The length in physical lines of the longest function in a scope,
+    /// itself included, or `0` when there is none.
+    #[inline]
+    #[must_use]
+    pub fn function_length_max(&self) -> f64 {
+        usize_to_f64(self.function_length_max)
+    }
+
+    /// The ratio of comment lines to comment and source lines,
+    /// `cloc / (cloc + sloc)`.
+    ///
+    /// Comments are counted in the space containing them, so a comment
+    /// starting after the end of a function belongs to its enclosing space.
+    /// If there are neither comment nor source lines, its value is `NaN`.
+    #[inline]
+    #[must_use]
+    pub fn comment_ratio(&self) -> f64 {
+        let cloc = self.cloc();
+        let lines = cloc + self.sloc();
+        if lines == 0. {
+            return f64::NAN;
+        }
+        cloc / lines
+    }
+
+    // Sets the length in physical lines of the function of this scope
+    #[inline]
+    pub(crate) fn set_function_length(&mut self, length: usize) {
+        self.function_length_max = length;
+    }
+
+    // Sets whether the top-level imports are excluded from the metrics
+    #[inline]
+    pub(crate) fn set_exclude_imports(&mut self, exclude_imports: bool) {
+        self.exclude_imports = exclude_imports;
+    }
+
+    // Excludes the lines of a node from the `Sloc`, `Ploc` and `Lloc`
+    // metrics. The logical lines of the node are counted apart, since
+    // each language counts them differently.
+    fn exclude<T: Loc>(&mut self, node: &Node) {
+        for line in node.start_row()..=node.end_row() {
+            if self.ploc.excluded.insert(line) {
+                self.sloc.excluded += 1;
+            }
+        }
+
+        self.lloc.excluded += count_logical_lines::<T>(node);
+    }
+
     #[inline]
     pub(crate) fn compute_minmax(&mut self) {
         self.sloc.compute_minmax();
@@ -594,6 +717,108 @@ impl Stats {
             self.blank_max = self.blank_max.max(blank);
         }
     }
+
+    // Appends the metrics of a code following this one, e.g. of another
+    // file: unlike `merge`, which merges a nested space whose lines are
+    // already counted, the lines of both codes are added up
+    pub(crate) fn append(&mut self, other: &Stats) {
+        self.sloc.append(&other.sloc);
+        self.ploc.append(&other.ploc);
+        self.cloc.append(&other.cloc);
+        self.lloc.append(&other.lloc);
+        self.space_count += other.space_count;
+        self.blank_min = self.blank_min.min(other.blank_min);
+        self.blank_max = self.blank_max.max(other.blank_max);
+        self.function_length_max = self.function_length_max.max(other.function_length_max);
+    }
+}
+
+impl Stats {
+    // Computes the metrics of a code without a grammar, line by line,
+    // recognizing `#` and `//` line comments and `/* */` block comments.
+    // No statement is recognized, so `Lloc` stays at zero.
+    pub(crate) fn compute_plain_text(&mut self, code: &[u8], syntax: &CommentSyntax) {
+        let code = code.strip_suffix(b"\n").unwrap_or(code);
+        if code.is_empty() {
+            self.sloc.unit = true;
+            return;
+        }
+
+        // Empty delimiters would match anywhere
+        let line_prefixes: Vec<&[u8]> = ["#", "//"]
+            .into_iter()
+            .chain(syntax.line.iter().map(String::as_str))
+            .filter(|prefix| !prefix.is_empty())
+            .map(str::as_bytes)
+            .collect();
+        let blocks: Vec<(&[u8], &[u8])> = [("/*", "*/")]
+            .into_iter()
+            .chain(
+                syntax
+                    .block
+                    .iter()
+                    .map(|(open, close)| (open.as_str(), close.as_str())),
+            )
+            .filter(|(open, close)| !open.is_empty() && !close.is_empty())
+            .map(|(open, close)| (open.as_bytes(), close.as_bytes()))
+            .collect();
+
+        let mut closing = None;
+        for (row, line) in code.split(|byte| *byte == b'\n').enumerate() {
+            let (has_code, has_comment) =
+                scan_plain_text_line(line, &line_prefixes, &blocks, &mut closing);
+            if has_code {
+                self.ploc.lines.insert(row);
+                if has_comment {
+                    self.cloc.code_comment_lines += 1;
+                }
+            } else if has_comment {
+                self.cloc.only_comment_lines += 1;
+            }
+            self.sloc.end = row;
+        }
+    }
+}
+
+// Returns whether a line contains code and whether it contains a comment,
+// tracking across lines the delimiter closing a block comment still open.
+// Block comments are looked for first, so that an opening delimiter such
+// as `--[[` wins over a line comment prefix such as `--`
+fn scan_plain_text_line<'a>(
+    line: &[u8],
+    line_prefixes: &[&[u8]],
+    blocks: &[(&[u8], &'a [u8])],
+    closing: &mut Option<&'a [u8]>,
+) -> (bool, bool) {
+    let (mut has_code, mut has_comment) = (false, false);
+    let mut rest = line;
+    loop {
+        if let Some(close) = *closing {
+            has_comment = true;
+            match rest.windows(close.len()).position(|window| window == close) {
+                Some(end) => {
+                    *closing = None;
+                    rest = &rest[end + close.len()..];
+                }
+                None => break,
+            }
+        }
+        let Some(start) = rest.iter().position(|byte| !byte.is_ascii_whitespace()) else {
+            break;
+        };
+        rest = &rest[start..];
+        if let Some((open, close)) = blocks.iter().find(|(open, _)| rest.starts_with(open)) {
+            *closing = Some(*close);
+            rest = &rest[open.len()..];
+        } else if line_prefixes.iter().any(|prefix| rest.starts_with(prefix)) {
+            has_comment = true;
+            break;
+        } else {
+            has_code = true;
+            rest = &rest[1..];
+        }
+    }
+    (has_code, has_comment)
 }
 
 pub trait Loc
@@ -603,6 +828,47 @@ where
     fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool);
 }
 
+// Counts the logical lines of a node and all its descendants
+pub(crate) fn count_logical_lines<T: Loc>(node: &Node) -> usize {
+    let mut stats = Stats::default();
+    let mut stack = vec![*node];
+    while let Some(node) = stack.pop() {
+        T::compute(&node, &mut stats, false, false);
+        stack.extend(node.children());
+    }
+    stats.lloc.logical_lines
+}
+
+// Top-level import statements, which can be excluded from the metrics
+#[inline]
+fn is_top_level_import(node: &Node) -> bool {
+    matches!(
+        node.kind(),
+        "import_statement"
+            | "import_from_statement"
+            | "future_import_statement"
+            | "use_declaration"
+            | "import_declaration"
+    ) && node
+        .parent()
+        .is_some_and(|parent| parent.parent().is_none())
+}
+
+// Computes the `Loc` metrics of a node, leaving out the top-level
+// imports when they are excluded.
+#[inline]
+pub(crate) fn compute_loc<T: Loc>(
+    node: &Node,
+    stats: &mut Stats,
+    is_func_space: bool,
+    is_unit: bool,
+) {
+    T::compute(node, stats, is_func_space, is_unit);
+    if stats.exclude_imports && is_top_level_import(node) {
+        stats.exclude::<T>(node);
+    }
+}
+
 #[inline]
 fn init(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) -> (usize, usize) {
     let start = node.start_row();
@@ -657,6 +923,12 @@ fn check_comment_ends_on_code_line(stats: &mut Stats, start_code_line: usize) {
     }
 }
 
+#[inline]
+fn record_physical_line(stats: &mut Stats, start: usize) {
+    check_comment_ends_on_code_line(stats, start);
+    stats.ploc.lines.insert(start);
+}
+
 #[inline]
 fn record_code_line(stats: &mut Stats, start: usize) {
     check_comment_ends_on_code_line(stats, start);
@@ -678,7 +950,9 @@ impl Loc for PythonCode {
                 add_cloc_lines(stats, start, end);
             }
             String => {
-                let parent = node.parent().expect("TODO: Add context for why this shouldn't fail");
+                let Some(parent) = node.parent() else {
+                    return;
+                };
                 if let ExpressionStatement = parent.kind_id().into() {
                     add_cloc_lines(stats, start, end);
                 } else if parent.start_row() != start {
@@ -702,6 +976,7 @@ impl Loc for PythonCode {
             | IfStatement
             | ForStatement
             | WhileStatement
+            | MatchStatement
             | TryStatement
             | WithStatement
             | GlobalStatement
@@ -1110,7 +1385,18 @@ impl Loc for GoCode {
             | "statement_list"
             | "parameter_list"
             | "field_declaration_list" => {}
-            _ => record_code_line(stats, start),
+            _ => {
+                // LLOC in Go is counted for the statements of a block, so
+                // `a := 1; b := 2` counts 2 and an `if` initializer counts 0
+                if node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "statement_list")
+                    && node.kind() != "comment"
+                {
+                    stats.lloc.logical_lines += 1;
+                }
+                record_physical_line(stats, start);
+            }
         }
     }
 }
@@ -1142,18 +1428,87 @@ impl Loc for CsharpCode {
             "comment" => add_cloc_lines(stats, start, end),
             "compilation_unit" | "class_body" | "block" | "parameter_list" | "argument_list"
             | "attribute_list" => {}
+            // LLOC in C# is counted for statements only, as in Java
+            "break_statement"
+            | "checked_statement"
+            | "continue_statement"
+            | "do_statement"
+            | "expression_statement"
+            | "fixed_statement"
+            | "for_statement"
+            | "foreach_statement"
+            | "goto_statement"
+            | "if_statement"
+            | "labeled_statement"
+            | "local_declaration_statement"
+            | "lock_statement"
+            | "return_statement"
+            | "switch_statement"
+            | "throw_statement"
+            | "try_statement"
+            | "unsafe_statement"
+            | "using_statement"
+            | "while_statement"
+            | "yield_statement" => {
+                stats.lloc.logical_lines += 1;
+                record_physical_line(stats, start);
+            }
+            _ => record_physical_line(stats, start),
+        }
+    }
+}
+
+impl Loc for RCode {
+    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+        let (start, end) = init(node, stats, is_func_space, is_unit);
+
+        match node.kind() {
+            "comment" => add_cloc_lines(stats, start, end),
+            "program" | "braced_expression" | "parameters" | "arguments" => {}
             _ => record_code_line(stats, start),
         }
     }
 }
 
+impl Loc for PerlCode {
+    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+        let (start, end) = init(node, stats, is_func_space, is_unit);
+
+        match node.kind() {
+            "comment" | "pod" => add_cloc_lines(stats, start, end),
+            "source_file" | "block" => {}
+            _ => record_code_line(stats, start),
+        }
+    }
+}
+
+impl Loc for GraphqlCode {
+    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+        let (start, end) = init(node, stats, is_func_space, is_unit);
+
+        match node.kind() {
+            // Descriptions document the definitions, as the docstrings of Python
+            "comment" | "description" => add_cloc_lines(stats, start, end),
+            "string_value"
+                if node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "description") => {}
+            // The definitions start with their description, so only their
+            // tokens mark the lines of code
+            _ if node.child_count() == 0 => record_code_line(stats, start),
+            _ => {}
+        }
+    }
+}
+
 implement_metric_trait!(Loc, PreprocCode, CcommentCode);
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        tools::check_metrics, CppParser, CsharpParser, GoParser, JavaParser, JavascriptParser,
-        KotlinParser, LuaParser, MozjsParser, ParserEngineRust, PythonParser,
+        check_func_space, lloc, tools::check_metrics, CppParser, CsharpParser, GoParser,
+        JavaParser, JavascriptParser, KotlinParser, LuaParser, MozjsParser, ParserEngineRust,
+        PythonParser, LANG,
     };
 
     #[test]
@@ -1190,7 +1545,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -1232,7 +1588,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 1.0,
-                      "blank_max": 1.0
+                      "blank_max": 1.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -1275,7 +1632,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 1.0,
-                      "blank_max": 1.0
+                      "blank_max": 1.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -1306,7 +1664,8 @@ mod tests {
                       "lloc_min": 0.0,
                       "lloc_max": 0.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
             );
         });
@@ -1348,7 +1707,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -1397,7 +1757,8 @@ mod tests {
                   "lloc_min": 6.0,
                   "lloc_max": 6.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.2857142857142857
                 }
                 "#
                 );
@@ -1445,7 +1806,8 @@ mod tests {
                   "lloc_min": 6.0,
                   "lloc_max": 6.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.3076923076923077
                 }
                 "#
                 );
@@ -1494,7 +1856,8 @@ mod tests {
                   "lloc_min": 6.0,
                   "lloc_max": 6.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.3333333333333333
                 }
                 "#
                 );
@@ -1544,7 +1907,8 @@ mod tests {
                       "lloc_min": 6.0,
                       "lloc_max": 6.0,
                       "blank_min": 1.0,
-                      "blank_max": 1.0
+                      "blank_max": 1.0,
+                      "comment_ratio": 0.26666666666666666
                     }"###
                 );
             },
@@ -1593,7 +1957,8 @@ mod tests {
                   "lloc_min": 1.0,
                   "lloc_max": 1.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.26666666666666666
                 }
                 "#
                 );
@@ -1643,7 +2008,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.26666666666666666
                 }
                 "#
                 );
@@ -1694,7 +2060,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.29411764705882354
                 }
                 "#
                 );
@@ -1746,7 +2113,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.2777777777777778
                 }
                 "#
                 );
@@ -1795,7 +2163,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.23076923076923078
                 }
                 "#
                 );
@@ -1846,7 +2215,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 1.0,
-                  "blank_max": 1.0
+                  "blank_max": 1.0,
+                  "comment_ratio": 0.29411764705882354
                 }
                 "#
                 );
@@ -1888,7 +2258,8 @@ mod tests {
                   "lloc_min": 2.0,
                   "lloc_max": 2.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.2857142857142857
                 }
                 "#
                 );
@@ -1929,7 +2300,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5555555555555556
                     }"###
                 );
             },
@@ -1969,7 +2341,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.5555555555555556
                 }
                 "#
                 );
@@ -2009,7 +2382,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2050,7 +2424,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2090,7 +2465,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2128,7 +2504,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2170,7 +2547,8 @@ mod tests {
                   "lloc_min": 2.0,
                   "lloc_max": 2.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2210,7 +2588,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2252,7 +2631,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2294,7 +2674,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2334,7 +2715,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2375,7 +2757,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2409,7 +2792,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -2442,7 +2826,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -2475,7 +2860,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -2508,7 +2894,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -2546,7 +2933,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
                 );
             },
@@ -2585,7 +2973,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
                 );
             },
@@ -2624,7 +3013,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
                 );
             },
@@ -2662,7 +3052,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
                 );
             },
@@ -2700,7 +3091,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -2739,7 +3131,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.5
                 }
                 "#
                 );
@@ -2782,7 +3175,8 @@ mod tests {
                   "lloc_min": 3.0,
                   "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2835,7 +3229,8 @@ mod tests {
                   "lloc_min": 8.0,
                   "lloc_max": 8.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.30434782608695654
                 }
                 "#
                 );
@@ -2877,7 +3272,8 @@ mod tests {
                   "lloc_min": 3.0,
                   "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2919,7 +3315,8 @@ mod tests {
                   "lloc_min": 3.0,
                   "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -2957,7 +3354,8 @@ mod tests {
                   "lloc_min": 0.0,
                   "lloc_max": 0.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.5
                 }
                 "#
                 );
@@ -3000,7 +3398,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.4
                     }"###
                 );
             },
@@ -3040,7 +3439,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 2.0,
-                      "blank_max": 2.0
+                      "blank_max": 2.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3079,7 +3479,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3118,7 +3519,8 @@ mod tests {
                       "lloc_min": 0.0,
                       "lloc_max": 0.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3152,7 +3554,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -3190,7 +3593,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3230,7 +3634,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3264,7 +3669,8 @@ mod tests {
                       "lloc_min": 1.0,
                       "lloc_max": 1.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
             );
         });
@@ -3302,7 +3708,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.4
                     }"###
                 );
             },
@@ -3343,7 +3750,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.42857142857142855
                     }"###
                 );
             },
@@ -3385,7 +3793,8 @@ mod tests {
                       "lloc_min": 4.0,
                       "lloc_max": 4.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.4444444444444444
                     }"###
                 );
             },
@@ -3427,7 +3836,8 @@ mod tests {
                       "lloc_min": 4.0,
                       "lloc_max": 4.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.4444444444444444
                     }"###
                 );
             },
@@ -3479,7 +3889,8 @@ mod tests {
                       "lloc_min": 9.0,
                       "lloc_max": 9.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.36
                     }"###
                 );
             },
@@ -3521,7 +3932,8 @@ mod tests {
                       "lloc_min": 5.0,
                       "lloc_max": 5.0,
                       "blank_min": 1.0,
-                      "blank_max": 1.0
+                      "blank_max": 1.0,
+                      "comment_ratio": 0.3333333333333333
                     }"###
                 );
             },
@@ -3564,7 +3976,8 @@ mod tests {
                       "lloc_min": 5.0,
                       "lloc_max": 5.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.4166666666666667
                     }"###
                 );
             },
@@ -3610,7 +4023,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.18181818181818182
                     }"###
                 );
             },
@@ -3658,7 +4072,8 @@ mod tests {
                       "lloc_min": 12.0,
                       "lloc_max": 12.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.5
                     }"###
         );
       },
@@ -3695,7 +4110,8 @@ mod tests {
                       "lloc_min": 2.0,
                       "lloc_max": 2.0,
                       "blank_min": 0.0,
-                      "blank_max": 0.0
+                      "blank_max": 0.0,
+                      "comment_ratio": 0.0
                     }"###
                 );
             },
@@ -3742,7 +4158,8 @@ mod tests {
                       "lloc_min": 3.0,
                       "lloc_max": 3.0,
                       "blank_min": 1.0,
-                      "blank_max": 1.0
+                      "blank_max": 1.0,
+                      "comment_ratio": 0.35294117647058826
                     }"###
                 );
             },
@@ -3790,7 +4207,8 @@ mod tests {
           "lloc_min": 2.0,
           "lloc_max": 2.0,
           "blank_min": 0.0,
-          "blank_max": 0.0
+          "blank_max": 0.0,
+          "comment_ratio": 0.3333333333333333
         }
         "#
         );
@@ -3831,7 +4249,8 @@ mod tests {
                   "lloc_min": 3.0,
                   "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -3875,7 +4294,8 @@ fun factorial(n: Int): Int {
                   "lloc_min": 4.0,
                   "lloc_max": 4.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.3333333333333333
                 }
                 "#
                 );
@@ -3918,7 +4338,8 @@ fun factorial(n: Int): Int {
                   "lloc_min": 5.0,
                   "lloc_max": 5.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -3968,7 +4389,8 @@ fun factorial(n: Int): Int {
                   "lloc_min": 12.0,
                   "lloc_max": 12.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4042,7 +4464,8 @@ fun factorial(n: Int): Int {
                   "lloc_min": 32.0,
                   "lloc_max": 32.0,
                   "blank_min": 6.0,
-                  "blank_max": 6.0
+                  "blank_max": 6.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4083,7 +4506,8 @@ end",
                   "lloc_min": 1.0,
                   "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4129,7 +4553,8 @@ end",
                   "lloc_min": 1.0,
                   "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.25
                 }
                 "#
                 );
@@ -4172,7 +4597,8 @@ end",
                   "lloc_min": 1.0,
                   "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4222,7 +4648,8 @@ end",
                   "lloc_min": 1.0,
                   "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4301,7 +4728,8 @@ end",
                   "lloc_min": 1.0,
                   "lloc_max": 9.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4325,12 +4753,12 @@ end",
                 {
                   "sloc": 3.0,
                   "ploc": 3.0,
-                  "lloc": 3.0,
+                  "lloc": 1.0,
                   "cloc": 0.0,
                   "blank": 0.0,
                   "sloc_average": 1.5,
                   "ploc_average": 1.5,
-                  "lloc_average": 1.5,
+                  "lloc_average": 0.5,
                   "cloc_average": 0.0,
                   "blank_average": 0.0,
                   "sloc_min": 3.0,
@@ -4339,10 +4767,11 @@ end",
                   "cloc_max": 0.0,
                   "ploc_min": 3.0,
                   "ploc_max": 3.0,
-                  "lloc_min": 3.0,
-                  "lloc_max": 3.0,
+                  "lloc_min": 1.0,
+                  "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4371,12 +4800,12 @@ func factorial(n int) int {
                 {
                   "sloc": 10.0,
                   "ploc": 6.0,
-                  "lloc": 6.0,
+                  "lloc": 3.0,
                   "cloc": 4.0,
                   "blank": 0.0,
                   "sloc_average": 4.5,
                   "ploc_average": 3.0,
-                  "lloc_average": 3.0,
+                  "lloc_average": 1.5,
                   "cloc_average": 2.0,
                   "blank_average": 0.0,
                   "sloc_min": 9.0,
@@ -4385,10 +4814,11 @@ func factorial(n int) int {
                   "cloc_max": 3.0,
                   "ploc_min": 6.0,
                   "ploc_max": 6.0,
-                  "lloc_min": 6.0,
-                  "lloc_max": 6.0,
+                  "lloc_min": 3.0,
+                  "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.2857142857142857
                 }
                 "#
                 );
@@ -4414,12 +4844,12 @@ func factorial(n int) int {
                 {
                   "sloc": 7.0,
                   "ploc": 5.0,
-                  "lloc": 5.0,
+                  "lloc": 3.0,
                   "cloc": 0.0,
                   "blank": 2.0,
                   "sloc_average": 3.5,
                   "ploc_average": 2.5,
-                  "lloc_average": 2.5,
+                  "lloc_average": 1.5,
                   "cloc_average": 0.0,
                   "blank_average": 1.0,
                   "sloc_min": 7.0,
@@ -4428,10 +4858,11 @@ func factorial(n int) int {
                   "cloc_max": 0.0,
                   "ploc_min": 5.0,
                   "ploc_max": 5.0,
-                  "lloc_min": 5.0,
-                  "lloc_max": 5.0,
+                  "lloc_min": 3.0,
+                  "lloc_max": 3.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4464,12 +4895,12 @@ func factorial(n int) int {
                 {
                   "sloc": 14.0,
                   "ploc": 12.0,
-                  "lloc": 12.0,
+                  "lloc": 7.0,
                   "cloc": 0.0,
                   "blank": 2.0,
                   "sloc_average": 7.0,
                   "ploc_average": 6.0,
-                  "lloc_average": 6.0,
+                  "lloc_average": 3.5,
                   "cloc_average": 0.0,
                   "blank_average": 1.0,
                   "sloc_min": 14.0,
@@ -4478,10 +4909,11 @@ func factorial(n int) int {
                   "cloc_max": 0.0,
                   "ploc_min": 12.0,
                   "ploc_max": 12.0,
-                  "lloc_min": 12.0,
-                  "lloc_max": 12.0,
+                  "lloc_min": 7.0,
+                  "lloc_max": 7.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4544,12 +4976,12 @@ func (c *Calculator) ClearHistory() {
                 {
                   "sloc": 44.0,
                   "ploc": 37.0,
-                  "lloc": 37.0,
+                  "lloc": 17.0,
                   "cloc": 0.0,
                   "blank": 7.0,
                   "sloc_average": 4.25,
                   "ploc_average": 4.625,
-                  "lloc_average": 4.625,
+                  "lloc_average": 2.125,
                   "cloc_average": 0.0,
                   "blank_average": 0.875,
                   "sloc_min": 3.0,
@@ -4558,10 +4990,11 @@ func (c *Calculator) ClearHistory() {
                   "cloc_max": 0.0,
                   "ploc_min": 3.0,
                   "ploc_max": 8.0,
-                  "lloc_min": 3.0,
-                  "lloc_max": 8.0,
+                  "lloc_min": 1.0,
+                  "lloc_max": 5.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4585,12 +5018,12 @@ func (c *Calculator) ClearHistory() {
                 {
                   "sloc": 3.0,
                   "ploc": 3.0,
-                  "lloc": 3.0,
+                  "lloc": 1.0,
                   "cloc": 0.0,
                   "blank": 0.0,
-                  "sloc_average": 3.0,
-                  "ploc_average": 3.0,
-                  "lloc_average": 3.0,
+                  "sloc_average": 1.5,
+                  "ploc_average": 1.5,
+                  "lloc_average": 0.5,
                   "cloc_average": 0.0,
                   "blank_average": 0.0,
                   "sloc_min": 3.0,
@@ -4599,10 +5032,11 @@ func (c *Calculator) ClearHistory() {
                   "cloc_max": 0.0,
                   "ploc_min": 3.0,
                   "ploc_max": 3.0,
-                  "lloc_min": 3.0,
-                  "lloc_max": 3.0,
+                  "lloc_min": 1.0,
+                  "lloc_max": 1.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4631,24 +5065,25 @@ int Factorial(int n) {
                 {
                   "sloc": 10.0,
                   "ploc": 6.0,
-                  "lloc": 6.0,
+                  "lloc": 3.0,
                   "cloc": 4.0,
                   "blank": 0.0,
-                  "sloc_average": 10.0,
-                  "ploc_average": 6.0,
-                  "lloc_average": 6.0,
-                  "cloc_average": 4.0,
+                  "sloc_average": 4.5,
+                  "ploc_average": 3.0,
+                  "lloc_average": 1.5,
+                  "cloc_average": 2.0,
                   "blank_average": 0.0,
-                  "sloc_min": 10.0,
-                  "sloc_max": 10.0,
-                  "cloc_min": 4.0,
-                  "cloc_max": 4.0,
+                  "sloc_min": 9.0,
+                  "sloc_max": 9.0,
+                  "cloc_min": 3.0,
+                  "cloc_max": 3.0,
                   "ploc_min": 6.0,
                   "ploc_max": 6.0,
-                  "lloc_min": 6.0,
-                  "lloc_max": 6.0,
+                  "lloc_min": 3.0,
+                  "lloc_max": 3.0,
                   "blank_min": 0.0,
-                  "blank_max": 0.0
+                  "blank_max": 0.0,
+                  "comment_ratio": 0.2857142857142857
                 }
                 "#
                 );
@@ -4674,24 +5109,25 @@ int Factorial(int n) {
                 {
                   "sloc": 7.0,
                   "ploc": 5.0,
-                  "lloc": 5.0,
+                  "lloc": 3.0,
                   "cloc": 0.0,
                   "blank": 2.0,
-                  "sloc_average": 7.0,
-                  "ploc_average": 5.0,
-                  "lloc_average": 5.0,
+                  "sloc_average": 3.5,
+                  "ploc_average": 2.5,
+                  "lloc_average": 1.5,
                   "cloc_average": 0.0,
-                  "blank_average": 2.0,
+                  "blank_average": 1.0,
                   "sloc_min": 7.0,
                   "sloc_max": 7.0,
                   "cloc_min": 0.0,
                   "cloc_max": 0.0,
                   "ploc_min": 5.0,
                   "ploc_max": 5.0,
-                  "lloc_min": 5.0,
-                  "lloc_max": 5.0,
+                  "lloc_min": 3.0,
+                  "lloc_max": 3.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4724,24 +5160,25 @@ int Factorial(int n) {
                 {
                   "sloc": 14.0,
                   "ploc": 12.0,
-                  "lloc": 12.0,
+                  "lloc": 7.0,
                   "cloc": 0.0,
                   "blank": 2.0,
-                  "sloc_average": 14.0,
-                  "ploc_average": 12.0,
-                  "lloc_average": 12.0,
+                  "sloc_average": 7.0,
+                  "ploc_average": 6.0,
+                  "lloc_average": 3.5,
                   "cloc_average": 0.0,
-                  "blank_average": 2.0,
+                  "blank_average": 1.0,
                   "sloc_min": 14.0,
                   "sloc_max": 14.0,
                   "cloc_min": 0.0,
                   "cloc_max": 0.0,
                   "ploc_min": 12.0,
                   "ploc_max": 12.0,
-                  "lloc_min": 12.0,
-                  "lloc_max": 12.0,
+                  "lloc_min": 7.0,
+                  "lloc_max": 7.0,
                   "blank_min": 2.0,
-                  "blank_max": 2.0
+                  "blank_max": 2.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
@@ -4798,12 +5235,12 @@ int Factorial(int n) {
                 {
                   "sloc": 38.0,
                   "ploc": 32.0,
-                  "lloc": 32.0,
+                  "lloc": 16.0,
                   "cloc": 0.0,
                   "blank": 6.0,
                   "sloc_average": 4.75,
                   "ploc_average": 4.0,
-                  "lloc_average": 4.0,
+                  "lloc_average": 2.0,
                   "cloc_average": 0.0,
                   "blank_average": 0.75,
                   "sloc_min": 38.0,
@@ -4812,14 +5249,75 @@ int Factorial(int n) {
                   "cloc_max": 0.0,
                   "ploc_min": 32.0,
                   "ploc_max": 32.0,
-                  "lloc_min": 32.0,
-                  "lloc_max": 32.0,
+                  "lloc_min": 16.0,
+                  "lloc_max": 16.0,
                   "blank_min": 6.0,
-                  "blank_max": 6.0
+                  "blank_max": 6.0,
+                  "comment_ratio": 0.0
                 }
                 "#
                 );
             },
         );
     }
+
+    #[test]
+    fn lloc_counts_statements() {
+        assert_eq!(lloc(b"void f() { a=1; b=2; }", LANG::Cpp), 2);
+        assert_eq!(lloc(b"a = 1\nb = 2\n", LANG::Python), 2);
+        assert_eq!(lloc(b"func f() {\n    a := 1; b := 2\n}\n", LANG::Go), 2);
+        assert_eq!(lloc(b"void F() { a = 1; b = 2; }", LANG::Csharp), 2);
+        assert_eq!(lloc(b"class A { void f() { a = 1; b = 2; } }", LANG::Java), 2);
+    }
+
+    #[test]
+    fn lloc_counts_code_lines() {
+        // These languages count the lines containing code, not the statements
+        assert_eq!(lloc(b"fun f() { val a = 1; val b = 2 }", LANG::Kotlin), 1);
+        assert_eq!(lloc(b"local a = 1; local b = 2", LANG::Lua), 1);
+        assert_eq!(lloc(b"a = 1; b = 2", LANG::Elixir), 1);
+        assert_eq!(lloc(b"f() -> A = 1, B = 2, A + B.", LANG::Erlang), 1);
+        assert_eq!(lloc(b"pub fn f() { let a = 1 let b = 2 a + b }", LANG::Gleam), 1);
+        assert_eq!(lloc(b"a <- 1; b <- 2", LANG::R), 1);
+        assert_eq!(lloc(b"my $a = 1; my $b = 2;", LANG::Perl), 1);
+        assert_eq!(lloc(b"a <- 1\nb <- 2\n", LANG::R), 2);
+    }
+
+    #[test]
+    fn python_comment_ratio() {
+        check_func_space::<PythonParser, _>(
+            "def documented(x):
+    # Doubles x
+    # and returns it
+    return x * 2
+
+def bare(x):
+    return x * 2
+",
+            "foo.py",
+            |space| {
+                let documented = space.spaces[0].metrics.loc.comment_ratio();
+                let bare = space.spaces[1].metrics.loc.comment_ratio();
+                assert_eq!(documented, 2. / 6.);
+                assert_eq!(bare, 0.);
+                assert!(documented > bare);
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_comment_after_function() {
+        check_func_space::<JavascriptParser, _>(
+            "function f(x) {
+    return x;
+} /* Belongs to the file,
+     not to the function */",
+            "foo.js",
+            |space| {
+                assert_eq!(space.spaces[0].metrics.loc.comment_ratio(), 0.);
+                assert_eq!(space.metrics.loc.cloc(), 2.);
+                assert!(space.metrics.loc.comment_ratio() > 0.);
+            },
+        );
+    }
 }
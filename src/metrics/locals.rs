@@ -0,0 +1,339 @@
+use std::fmt;
+
+use serde::{ser::Serializer, Serialize};
+
+use crate::{
+    checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct,
+    CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode,
+    JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode,
+    RCode, RustCode, TsxCode, TypescriptCode,
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// The `Locals` metric.
+///
+/// This metric counts the local variables declared in a space, i.e. the
+/// names bound by the `Rust` `let` statements, the `JavaScript` and
+/// `TypeScript` `var`, `let` and `const` declarations, the `Python`
+/// assignments, the `Go` `:=` and `var` declarations, and the `Java` and
+/// `C#` local variable declarations. A destructuring declaration counts
+/// each name it binds.
+///
+/// `Python` has no declarations, so each assignment to a name counts,
+/// while the assignments to attributes or items do not. The declarations
+/// at the top level of a file count toward its unit space.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    locals: usize,
+    locals_sum: usize,
+    locals_max: usize,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = MetricStruct::new(serializer, "locals", 2)?;
+        st.serialize_field("total", &self.locals_sum())?;
+        st.serialize_field("max", &self.locals_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "total: {}, max: {}",
+            self.locals_sum(),
+            self.locals_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Locals` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.locals_sum += other.locals_sum;
+        self.locals_max = self.locals_max.max(other.locals_max);
+    }
+
+    /// Returns the number of local variables declared in a space.
+    #[inline]
+    #[must_use]
+    pub fn locals(&self) -> f64 {
+        usize_to_f64(self.locals)
+    }
+
+    /// Returns the number of local variables declared in a space and its
+    /// subspaces.
+    #[inline]
+    #[must_use]
+    pub fn locals_sum(&self) -> f64 {
+        usize_to_f64(self.locals_sum)
+    }
+
+    /// Returns the largest number of local variables declared in a single
+    /// space, among a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn locals_max(&self) -> f64 {
+        usize_to_f64(self.locals_max)
+    }
+
+    #[inline]
+    pub(crate) fn compute_sum(&mut self) {
+        self.locals_sum += self.locals;
+        self.locals_max = self.locals_max.max(self.locals);
+    }
+}
+
+pub trait Locals
+where
+    Self: Checker,
+{
+    fn compute(_node: &Node, _stats: &mut Stats) {}
+}
+
+// Counts the names bound by a pattern, i.e. its nodes of the `names` kinds,
+// without looking into the children in the `skipped` fields, such as the
+// default values of the destructured names
+fn count_bound_names(pattern: &Node, names: &[&str], skipped: &[&str]) -> usize {
+    let mut count = 0;
+    let mut stack = vec![*pattern];
+    while let Some(node) = stack.pop() {
+        if names.contains(&node.kind()) {
+            count += 1;
+            continue;
+        }
+        let skipped_children: Vec<_> = skipped
+            .iter()
+            .filter_map(|field| node.child_by_field_name(field))
+            .map(|child| child.id())
+            .collect();
+        stack.extend(
+            node.children()
+                .filter(|child| !skipped_children.contains(&child.id())),
+        );
+    }
+    count
+}
+
+impl Locals for RustCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        if node.kind() == "let_declaration"
+            && let Some(pattern) = node.child_by_field_name("pattern")
+        {
+            // The `type` of a pattern is the path of a struct or a variant,
+            // e.g. `Point` in `Point { x, y }`
+            stats.locals += count_bound_names(
+                &pattern,
+                &["identifier", "shorthand_field_identifier"],
+                &["type"],
+            );
+        }
+    }
+}
+
+// Counts the names declared by a `JavaScript` or a `TypeScript` variable
+// declarator, e.g. `a` and `b` in `const { a, b: [b = 0] } = c`
+fn js_compute(node: &Node, stats: &mut Stats) {
+    if node.kind() == "variable_declarator"
+        && let Some(name) = node.child_by_field_name("name")
+    {
+        stats.locals += count_bound_names(
+            &name,
+            &["identifier", "shorthand_property_identifier_pattern"],
+            &["key", "right"],
+        );
+    }
+}
+
+impl Locals for JavascriptCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        js_compute(node, stats);
+    }
+}
+
+impl Locals for MozjsCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        js_compute(node, stats);
+    }
+}
+
+impl Locals for TypescriptCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        js_compute(node, stats);
+    }
+}
+
+impl Locals for TsxCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        js_compute(node, stats);
+    }
+}
+
+impl Locals for PythonCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        if node.kind() == "assignment"
+            && let Some(left) = node.child_by_field_name("left")
+        {
+            stats.locals += python_targets(&left);
+        }
+    }
+}
+
+// Counts the names assigned by a `Python` assignment target, e.g. `a` and
+// `b` in `a, (b, self.c) = ...`
+fn python_targets(target: &Node) -> usize {
+    match target.kind() {
+        "identifier" => 1,
+        "pattern_list" | "tuple_pattern" | "list_pattern" | "list_splat_pattern" => {
+            target.children().map(|child| python_targets(&child)).sum()
+        }
+        _ => 0,
+    }
+}
+
+impl Locals for GoCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        let names = match node.kind() {
+            "short_var_declaration" => node.child_by_field_name("left"),
+            "var_spec" => Some(*node),
+            _ => None,
+        };
+        if let Some(names) = names {
+            stats.locals += names
+                .children()
+                .filter(|child| child.kind() == "identifier")
+                .count();
+        }
+    }
+}
+
+impl Locals for JavaCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        if node.kind() == "local_variable_declaration" {
+            stats.locals += node
+                .children()
+                .filter(|child| child.kind() == "variable_declarator")
+                .count();
+        }
+    }
+}
+
+impl Locals for CsharpCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        if node.kind() != "local_declaration_statement" {
+            return;
+        }
+        let declarators = node
+            .children()
+            .filter(|child| child.kind() == "variable_declaration")
+            .flat_map(|declaration| declaration.children())
+            .filter(|child| child.kind() == "variable_declarator");
+        for declarator in declarators {
+            // A deconstruction, e.g. `var (a, b) = ...`, has no name
+            stats.locals += match declarator.child_by_field_name("name") {
+                Some(_) => 1,
+                None => declarator
+                    .children()
+                    .filter(|child| child.kind() == "tuple_pattern")
+                    .map(|pattern| count_bound_names(&pattern, &["identifier"], &[]))
+                    .sum(),
+            };
+        }
+    }
+}
+
+implement_metric_trait!(
+    [Locals],
+    CppCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode,
+    ElixirCode,
+    ErlangCode,
+    GleamCode,
+    LuaCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tools::{check_func_space, check_metrics},
+        JavascriptParser, ParserEngineRust, PythonParser,
+    };
+
+    #[test]
+    fn rust_locals() {
+        check_func_space::<ParserEngineRust, _>(
+            "fn f(point: Point, pair: (i32, i32)) -> i32 {
+                 let total = 0;
+                 let Point { x, y: mut height } = point;
+                 let (first, _) = pair;
+                 let Some(value) = Some(x) else { return 0 };
+                 total + first + height + value
+             }",
+            "foo.rs",
+            |space| {
+                let function = &space.spaces[0].metrics.locals;
+                // `total`, `x`, `height`, `first` and `value`, as `_` binds
+                // no name
+                assert_eq!(function.locals(), 5.);
+                assert_eq!(function.locals_max(), 5.);
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_locals() {
+        check_metrics::<JavascriptParser>(
+            "function f(options) {
+                 var count = 0;
+                 let { name, size: [width, height = count] } = options;
+                 return name + width + height;
+             }",
+            "foo.js",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.locals,
+                    @r###"
+                    {
+                      "total": 4.0,
+                      "max": 4.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_locals() {
+        check_func_space::<PythonParser, _>(
+            "def f(self, items):
+    total = 0
+    first, *rest = items
+    self.count = len(rest)
+    items[0] = last = first
+    return total + last
+",
+            "foo.py",
+            |space| {
+                // `total`, `first`, `rest` and `last`
+                assert_eq!(space.spaces[0].metrics.locals.locals(), 4.);
+            },
+        );
+    }
+}
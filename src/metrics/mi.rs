@@ -1,15 +1,13 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use super::{cyclomatic, halstead, loc};
 use crate::{
-    checker::Checker, macros::implement_metric_trait, CcommentCode, CppCode, CsharpCode,
-    ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode, KotlinCode, LuaCode,
-    MozjsCode, PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    checker::Checker, macros::implement_metric_trait, output::MetricStruct, CcommentCode, CppCode,
+    CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode, JavaCode, JavascriptCode,
+    KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode, RCode, RustCode, TsxCode,
+    TypescriptCode,
 };
 
 /// The `Mi` metric.
@@ -28,7 +26,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("maintainability_index", 3)?;
+        let mut st = MetricStruct::new(serializer, "maintainability_index", 3)?;
         st.serialize_field("mi_original", &self.mi_original())?;
         st.serialize_field("mi_sei", &self.mi_sei())?;
         st.serialize_field("mi_visual_studio", &self.mi_visual_studio())?;
@@ -125,7 +123,10 @@ implement_metric_trait!(
     GleamCode,
     LuaCode,
     GoCode,
-    CsharpCode
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
 );
 
 #[cfg(test)]
@@ -1,14 +1,20 @@
 pub mod abc;
+pub mod boolean_ops;
 pub mod cognitive;
+pub mod custom;
 pub mod cyclomatic;
 pub mod exit;
 pub mod halstead;
+pub mod jsx;
+pub mod locals;
 pub mod loc;
 pub mod mi;
 pub mod nargs;
 pub mod nom;
 pub mod npa;
 pub mod npm;
+pub mod template;
+pub mod unsafety;
 pub mod wmc;
 
 // Insight-driven metrics that complement the core analysis suite
@@ -1,14 +1,12 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
-    checker::Checker, macros::implement_metric_trait, node::Node, traits::Search, CcommentCode,
-    CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode,
-    KotlinCode, LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct,
+    traits::Search, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode,
+    GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode,
+    PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 #[inline]
@@ -59,7 +57,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("nargs", 10)?;
+        let mut st = MetricStruct::new(serializer, "nargs", 10)?;
         st.serialize_field("total_functions", &self.fn_args_sum())?;
         st.serialize_field("total_closures", &self.closure_args_sum())?;
         st.serialize_field("average_functions", &self.fn_args_average())?;
@@ -252,12 +250,72 @@ impl NArgs for CppCode {
     }
 }
 
+// Counts the formal parameters of a `Java` method or record, e.g. `x` and
+// `y` in `record Point(int x, int y)`
+fn formal_parameters(node: &Node) -> usize {
+    node.child_by_field_name("parameters")
+        .map_or(0, |parameters| {
+            parameters
+                .children()
+                .filter(|child| matches!(child.kind(), "formal_parameter" | "spread_parameter"))
+                .count()
+        })
+}
+
+// Checks whether a `Java` record declares its canonical constructor, either
+// as a compact constructor or as a constructor taking as many arguments as
+// the record has components
+pub(crate) fn declares_canonical_constructor(record: &Node) -> bool {
+    let components = formal_parameters(record);
+    record.child_by_field_name("body").is_some_and(|body| {
+        body.children().any(|child| match child.kind() {
+            "compact_constructor_declaration" => true,
+            "constructor_declaration" => formal_parameters(&child) == components,
+            _ => false,
+        })
+    })
+}
+
+impl NArgs for JavaCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        match node.kind() {
+            // The arguments of a compact constructor are the components of
+            // its record
+            "compact_constructor_declaration" => {
+                if let Some(record) = node.parent().and_then(|body| body.parent()) {
+                    stats.fn_nargs += formal_parameters(&record);
+                }
+            }
+            // An implicit canonical constructor has no node, so its
+            // arguments count toward the record, which `Nom` counts as a
+            // method
+            "record_declaration" => {
+                if !declares_canonical_constructor(node) {
+                    stats.fn_nargs += formal_parameters(node);
+                }
+            }
+            _ if Self::is_func(node) => compute_args::<Self>(node, &mut stats.fn_nargs),
+            _ if Self::is_closure(node) => compute_args::<Self>(node, &mut stats.closure_nargs),
+            _ => {}
+        }
+    }
+}
+
 // Go language - delegate to default impl
 impl NArgs for GoCode {}
 
 // C# language - delegate to default impl
 impl NArgs for CsharpCode {}
 
+// R language - delegate to default impl
+impl NArgs for RCode {}
+
+// Perl language - delegate to default impl
+impl NArgs for PerlCode {}
+
+// GraphQL language - delegate to default impl
+impl NArgs for GraphqlCode {}
+
 implement_metric_trait!(
     [NArgs],
     ElixirCode,
@@ -272,15 +330,15 @@ implement_metric_trait!(
     RustCode,
     PreprocCode,
     CcommentCode,
-    JavaCode,
     KotlinCode
 );
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        tools::check_metrics, CppParser, CsharpParser, GoParser, JavaParser, JavascriptParser,
-        KotlinParser, LuaParser, ParserEngineRust, PythonParser, TypescriptParser,
+        tools::{check_func_space, check_metrics},
+        CppParser, CsharpParser, GoParser, JavaParser, JavascriptParser, KotlinParser, LuaParser,
+        ParserEngineRust, PythonParser, SpaceKind, TypescriptParser,
     };
 
     #[test]
@@ -1649,6 +1707,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn java_record_components() {
+        check_func_space::<JavaParser, _>(
+            "record Point(int x, int y) {
+                 double norm() { return Math.sqrt(x * x + y * y); }
+             }",
+            "foo.java",
+            |space| {
+                let record = &space.spaces[0];
+                assert_eq!(record.name.as_deref(), Some("Point"));
+                assert_eq!(record.kind, SpaceKind::Class);
+                // The components are the arguments of the implicit
+                // canonical constructor
+                assert_eq!(record.metrics.nargs.fn_args(), 2.);
+                // which counts as a method, along with `norm`
+                assert_eq!(space.metrics.nom.methods_sum(), 2.);
+                assert_eq!(space.metrics.nargs.fn_args_average(), 1.);
+                assert_eq!(space.metrics.nargs.fn_args_max(), 2.);
+                assert_eq!(space.metrics.nargs.fn_args_min(), 0.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_sealed_types() {
+        check_func_space::<JavaParser, _>(
+            "sealed interface Shape permits Circle, Square {
+                 double area();
+             }
+             record Circle(double radius) implements Shape {
+                 public double area() { return Math.PI * radius * radius; }
+             }
+             non-sealed class Square implements Shape {
+                 public double area() { return 1; }
+             }",
+            "foo.java",
+            |space| {
+                let types: Vec<_> = space
+                    .spaces
+                    .iter()
+                    .map(|space| (space.name.as_deref(), space.kind))
+                    .collect();
+                assert_eq!(
+                    types,
+                    [
+                        (Some("Shape"), SpaceKind::Interface),
+                        (Some("Circle"), SpaceKind::Class),
+                        (Some("Square"), SpaceKind::Class)
+                    ]
+                );
+                // The implicit constructor of `Circle` and the three `area`
+                // methods, the abstract one included
+                assert_eq!(space.metrics.nom.functions_sum(), 4.);
+                assert_eq!(space.metrics.nargs.fn_args_sum(), 1.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_record_compact_constructor() {
+        check_func_space::<JavaParser, _>(
+            "record Range(int start, int end) {
+                 Range {
+                     if (start > end) throw new IllegalArgumentException();
+                 }
+             }",
+            "foo.java",
+            |space| {
+                let record = &space.spaces[0];
+                assert_eq!(record.metrics.nargs.fn_args(), 0.);
+                let constructor = &record.spaces[0];
+                assert_eq!(constructor.name.as_deref(), Some("Range"));
+                assert_eq!(constructor.kind, SpaceKind::Function);
+                assert_eq!(constructor.metrics.nargs.fn_args(), 2.);
+            },
+        );
+    }
+
     #[test]
     fn typescript_nargs_no_args() {
         check_metrics::<TypescriptParser>(
@@ -1,14 +1,12 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
-    checker::Checker, macros::implement_metric_trait, node::Node, CcommentCode, CppCode,
-    CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode, KotlinCode,
-    LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    checker::Checker, macros::implement_metric_trait, nargs::declares_canonical_constructor,
+    node::Node, output::MetricStruct, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode,
+    GleamCode, GoCode, GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode,
+    PerlCode, PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 #[inline]
@@ -30,7 +28,10 @@ pub struct Stats {
     functions_max: usize,
     closures_min: usize,
     closures_max: usize,
+    methods: usize,
+    methods_sum: usize,
     space_count: usize,
+    member: bool,
 }
 
 impl Default for Stats {
@@ -44,7 +45,10 @@ impl Default for Stats {
             functions_max: 0,
             closures_min: usize::MAX,
             closures_max: 0,
+            methods: 0,
+            methods_sum: 0,
             space_count: 1,
+            member: false,
         }
     }
 }
@@ -54,7 +58,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("nom", 10)?;
+        let mut st = MetricStruct::new(serializer, "nom", 13)?;
         st.serialize_field("functions", &self.functions_sum())?;
         st.serialize_field("closures", &self.closures_sum())?;
         st.serialize_field("functions_average", &self.functions_average())?;
@@ -65,6 +69,9 @@ impl Serialize for Stats {
         st.serialize_field("functions_max", &self.functions_max())?;
         st.serialize_field("closures_min", &self.closures_min())?;
         st.serialize_field("closures_max", &self.closures_max())?;
+        st.serialize_field("nom_functions", &self.free_functions_sum())?;
+        st.serialize_field("nom_methods", &self.methods_sum())?;
+        st.serialize_field("nom_closures", &self.closures_sum())?;
         st.end()
     }
 }
@@ -82,7 +89,10 @@ impl fmt::Display for Stats {
              functions_min: {} \
              functions_max: {} \
              closures_min: {} \
-             closures_max: {}",
+             closures_max: {} \
+             nom_functions: {} \
+             nom_methods: {} \
+             nom_closures: {}",
             self.functions_sum(),
             self.closures_sum(),
             self.functions_average(),
@@ -93,6 +103,9 @@ impl fmt::Display for Stats {
             self.functions_max(),
             self.closures_min(),
             self.closures_max(),
+            self.free_functions_sum(),
+            self.methods_sum(),
+            self.closures_sum(),
         )
     }
 }
@@ -111,6 +124,7 @@ impl Stats {
         self.closures_max = self.closures_max.max(other.closures_max);
         self.functions_sum += other.functions_sum;
         self.closures_sum += other.closures_sum;
+        self.methods_sum += other.methods_sum;
         self.space_count += other.space_count;
     }
 
@@ -129,6 +143,13 @@ impl Stats {
         usize_to_f64(self.closures)
     }
 
+    /// Counts the number of methods in a scope
+    #[inline]
+    #[must_use]
+    pub fn methods(&self) -> f64 {
+        usize_to_f64(self.methods)
+    }
+
     /// Return the sum metric for functions
     #[inline]
     #[must_use]
@@ -144,6 +165,22 @@ impl Stats {
         usize_to_f64(self.closures_sum)
     }
 
+    /// Return the sum metric for methods, i.e. the functions defined
+    /// as members of a class, struct, interface, trait or implementation
+    #[inline]
+    #[must_use]
+    pub fn methods_sum(&self) -> f64 {
+        usize_to_f64(self.methods_sum)
+    }
+
+    /// Return the sum metric for free functions, i.e. the functions
+    /// which are not methods
+    #[inline]
+    #[must_use]
+    pub fn free_functions_sum(&self) -> f64 {
+        usize_to_f64(self.functions_sum - self.methods_sum)
+    }
+
     /// Returns the average number of function definitions over all spaces
     #[inline]
     #[must_use]
@@ -204,6 +241,7 @@ impl Stats {
     pub(crate) fn compute_sum(&mut self) {
         self.functions_sum += self.functions;
         self.closures_sum += self.closures;
+        self.methods_sum += self.methods;
     }
     #[inline]
     pub(crate) fn compute_minmax(&mut self) {
@@ -213,6 +251,13 @@ impl Stats {
         self.closures_max = self.closures_max.max(self.closures);
         self.compute_sum();
     }
+
+    // Marks the space as a member of a class, struct, interface, trait or
+    // implementation, so its function is counted as a method
+    #[inline]
+    pub(crate) fn set_member(&mut self, member: bool) {
+        self.member = member;
+    }
 }
 
 pub trait Nom
@@ -220,13 +265,33 @@ where
     Self: Checker,
 {
     fn compute(node: &Node, stats: &mut Stats) {
-        if Self::is_func(node) {
-            stats.functions += 1;
-            return;
+        count_functions::<Self>(node, stats);
+    }
+}
+
+#[inline]
+fn count_functions<T: Checker>(node: &Node, stats: &mut Stats) {
+    if T::is_func(node) {
+        stats.functions += 1;
+        if stats.member {
+            stats.methods += 1;
         }
-        if Self::is_closure(node) {
-            stats.closures += 1;
+        return;
+    }
+    if T::is_closure(node) {
+        stats.closures += 1;
+    }
+}
+
+impl Nom for JavaCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        // An implicit canonical constructor has no node, so it counts as a
+        // method of the record, as its arguments count toward the record
+        if node.kind() == "record_declaration" && !declares_canonical_constructor(node) {
+            stats.functions += 1;
+            stats.methods += 1;
         }
+        count_functions::<Self>(node, stats);
     }
 }
 
@@ -241,14 +306,16 @@ implement_metric_trait!(
     RustCode,
     PreprocCode,
     CcommentCode,
-    JavaCode,
     KotlinCode,
     ElixirCode,
     ErlangCode,
     GleamCode,
     LuaCode,
     GoCode,
-    CsharpCode
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
 );
 
 #[cfg(test)]
@@ -284,7 +351,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 2.0
+                  "closures_max": 2.0,
+                  "nom_functions": 3.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 2.0
                 }
                 "#
                 );
@@ -314,7 +384,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 1.0
+                      "closures_max": 1.0,
+                      "nom_functions": 2.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 1.0
                     }"###
                 );
             },
@@ -345,7 +418,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -377,7 +453,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 1.0
+                  "closures_max": 1.0,
+                  "nom_functions": 0.0,
+                  "nom_methods": 2.0,
+                  "nom_closures": 1.0
                 }
                 "#
                 );
@@ -418,7 +497,44 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 4.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
+                }
+                "#
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_free_function_and_closure_nom() {
+        check_metrics::<JavascriptParser>(
+            "function add(a, b) {
+                 return a + b;
+             }
+             const double = (x) => x * 2;",
+            "foo.js",
+            |metric| {
+                // Number of spaces = 3
+                insta::assert_json_snapshot!(
+                    metric.nom,
+                    @r#"
+                {
+                  "functions": 1.0,
+                  "closures": 1.0,
+                  "functions_average": 0.3333333333333333,
+                  "closures_average": 0.3333333333333333,
+                  "total": 2.0,
+                  "average": 0.6666666666666666,
+                  "functions_min": 0.0,
+                  "functions_max": 1.0,
+                  "closures_min": 0.0,
+                  "closures_max": 1.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 1.0
                 }
                 "#
                 );
@@ -449,7 +565,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -477,7 +596,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -507,7 +629,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -538,7 +663,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 0.0
+                      "closures_max": 0.0,
+                      "nom_functions": 1.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 0.0
                     }"###
                 );
             },
@@ -569,7 +697,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 1.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -601,7 +732,10 @@ mod tests {
                   "functions_min": 0.0,
                   "functions_max": 1.0,
                   "closures_min": 0.0,
-                  "closures_max": 0.0
+                  "closures_max": 0.0,
+                  "nom_functions": 2.0,
+                  "nom_methods": 0.0,
+                  "nom_closures": 0.0
                 }
                 "#
                 );
@@ -634,7 +768,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 1.0
+                      "closures_max": 1.0,
+                      "nom_functions": 1.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 1.0
                     }"###
                 );
             },
@@ -659,7 +796,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 0.0
+                      "closures_max": 0.0,
+                      "nom_functions": 1.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 0.0
                     }"###
             );
         });
@@ -686,7 +826,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 0.0,
                       "closures_min": 0.0,
-                      "closures_max": 1.0
+                      "closures_max": 1.0,
+                      "nom_functions": 0.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 1.0
                     }"###
                 );
             },
@@ -716,7 +859,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 0.0,
                       "closures_min": 0.0,
-                      "closures_max": 1.0
+                      "closures_max": 1.0,
+                      "nom_functions": 0.0,
+                      "nom_methods": 0.0,
+                      "nom_closures": 1.0
                     }"###
                 );
             },
@@ -750,7 +896,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 0.0
+                      "closures_max": 0.0,
+                      "nom_functions": 0.0,
+                      "nom_methods": 2.0,
+                      "nom_closures": 0.0
                     }"###
                 );
             },
@@ -794,7 +943,10 @@ mod tests {
                       "functions_min": 0.0,
                       "functions_max": 1.0,
                       "closures_min": 0.0,
-                      "closures_max": 1.0
+                      "closures_max": 1.0,
+                      "nom_functions": 0.0,
+                      "nom_methods": 4.0,
+                      "nom_closures": 1.0
                     }"###
                 );
             },
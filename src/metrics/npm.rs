@@ -1,15 +1,12 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
     checker::Checker, language_java::Java, macros::implement_metric_trait, node::Node,
-    traits::Search, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode,
-    JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode,
-    TsxCode, TypescriptCode,
+    output::MetricStruct, traits::Search, CcommentCode, CppCode, CsharpCode, ElixirCode,
+    ErlangCode, GleamCode, GoCode, GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode,
+    MozjsCode, PerlCode, PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 #[inline]
@@ -42,7 +39,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("npm", 9)?;
+        let mut st = MetricStruct::new(serializer, "npm", 9)?;
         st.serialize_field("classes", &self.class_npm_sum())?;
         st.serialize_field("interfaces", &self.interface_npm_sum())?;
         st.serialize_field("class_methods", &self.class_nm_sum())?;
@@ -294,7 +291,10 @@ implement_metric_trait!(
     CcommentCode,
     KotlinCode,
     GoCode,
-    CsharpCode
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
 );
 
 #[cfg(test)]
@@ -0,0 +1,199 @@
+use std::fmt;
+
+use serde::{ser::Serializer, Serialize};
+
+use crate::{
+    checker::Checker, macros::implement_metric_trait, node::Node, output::MetricStruct,
+    CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, GraphqlCode,
+    JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode, PreprocCode, PythonCode,
+    RCode, RustCode, TsxCode, TypescriptCode,
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// The `Template` metric.
+///
+/// This metric counts the `template` declarations of a space and measures
+/// how deeply template argument lists are nested, e.g. `2` for
+/// `std::vector<std::map<int, std::string>>`. It is only computed for
+/// `C++` code.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    declarations: usize,
+    depth: usize,
+    declarations_sum: usize,
+    depth_max: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = MetricStruct::new(serializer, "template", 2)?;
+        st.serialize_field("template_count", &self.declarations_sum())?;
+        st.serialize_field("template_depth", &self.depth_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "template_count: {}, template_depth: {}",
+            self.declarations_sum(),
+            self.depth_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Template` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.declarations_sum += other.declarations_sum;
+        self.depth_max = self.depth_max.max(other.depth_max);
+        self.is_enabled |= other.is_enabled;
+    }
+
+    /// Returns the number of `template` declarations in a space.
+    #[inline]
+    #[must_use]
+    pub fn declarations(&self) -> f64 {
+        usize_to_f64(self.declarations)
+    }
+
+    /// Returns the deepest nesting of template argument lists in a space.
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> f64 {
+        usize_to_f64(self.depth)
+    }
+
+    /// Returns the number of `template` declarations in a space
+    /// and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn declarations_sum(&self) -> f64 {
+        usize_to_f64(self.declarations_sum)
+    }
+
+    /// Returns the deepest nesting of template argument lists
+    /// in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn depth_max(&self) -> f64 {
+        usize_to_f64(self.depth_max)
+    }
+
+    #[inline]
+    pub(crate) fn compute_sum(&mut self) {
+        self.declarations_sum += self.declarations;
+        self.depth_max = self.depth_max.max(self.depth);
+    }
+
+    // Checks if the `Template` metric is disabled
+    #[inline]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Template
+where
+    Self: Checker,
+{
+    fn compute(_node: &Node, _stats: &mut Stats) {}
+}
+
+impl Template for CppCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        stats.is_enabled = true;
+
+        match node.kind() {
+            "template_declaration" => {
+                stats.declarations += 1;
+            }
+            "template_argument_list" => {
+                // Only the argument lists inside the space of the node are counted
+                let depth = 1 + node
+                    .ancestors()
+                    .take_while(|ancestor| {
+                        !Self::is_func(ancestor) && !Self::is_func_space(ancestor)
+                    })
+                    .filter(|ancestor| ancestor.kind() == "template_argument_list")
+                    .count();
+                stats.depth = stats.depth.max(depth);
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(
+    [Template],
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    RustCode,
+    PreprocCode,
+    CcommentCode,
+    JavaCode,
+    KotlinCode,
+    ElixirCode,
+    ErlangCode,
+    GleamCode,
+    LuaCode,
+    GoCode,
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{tools::check_metrics, CppParser, PythonParser};
+
+    #[test]
+    fn cpp_nested_template_arguments() {
+        check_metrics::<CppParser>(
+            "template <typename T>
+             T first(const std::vector<T>& items) {
+                 return items[0];
+             }
+
+             void index() {
+                 std::vector<std::map<int, std::string>> table;
+             }",
+            "foo.cpp",
+            |metric| {
+                // vector > map
+                insta::assert_json_snapshot!(
+                    metric.template,
+                    @r###"
+                    {
+                      "template_count": 1.0,
+                      "template_depth": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_template_disabled() {
+        check_metrics::<PythonParser>("def f():\n    pass", "foo.py", |metric| {
+            assert!(metric.template.is_disabled());
+        });
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+
+use serde::{ser::Serializer, Serialize};
+
+use crate::{
+    checker::Checker, language_rust::Rust, macros::implement_metric_trait, node::Node,
+    output::MetricStruct, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode,
+    GoCode, GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode,
+    PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
+};
+
+#[inline]
+fn usize_to_f64(value: usize) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        value as f64
+    }
+}
+
+/// The `Unsafe` metric.
+///
+/// This metric counts the `unsafe` blocks and the `unsafe` functions
+/// of a space. It is only computed for `Rust` code.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    blocks: usize,
+    functions: usize,
+    blocks_sum: usize,
+    functions_sum: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = MetricStruct::new(serializer, "unsafe_count", 3)?;
+        st.serialize_field("blocks", &self.blocks_sum())?;
+        st.serialize_field("functions", &self.functions_sum())?;
+        st.serialize_field("total", &self.total())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "blocks: {}, functions: {}, total: {}",
+            self.blocks_sum(),
+            self.functions_sum(),
+            self.total()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Unsafe` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.blocks_sum += other.blocks_sum;
+        self.functions_sum += other.functions_sum;
+        self.is_enabled |= other.is_enabled;
+    }
+
+    /// Returns the number of `unsafe` blocks in a space.
+    #[inline]
+    #[must_use]
+    pub fn blocks(&self) -> f64 {
+        usize_to_f64(self.blocks)
+    }
+
+    /// Returns the number of `unsafe` functions in a space.
+    #[inline]
+    #[must_use]
+    pub fn functions(&self) -> f64 {
+        usize_to_f64(self.functions)
+    }
+
+    /// Returns the number of `unsafe` blocks in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn blocks_sum(&self) -> f64 {
+        usize_to_f64(self.blocks_sum)
+    }
+
+    /// Returns the number of `unsafe` functions in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn functions_sum(&self) -> f64 {
+        usize_to_f64(self.functions_sum)
+    }
+
+    /// Returns the total number of `unsafe` blocks and functions
+    /// in a space and its subspaces.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.blocks_sum() + self.functions_sum()
+    }
+
+    #[inline]
+    pub(crate) fn compute_sum(&mut self) {
+        self.blocks_sum += self.blocks;
+        self.functions_sum += self.functions;
+    }
+
+    // Checks if the `Unsafe` metric is disabled
+    #[inline]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Unsafety
+where
+    Self: Checker,
+{
+    fn compute(_node: &Node, _stats: &mut Stats) {}
+}
+
+impl Unsafety for RustCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            Rust::UnsafeBlock => stats.blocks += 1,
+            Rust::FunctionItem | Rust::FunctionSignatureItem => {
+                let is_unsafe = node
+                    .children()
+                    .filter(|child| child.kind_id() == Rust::FunctionModifiers)
+                    .flat_map(|modifiers| modifiers.children())
+                    .any(|modifier| modifier.kind_id() == Rust::Unsafe);
+                if is_unsafe {
+                    stats.functions += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(
+    [Unsafety],
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    CppCode,
+    PreprocCode,
+    CcommentCode,
+    JavaCode,
+    KotlinCode,
+    ElixirCode,
+    ErlangCode,
+    GleamCode,
+    LuaCode,
+    GoCode,
+    CsharpCode,
+    RCode,
+    PerlCode,
+    GraphqlCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{tools::check_metrics, ParserEngineRust, PythonParser};
+
+    #[test]
+    fn rust_unsafe_blocks() {
+        check_metrics::<ParserEngineRust>(
+            "fn f(p: *const u8) -> u8 {
+                 let a = unsafe { *p };
+                 let b = unsafe { *p.add(1) };
+                 a + b
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.unsafe_count,
+                    @r###"
+                    {
+                      "blocks": 2.0,
+                      "functions": 0.0,
+                      "total": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_unsafe_functions() {
+        check_metrics::<ParserEngineRust>(
+            "unsafe fn f(p: *const u8) -> u8 {
+                 *p
+             }
+             fn g(p: *const u8) -> u8 {
+                 unsafe { f(p) }
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.unsafe_count,
+                    @r###"
+                    {
+                      "blocks": 1.0,
+                      "functions": 1.0,
+                      "total": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_safe_code() {
+        check_metrics::<ParserEngineRust>(
+            "fn f(a: u8, b: u8) -> u8 {
+                 a + b
+             }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.unsafe_count,
+                    @r###"
+                    {
+                      "blocks": 0.0,
+                      "functions": 0.0,
+                      "total": 0.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_unsafe_disabled() {
+        check_metrics::<PythonParser>("def f():\n    pass", "foo.py", |metric| {
+            assert!(metric.unsafe_count.is_disabled());
+        });
+    }
+}
@@ -1,14 +1,12 @@
 use std::fmt;
 
-use serde::{
-    ser::{SerializeStruct, Serializer},
-    Serialize,
-};
+use serde::{ser::Serializer, Serialize};
 
 use crate::{
-    checker::Checker, cyclomatic, macros::implement_metric_trait, spaces::SpaceKind, CcommentCode,
-    CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode, GoCode, JavaCode, JavascriptCode,
-    KotlinCode, LuaCode, MozjsCode, PreprocCode, PythonCode, RustCode, TsxCode, TypescriptCode,
+    checker::Checker, cyclomatic, macros::implement_metric_trait, output::MetricStruct,
+    spaces::SpaceKind, CcommentCode, CppCode, CsharpCode, ElixirCode, ErlangCode, GleamCode,
+    GoCode, GraphqlCode, JavaCode, JavascriptCode, KotlinCode, LuaCode, MozjsCode, PerlCode,
+    PreprocCode, PythonCode, RCode, RustCode, TsxCode, TypescriptCode,
 };
 
 // Historical note: tree-sitter-java 0.19.0 failed to recognise modern `switch` constructs
@@ -19,6 +17,7 @@ use crate::{
 /// The `Wmc` metric.
 ///
 /// This metric sums the cyclomatic complexities of all the methods defined in a class.
+/// Structs count as classes.
 /// The `Wmc` (Weighted Methods per Class) is an object-oriented metric for classes.
 ///
 /// Original paper and definition:
@@ -38,7 +37,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("wmc", 3)?;
+        let mut st = MetricStruct::new(serializer, "wmc", 3)?;
         st.serialize_field("classes", &self.class_wmc_sum())?;
         st.serialize_field("interfaces", &self.interface_wmc_sum())?;
         st.serialize_field("total", &self.total_wmc())?;
@@ -65,7 +64,7 @@ impl Stats {
         // into the `Wmc` metric value of a class or interface
         if other.space_kind == SpaceKind::Function {
             match self.space_kind {
-                SpaceKind::Class => self.class_wmc += other.cyclomatic,
+                SpaceKind::Class | SpaceKind::Struct => self.class_wmc += other.cyclomatic,
                 SpaceKind::Interface => self.interface_wmc += other.cyclomatic,
                 _ => {}
             }
@@ -132,46 +131,73 @@ where
     fn compute(space_kind: SpaceKind, cyclomatic: &cyclomatic::Stats, stats: &mut Stats);
 }
 
-impl Wmc for JavaCode {
-    fn compute(space_kind: SpaceKind, cyclomatic: &cyclomatic::Stats, stats: &mut Stats) {
-        match space_kind {
-            SpaceKind::Unit | SpaceKind::Class | SpaceKind::Interface | SpaceKind::Function => {
-                if stats.space_kind == SpaceKind::Unknown {
-                    stats.space_kind = space_kind;
-                }
-                if matches!(space_kind, SpaceKind::Function) {
-                    // Saves the cyclomatic complexity of the method
-                    stats.cyclomatic = cyclomatic.cyclomatic_sum();
-                }
+// Keeps the kind of the space and, for a method, its cyclomatic
+// complexity, which is merged into the `Wmc` of its class
+fn compute_oo_space(space_kind: SpaceKind, cyclomatic: &cyclomatic::Stats, stats: &mut Stats) {
+    match space_kind {
+        SpaceKind::Unit
+        | SpaceKind::Class
+        | SpaceKind::Struct
+        | SpaceKind::Interface
+        | SpaceKind::Function => {
+            if stats.space_kind == SpaceKind::Unknown {
+                stats.space_kind = space_kind;
+            }
+            if matches!(space_kind, SpaceKind::Function) {
+                // Saves the cyclomatic complexity of the method
+                stats.cyclomatic = cyclomatic.cyclomatic_sum();
             }
-            _ => {}
         }
+        _ => {}
     }
 }
 
+macro_rules! implement_oo_wmc {
+    ($($code:ident),+) => (
+        $(
+            impl Wmc for $code {
+                fn compute(
+                    space_kind: SpaceKind,
+                    cyclomatic: &cyclomatic::Stats,
+                    stats: &mut Stats,
+                ) {
+                    compute_oo_space(space_kind, cyclomatic, stats);
+                }
+            }
+        )+
+    );
+}
+
+implement_oo_wmc!(
+    JavaCode,
+    CsharpCode,
+    KotlinCode,
+    CppCode,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode
+);
+
 implement_metric_trait!(
     Wmc,
     ElixirCode,
     ErlangCode,
     GleamCode,
     LuaCode,
-    PythonCode,
-    MozjsCode,
-    JavascriptCode,
-    TypescriptCode,
-    TsxCode,
     RustCode,
-    CppCode,
     PreprocCode,
     CcommentCode,
-    KotlinCode,
     GoCode,
-    CsharpCode
+    RCode,
+    PerlCode,
+    GraphqlCode
 );
 
 #[cfg(test)]
 mod tests {
-    use crate::{tools::check_metrics, JavaParser};
+    use crate::{tools::check_metrics, CsharpParser, JavaParser, PythonParser};
 
     #[test]
     fn java_single_class() {
@@ -682,4 +708,70 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn python_class_two_methods() {
+        check_metrics::<PythonParser>(
+            "class A: # wmc = 5
+                 def m1(self, a): # +1
+                     if a: # +1
+                         return 1
+                     return 0
+
+                 def m2(self, a, b): # +1
+                     if a: # +1
+                         return 1
+                     elif b: # +1
+                         return 2
+                     return 0",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.wmc,
+                    @r###"
+                    {
+                      "classes": 5.0,
+                      "interfaces": 0.0,
+                      "total": 5.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn csharp_class_and_interface() {
+        check_metrics::<CsharpParser>(
+            "interface IShape { // wmc = 1
+                 double Area() { // +1
+                     return 0;
+                 }
+             }
+
+             class Square { // wmc = 3
+                 int Side(int a) { // +1
+                     if (a < 0) { // +1
+                         return -a;
+                     }
+                     return a;
+                 }
+
+                 int Zero() { // +1
+                     return 0;
+                 }
+             }",
+            "foo.cs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.wmc,
+                    @r###"
+                    {
+                      "classes": 3.0,
+                      "interfaces": 1.0,
+                      "total": 4.0
+                    }"###
+                );
+            },
+        );
+    }
 }
@@ -128,6 +128,9 @@ fn parse_language_hint(hint: &str) -> LANG {
         "erlang" => LANG::Erlang,
         "gleam" => LANG::Gleam,
         "lua" => LANG::Lua,
+        "r" => LANG::R,
+        "perl" => LANG::Perl,
+        "graphql" => LANG::Graphql,
         _ => LANG::Rust, // Default fallback
     }
 }
@@ -1,7 +1,13 @@
-use tree_sitter::{Node as OtherNode, Parser, Tree as OtherTree, TreeCursor};
+use std::{ops::ControlFlow, time::Instant};
+
+use tree_sitter::{
+    Node as OtherNode, ParseOptions, ParseState, Parser, Point, Tree as OtherTree, TreeCursor,
+};
 
 use crate::{
+    analysis_context::{mark_timed_out, parse_deadline},
     checker::Checker,
+    langs::LANG,
     traits::{LanguageInfo, Search},
 };
 
@@ -9,12 +15,35 @@ use crate::{
 pub(crate) struct Tree(OtherTree);
 
 impl Tree {
-    pub(crate) fn new<T: LanguageInfo>(code: &[u8]) -> Self {
+    pub(crate) fn new<T: LanguageInfo>(code: &[u8]) -> Option<Self> {
+        Self::with_lang(code, &T::get_lang())
+    }
+
+    // Parses `code`, or returns `None` when the grammar of `lang` cannot be
+    // loaded, i.e. it was generated for another version of tree-sitter
+    pub(crate) fn with_lang(code: &[u8], lang: &LANG) -> Option<Self> {
         let mut parser = Parser::new();
-        parser
-            .set_language(&T::get_lang().get_ts_language()).expect("TODO: Add context for why this shouldn't fail");
+        parser.set_language(&lang.get_ts_language()).ok()?;
+
+        if let Some(deadline) = parse_deadline() {
+            let mut expired = |_: &ParseState| Instant::now() >= deadline;
+            let options = ParseOptions::new().progress_callback(&mut expired);
+            let mut read = |offset: usize, _: Point| code.get(offset..).unwrap_or_default();
+            if let Some(tree) = parser.parse_with_options(&mut read, None, Some(options)) {
+                return Some(Self(tree));
+            }
+            // The parse has been cancelled, so an empty code is analyzed instead
+            mark_timed_out();
+            parser.reset();
+            return parser.parse(b"", None).map(Self);
+        }
+
+        parser.parse(code, None).map(Self)
+    }
 
-        Self(parser.parse(code, None).expect("TODO: Add context for why this shouldn't fail"))
+    // Wraps a tree parsed outside of this crate
+    pub(crate) fn from_ts_tree(tree: OtherTree) -> Self {
+        Self(tree)
     }
 
     pub(crate) fn get_root(&self) -> Node<'_> {
@@ -34,11 +63,21 @@ impl<'a> Node<'a> {
         self.0.has_error()
     }
 
+    pub(crate) fn is_error(&self) -> bool {
+        self.0.is_error()
+    }
+
+    pub(crate) fn is_missing(&self) -> bool {
+        self.0.is_missing()
+    }
+
     pub(crate) fn id(&self) -> usize {
         self.0.id()
     }
 
-    pub(crate) fn kind(&self) -> &'static str {
+    /// Returns the kind of a node, e.g. `function_definition`.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
         self.0.kind()
     }
 
@@ -46,18 +85,42 @@ impl<'a> Node<'a> {
         self.0.kind_id()
     }
 
+    pub(crate) fn get_ts_node(&self) -> OtherNode<'a> {
+        self.0
+    }
+
+    pub(crate) fn to_sexp(&self) -> String {
+        self.0.to_sexp()
+    }
+
     pub(crate) fn utf8_text(&self, data: &'a [u8]) -> Option<&'a str> {
         self.0.utf8_text(data).ok()
     }
 
-    pub(crate) fn start_byte(&self) -> usize {
+    /// Returns the byte offset where a node starts.
+    #[must_use]
+    pub fn start_byte(&self) -> usize {
         self.0.start_byte()
     }
 
-    pub(crate) fn end_byte(&self) -> usize {
+    /// Returns the byte offset where a node ends.
+    #[must_use]
+    pub fn end_byte(&self) -> usize {
         self.0.end_byte()
     }
 
+    /// Returns the smallest node within this node spanning the byte range
+    /// from `start` to `end`.
+    ///
+    /// Returns `None` if the range is reversed or not contained in this node.
+    #[must_use]
+    pub fn descendant_for_byte_range(&self, start: usize, end: usize) -> Option<Node<'a>> {
+        if start > end || start < self.start_byte() || end > self.end_byte() {
+            return None;
+        }
+        self.0.descendant_for_byte_range(start, end).map(Node)
+    }
+
     pub(crate) fn start_position(&self) -> (usize, usize) {
         let temp = self.0.start_position();
         (temp.row, temp.column)
@@ -109,6 +172,33 @@ impl<'a> Node<'a> {
         None
     }
 
+    /// Returns the position of this node among the named children of its
+    /// parent, or `0` for the root.
+    #[must_use]
+    pub fn named_sibling_index(&self) -> usize {
+        self.0.parent().map_or(0, |parent| {
+            parent
+                .named_children(&mut parent.walk())
+                .position(|child| child.id() == self.0.id())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns how many named children of the parent of this node, this
+    /// node included, have the given kind.
+    #[must_use]
+    pub fn named_sibling_count_of_kind(&self, kind_id: u16) -> usize {
+        self.0.parent().map_or_else(
+            || usize::from(self.0.kind_id() == kind_id),
+            |parent| {
+                parent
+                    .named_children(&mut parent.walk())
+                    .filter(|child| child.kind_id() == kind_id)
+                    .count()
+            },
+        )
+    }
+
     #[inline]
     pub(crate) fn is_child(&self, id: u16) -> bool {
         self.0
@@ -124,7 +214,7 @@ impl<'a> Node<'a> {
         self.0.child_count()
     }
 
-    pub(crate) fn child_by_field_name(&self, name: &str) -> Option<Node<'_>> {
+    pub(crate) fn child_by_field_name(&self, name: &str) -> Option<Node<'a>> {
         self.0.child_by_field_name(name).map(Node)
     }
 
@@ -148,42 +238,42 @@ impl<'a> Node<'a> {
 
     // get_parent() method removed - never called anywhere in codebase
 
+    /// Returns an iterator over the ancestors of this node, starting from
+    /// its parent and ending with the root of the tree.
+    pub(crate) fn ancestors(&self) -> AncestorIter<'a> {
+        AncestorIter(self.parent())
+    }
+
     pub(crate) fn count_specific_ancestors<T: crate::ParserTrait>(
         &self,
         check: fn(&Node) -> bool,
         stop: fn(&Node) -> bool,
     ) -> usize {
-        let mut count = 0;
-        let mut node = *self;
-        while let Some(parent) = node.parent() {
-            if stop(&parent) {
-                break;
-            }
-            if check(&parent) && !T::Checker::is_else_if(&parent) {
-                count += 1;
-            }
-            node = parent;
-        }
-        count
+        self.ancestors()
+            .take_while(|parent| !stop(parent))
+            .filter(|parent| check(parent) && !T::Checker::is_else_if(parent))
+            .count()
     }
+}
 
-    pub(crate) fn has_ancestors(&self, typ: fn(&Node) -> bool, typs: fn(&Node) -> bool) -> bool {
-        let mut res = false;
-        let mut node = *self;
-        if let Some(parent) = node.parent() {
-            if typ(&parent) {
-                node = parent;
-            }
-        }
-        if let Some(parent) = node.parent() {
-            if typs(&parent) {
-                res = true;
-            }
-        }
-        res
+/// An iterator over the ancestors of an `AST` node.
+///
+/// It is created by the `ancestors` method of a [`Node`].
+#[derive(Clone, Debug)]
+pub struct AncestorIter<'a>(Option<Node<'a>>);
+
+impl<'a> Iterator for AncestorIter<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.0?;
+        self.0 = node.parent();
+        Some(node)
     }
 }
 
+impl std::iter::FusedIterator for AncestorIter<'_> {}
+
 /// An `AST` cursor.
 #[derive(Clone)]
 pub struct Cursor<'a>(TreeCursor<'a>);
@@ -208,58 +298,38 @@ impl<'a> Cursor<'a> {
 
 impl<'a> Search<'a> for Node<'a> {
     fn first_occurrence(&self, pred: fn(u16) -> bool) -> Option<Node<'a>> {
-        let mut cursor = self.cursor();
-        let mut stack = Vec::new();
-        let mut children = Vec::new();
-
-        stack.push(*self);
-
-        while let Some(node) = stack.pop() {
+        self.try_for_each(&mut |node| {
             if pred(node.kind_id()) {
-                return Some(node);
+                ControlFlow::Break(*node)
+            } else {
+                ControlFlow::Continue(())
             }
-            cursor.reset(&node);
-            if cursor.goto_first_child() {
-                loop {
-                    children.push(cursor.node());
-                    if !cursor.goto_next_sibling() {
-                        break;
-                    }
-                }
-                for child in std::mem::take(&mut children).into_iter().rev() {
-                    stack.push(child);
-                }
-            }
-        }
-
-        None
+        })
+        .break_value()
     }
 
     fn act_on_node(&self, action: &mut dyn FnMut(&Node<'a>)) {
-        let mut cursor = self.cursor();
-        let mut stack = Vec::new();
-        let mut children = Vec::new();
-
-        stack.push(*self);
+        let _: ControlFlow<()> = self.try_for_each(&mut |node| {
+            action(node);
+            ControlFlow::Continue(())
+        });
+    }
 
-        while let Some(node) = stack.pop() {
-            action(&node);
-            cursor.reset(&node);
-            if cursor.goto_first_child() {
-                loop {
-                    children.push(cursor.node());
-                    if !cursor.goto_next_sibling() {
-                        break;
-                    }
-                }
-                for child in std::mem::take(&mut children).into_iter().rev() {
-                    stack.push(child);
-                }
+    fn first_occurrence_kind(&self, pred: fn(&Node<'a>) -> bool) -> Option<Node<'a>> {
+        self.try_for_each(&mut |node| {
+            if pred(node) {
+                ControlFlow::Break(*node)
+            } else {
+                ControlFlow::Continue(())
             }
-        }
+        })
+        .break_value()
     }
 
-    fn first_occurrence_kind(&self, pred: fn(&Node<'a>) -> bool) -> Option<Node<'a>> {
+    fn try_for_each<B>(
+        &self,
+        visit: &mut dyn FnMut(&Node<'a>) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
         let mut cursor = self.cursor();
         let mut stack = Vec::new();
         let mut children = Vec::new();
@@ -267,9 +337,7 @@ impl<'a> Search<'a> for Node<'a> {
         stack.push(*self);
 
         while let Some(node) = stack.pop() {
-            if pred(&node) {
-                return Some(node);
-            }
+            visit(&node)?;
             cursor.reset(&node);
             if cursor.goto_first_child() {
                 loop {
@@ -284,7 +352,7 @@ impl<'a> Search<'a> for Node<'a> {
             }
         }
 
-        None
+        ControlFlow::Continue(())
     }
 
     fn first_child(&self, pred: fn(u16) -> bool) -> Option<Node<'a>> {
@@ -301,3 +369,92 @@ impl<'a> Search<'a> for Node<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GleamCode, PythonCode};
+
+    #[test]
+    fn try_for_each_stops_at_break() {
+        let code = b"def f(a):\n    if a:\n        return 1\n    return 2\n";
+        let tree = Tree::new::<PythonCode>(code).unwrap();
+        let root = tree.get_root();
+
+        let mut visited = Vec::new();
+        let found = root.try_for_each(&mut |node| {
+            visited.push(node.kind());
+            if node.kind() == "integer" {
+                ControlFlow::Break(node.start_byte())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        assert_eq!(found, ControlFlow::Break(code.iter().position(|c| *c == b'1').unwrap()));
+        assert_eq!(visited.last(), Some(&"integer"));
+        // The nodes of the second `return` come after the break
+        assert_eq!(visited.iter().filter(|kind| **kind == "return_statement").count(), 1);
+        assert_eq!(visited.iter().filter(|kind| **kind == "integer").count(), 1);
+
+        let mut count = 0;
+        let all = root.try_for_each(&mut |_| {
+            count += 1;
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(all, ControlFlow::Continue(()));
+        assert!(count > visited.len());
+    }
+
+    #[test]
+    fn ancestors_of_nested_node() {
+        let code = b"def f(a):\n    if a:\n        return 1\n";
+        let tree = Tree::new::<PythonCode>(code).unwrap();
+        let root = tree.get_root();
+        let integer = root
+            .first_occurrence_kind(|node| node.kind() == "integer")
+            .unwrap();
+
+        let kinds: Vec<&str> = integer.ancestors().map(|node| node.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "return_statement",
+                "block",
+                "if_statement",
+                "block",
+                "function_definition",
+                "module"
+            ]
+        );
+        assert_eq!(integer.ancestors().last().map(|node| node.id()), Some(root.id()));
+        assert_eq!(root.ancestors().count(), 0);
+    }
+
+    #[test]
+    fn named_sibling_index_of_case_clauses() {
+        let code = b"pub fn sign(x) {
+  case x {
+    0 -> 0
+    n if n > 0 -> 1
+    _ -> -1
+  }
+}
+";
+        let tree = Tree::new::<GleamCode>(code).unwrap();
+        let root = tree.get_root();
+        let mut clauses = Vec::new();
+        root.act_on_node(&mut |node| {
+            if node.kind() == "case_clause" {
+                clauses.push(*node);
+            }
+        });
+
+        let indices: Vec<usize> = clauses.iter().map(Node::named_sibling_index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+        for clause in &clauses {
+            assert_eq!(clause.named_sibling_count_of_kind(clause.kind_id()), 3);
+        }
+        assert_eq!(root.named_sibling_index(), 0);
+    }
+}
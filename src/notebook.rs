@@ -0,0 +1,260 @@
+//! Analysis of Jupyter notebooks.
+//!
+//! The code cells of a notebook are concatenated, in order, into a
+//! synthetic source which is analyzed as a single file. The line numbers of
+//! the returned spaces refer to this synthetic source, where the first line
+//! of a cell directly follows the last line of the previous one.
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    get_from_emacs_mode, get_function_spaces, CodeMetrics, FuncSpace, LineOffsetMap, SpaceKind,
+    LANG,
+};
+
+/// Options for analyzing a notebook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotebookOptions {
+    /// Whether markdown cells are analyzed as comments.
+    ///
+    /// When `true`, markdown cells are added to the synthetic source as line
+    /// comments, so they count toward the `CLOC` metric.
+    pub markdown_as_comments: bool,
+}
+
+/// Error returned when analyzing a notebook.
+#[derive(Debug)]
+pub enum NotebookError {
+    /// The notebook is not a valid `JSON` document.
+    Json(serde_json::Error),
+    /// The kernel language of the notebook is not supported.
+    UnsupportedLanguage(String),
+}
+
+impl fmt::Display for NotebookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotebookError::Json(err) => write!(f, "invalid notebook: {err}"),
+            NotebookError::UnsupportedLanguage(lang) => {
+                write!(f, "notebook language `{lang}` is not supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NotebookError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NotebookError::Json(err) => Some(err),
+            NotebookError::UnsupportedLanguage(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for NotebookError {
+    fn from(err: serde_json::Error) -> Self {
+        NotebookError::Json(err)
+    }
+}
+
+/// Checks if a path is a Jupyter notebook, i.e. it has an `ipynb` extension.
+#[must_use]
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Returns the function spaces of a Jupyter notebook.
+///
+/// The language of the code cells is read from the kernel metadata of the
+/// notebook and defaults to `Python`.
+///
+/// The metrics of the returned space cover the whole notebook, while each
+/// analyzed cell is one of its subspaces, named `cell <index>` after its
+/// index in the notebook. The functions defined in a cell are subspaces of
+/// the cell space.
+///
+/// # Errors
+///
+/// Returns [`NotebookError::Json`] if the notebook cannot be parsed and
+/// [`NotebookError::UnsupportedLanguage`] if its kernel language has no parser.
+pub fn get_notebook_spaces(
+    source: &[u8],
+    path: &Path,
+    options: &NotebookOptions,
+) -> Result<FuncSpace, NotebookError> {
+    notebook_spaces(source, path, options).map(|(_, space)| space)
+}
+
+// The spaces of a notebook along with the language of its code cells
+pub(crate) fn notebook_spaces(
+    source: &[u8],
+    path: &Path,
+    options: &NotebookOptions,
+) -> Result<(LANG, FuncSpace), NotebookError> {
+    let notebook: Value = serde_json::from_slice(source)?;
+    let language = notebook_language(&notebook);
+    let lang = get_from_emacs_mode(&language)
+        .ok_or_else(|| NotebookError::UnsupportedLanguage(language.clone()))?;
+    let comment = line_comment(lang);
+
+    let mut code = String::new();
+    let mut cells = Vec::new();
+    let mut line = 1;
+    let empty = Vec::new();
+    let notebook_cells = notebook
+        .get("cells")
+        .and_then(Value::as_array)
+        .unwrap_or(&empty);
+    for (index, cell) in notebook_cells.iter().enumerate() {
+        let mut cell_source = cell_source(cell);
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("code") => {}
+            Some("markdown") if options.markdown_as_comments => {
+                cell_source = cell_source
+                    .lines()
+                    .map(|text| format!("{comment} {text}\n"))
+                    .collect();
+            }
+            _ => continue,
+        }
+        if cell_source.trim().is_empty() {
+            continue;
+        }
+        if !cell_source.ends_with('\n') {
+            cell_source.push('\n');
+        }
+
+        let lines = cell_source.lines().count();
+        if let Some(mut space) =
+            get_function_spaces(&lang, cell_source.as_bytes().to_vec(), path, None)
+        {
+            let mut map = LineOffsetMap::new();
+            map.push(1, line);
+            map.map_space(&mut space);
+            space.name = Some(format!("cell {index}"));
+            space.start_line = line;
+            space.end_line = line + lines - 1;
+            cells.push(space);
+        }
+        code.push_str(&cell_source);
+        line += lines;
+    }
+
+    let mut notebook_space = get_function_spaces(&lang, code.into_bytes(), path, None)
+        .unwrap_or_else(|| FuncSpace {
+            name: None,
+            start_line: 0,
+            end_line: 0,
+            kind: SpaceKind::Unit,
+            spaces: Vec::new(),
+            metrics: CodeMetrics::default(),
+            supertypes: None,
+            signature: None,
+            decorators: Vec::new(),
+            content_hash: None,
+            custom: BTreeMap::new(),
+        });
+    notebook_space.name = path.to_str().map(ToString::to_string);
+    notebook_space.spaces = cells;
+    Ok((lang, notebook_space))
+}
+
+// The kernel language, from the kernel specification or the language info
+fn notebook_language(notebook: &Value) -> String {
+    let metadata = notebook.get("metadata");
+    metadata
+        .and_then(|metadata| metadata.get("kernelspec"))
+        .and_then(|kernel| kernel.get("language"))
+        .or_else(|| {
+            metadata
+                .and_then(|metadata| metadata.get("language_info"))
+                .and_then(|info| info.get("name"))
+        })
+        .and_then(Value::as_str)
+        .map_or_else(|| String::from("python"), str::to_lowercase)
+}
+
+// The source of a cell is either a string or a list of lines
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(source)) => source.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+fn line_comment(lang: LANG) -> &'static str {
+    match lang {
+        LANG::Python | LANG::R | LANG::Elixir | LANG::Perl | LANG::Graphql => "#",
+        LANG::Erlang => "%",
+        LANG::Lua => "--",
+        _ => "//",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r##"{
+        "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+        "nbformat": 4,
+        "cells": [
+            {"cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "Some text"]},
+            {
+                "cell_type": "code",
+                "metadata": {},
+                "outputs": [],
+                "source": ["x = 1\n", "if x > 0:\n", "    print(x)"]
+            }
+        ]
+    }"##;
+
+    #[test]
+    fn python_notebook_cell() {
+        let path = Path::new("foo.ipynb");
+        assert!(is_notebook(path));
+
+        let space =
+            get_notebook_spaces(NOTEBOOK.as_bytes(), path, &NotebookOptions::default()).unwrap();
+        assert_eq!(space.spaces.len(), 1);
+
+        let cell = &space.spaces[0];
+        assert_eq!(cell.name.as_deref(), Some("cell 1"));
+        assert_eq!((cell.start_line, cell.end_line), (1, 3));
+        assert_eq!(cell.metrics.cyclomatic.cyclomatic_sum(), 2.);
+        assert_eq!(space.metrics.cyclomatic.cyclomatic_sum(), 2.);
+        assert_eq!(space.metrics.loc.cloc(), 0.);
+    }
+
+    #[test]
+    fn python_notebook_markdown_as_comments() {
+        let options = NotebookOptions {
+            markdown_as_comments: true,
+        };
+        let space = get_notebook_spaces(NOTEBOOK.as_bytes(), Path::new("foo.ipynb"), &options)
+            .unwrap();
+
+        let names: Vec<_> = space
+            .spaces
+            .iter()
+            .map(|cell| (cell.name.as_deref(), cell.start_line))
+            .collect();
+        assert_eq!(names, [(Some("cell 0"), 1), (Some("cell 1"), 3)]);
+        assert_eq!(space.metrics.loc.cloc(), 2.);
+    }
+
+    #[test]
+    fn unsupported_notebook_language() {
+        let notebook = r#"{"metadata": {"kernelspec": {"language": "julia"}}, "cells": []}"#;
+        let result = get_notebook_spaces(
+            notebook.as_bytes(),
+            Path::new("foo.ipynb"),
+            &NotebookOptions::default(),
+        );
+        assert!(matches!(result, Err(NotebookError::UnsupportedLanguage(_))));
+    }
+}
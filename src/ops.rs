@@ -1,18 +1,23 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     path::{Path, PathBuf},
     string::ToString,
 };
 
 use serde::Serialize;
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+use tree_sitter::{Query, QueryCursor, QueryErrorKind, StreamingIterator};
 
 use crate::{
     checker::Checker,
     dump_ops::dump_ops,
     getter::Getter,
-    halstead::{Halstead, HalsteadMaps},
-    node::Node,
-    spaces::SpaceKind,
+    halstead::{self, Halstead, HalsteadMaps, HalsteadType},
+    langs::{action, get_function_spaces, LANG},
+    mi,
+    node::{Node, Tree},
+    spaces::{AverageSpaces, CodeMetrics, FuncSpace, SpaceKind},
     traits::{Callback, ParserTrait},
 };
 
@@ -264,6 +269,900 @@ pub fn operands_and_operators<'a, T: ParserTrait>(parser: &'a T, path: &'a Path)
     })
 }
 
+/// A function space in the list returned by [`flatten`].
+#[derive(Debug, Clone, Serialize)]
+pub struct FlatSpace<'a> {
+    /// The name of the function space.
+    pub name: Option<&'a str>,
+    /// The first line of the function space.
+    pub start_line: usize,
+    /// The last line of the function space.
+    pub end_line: usize,
+    /// The space kind.
+    pub kind: SpaceKind,
+    /// The nesting depth of the function space, `0` for the root.
+    pub depth: usize,
+    /// The index of the parent space in the flattened list.
+    ///
+    /// It is `None` for the root space.
+    pub parent: Option<usize>,
+    /// All metrics of the function space.
+    pub metrics: &'a CodeMetrics,
+}
+
+/// Flattens a function space and all its subspaces into a list.
+///
+/// Spaces are listed in source order, so a parent always comes
+/// before its children.
+#[must_use]
+pub fn flatten(space: &FuncSpace) -> Vec<FlatSpace<'_>> {
+    let mut flat = Vec::new();
+    let mut stack = vec![(space, 0, None)];
+
+    while let Some((space, depth, parent)) = stack.pop() {
+        let index = flat.len();
+        flat.push(FlatSpace {
+            name: space.name.as_deref(),
+            start_line: space.start_line,
+            end_line: space.end_line,
+            kind: space.kind,
+            depth,
+            parent,
+            metrics: &space.metrics,
+        });
+        stack.extend(
+            space
+                .spaces
+                .iter()
+                .rev()
+                .map(|child| (child, depth + 1, Some(index))),
+        );
+    }
+    flat
+}
+
+/// Merges the root spaces of several files into a single synthetic space,
+/// e.g. to report the metrics of a module made of several files.
+///
+/// The metrics of the files are merged as the metrics of nested spaces are,
+/// so sums, minimums and maximums cover all the files, and the averages are
+/// computed over all their functions and closures. The lines of the files
+/// are added up, e.g. the `SLOC` is the sum of the `SLOC` of the files.
+/// The `Halstead` and `Mi` metrics depend on the distinct operators and
+/// operands of the merged code, which are not known anymore, so they are
+/// left empty.
+///
+/// The returned space is a nameless [`SpaceKind::Unit`] whose subspaces are
+/// the merged spaces. Merging no space gives the metrics of an empty code.
+#[must_use]
+pub fn merge_spaces(spaces: &[FuncSpace]) -> FuncSpace {
+    let mut metrics = if let Some((first, others)) = spaces.split_first() {
+        let mut metrics = first.metrics.clone();
+        let mut loc = first.metrics.loc.clone();
+        for space in others {
+            metrics.merge(&space.metrics);
+            // The lines of the files are added up, instead of being merged as
+            // the lines of nested spaces
+            loc.append(&space.metrics.loc);
+        }
+        metrics.loc = loc;
+        metrics.halstead = halstead::Stats::default();
+        metrics.mi = mi::Stats::default();
+        metrics
+    } else {
+        let mut metrics = CodeMetrics::default();
+        metrics.compute_minmax();
+        metrics
+    };
+    metrics.compute_averages(true, AverageSpaces::PerMetric, false);
+
+    FuncSpace {
+        name: None,
+        start_line: 0,
+        end_line: 0,
+        kind: SpaceKind::Unit,
+        spaces: spaces.to_vec(),
+        metrics,
+        supertypes: None,
+        signature: None,
+        decorators: Vec::new(),
+        content_hash: None,
+        custom: BTreeMap::new(),
+    }
+}
+
+/// The metrics of a function touched by a patch, before and after it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionDelta {
+    /// The name of the function.
+    pub name: Option<String>,
+    /// The first line of the function in the patched code,
+    /// or in the base code if the patch removes the function.
+    pub start_line: usize,
+    /// The last line of the function in the patched code,
+    /// or in the base code if the patch removes the function.
+    pub end_line: usize,
+    /// The metrics of the function in the base code.
+    ///
+    /// It is `None` if the patch adds the function.
+    pub base: Option<CodeMetrics>,
+    /// The metrics of the function in the patched code.
+    ///
+    /// It is `None` if the patch removes the function.
+    pub patched: Option<CodeMetrics>,
+}
+
+impl FunctionDelta {
+    /// Returns the change of the `Cyclomatic Complexity` of the function.
+    #[must_use]
+    pub fn cyclomatic(&self) -> f64 {
+        self.delta(|metrics| metrics.cyclomatic.cyclomatic_sum())
+    }
+
+    /// Returns the change of the `Cognitive Complexity` of the function.
+    #[must_use]
+    pub fn cognitive(&self) -> f64 {
+        self.delta(|metrics| metrics.cognitive.cognitive_sum())
+    }
+
+    /// Returns the change of the `SLOC` of the function.
+    #[must_use]
+    pub fn sloc(&self) -> f64 {
+        self.delta(|metrics| metrics.loc.sloc())
+    }
+
+    fn delta(&self, metric: impl Fn(&CodeMetrics) -> f64) -> f64 {
+        let before = self.base.as_ref().map_or(0., &metric);
+        let after = self.patched.as_ref().map_or(0., &metric);
+        after - before
+    }
+}
+
+/// Compares the functions of a code before and after a patch.
+///
+/// Only the functions overlapping the lines changed by the patch are
+/// returned: the functions containing an inserted line of `patched` or a
+/// deleted line of `base`. Functions are matched between the two versions
+/// by name, in source order.
+#[must_use]
+pub fn analyze_patch(base: &[u8], patched: &[u8], lang: LANG) -> Vec<FunctionDelta> {
+    let path = Path::new("");
+    let (Some(base_space), Some(patched_space)) = (
+        get_function_spaces(&lang, base.to_vec(), path, None),
+        get_function_spaces(&lang, patched.to_vec(), path, None),
+    ) else {
+        return Vec::new();
+    };
+
+    let base_lines: Vec<&[u8]> = base.split(|c| *c == b'\n').collect();
+    let patched_lines: Vec<&[u8]> = patched.split(|c| *c == b'\n').collect();
+    let (deleted, inserted) = changed_lines(&base_lines, &patched_lines);
+    let touches = |space: &FuncSpace, lines: &[usize]| {
+        lines
+            .iter()
+            .any(|line| (space.start_line..=space.end_line).contains(line))
+    };
+
+    let mut base_functions: HashMap<(Option<&str>, usize), &FuncSpace> = HashMap::new();
+    for space in functions(&base_space) {
+        let key = next_key(space, |key| base_functions.contains_key(key));
+        base_functions.insert(key, space);
+    }
+
+    let mut deltas = Vec::new();
+    let mut matched = HashSet::new();
+    for space in functions(&patched_space) {
+        let key = next_key(space, |key| matched.contains(key));
+        let base = base_functions.get(&key).copied();
+        matched.insert(key);
+        if touches(space, &inserted) || base.is_some_and(|base| touches(base, &deleted)) {
+            deltas.push(FunctionDelta {
+                name: space.name.clone(),
+                start_line: space.start_line,
+                end_line: space.end_line,
+                base: base.map(|base| base.metrics.clone()),
+                patched: Some(space.metrics.clone()),
+            });
+        }
+    }
+
+    // The functions removed by the patch
+    let mut removed: Vec<_> = base_functions
+        .iter()
+        .filter(|(key, space)| !matched.contains(*key) && touches(space, &deleted))
+        .map(|(_, space)| *space)
+        .collect();
+    removed.sort_by_key(|space| space.start_line);
+    deltas.extend(removed.into_iter().map(|space| FunctionDelta {
+        name: space.name.clone(),
+        start_line: space.start_line,
+        end_line: space.end_line,
+        base: Some(space.metrics.clone()),
+        patched: None,
+    }));
+    deltas
+}
+
+/// Returns the functions touched by a selection of a code, from
+/// `start_byte` included to `end_byte` excluded, e.g. to show the
+/// complexity of the region selected in an editor.
+///
+/// The outermost functions overlapping the lines of the selection are
+/// returned in source order, with their nested functions as subspaces. A
+/// function only partially selected is returned whole, with the metrics of
+/// the whole function, since the metrics of a part of a function, such as
+/// its `Cognitive Complexity`, are not meaningful. The code outside the
+/// functions, e.g. the statements at the top level of a file, is not
+/// reported.
+///
+/// The selection is compared to the functions line by line, so a function
+/// sharing a line with the selection is touched. An empty selection
+/// touches the functions of its line.
+#[must_use]
+pub fn analyze_range(
+    code: &[u8],
+    lang: LANG,
+    start_byte: usize,
+    end_byte: usize,
+) -> Vec<FuncSpace> {
+    let Some(root) = get_function_spaces(&lang, code.to_vec(), Path::new(""), None) else {
+        return Vec::new();
+    };
+
+    let line_of = |byte: usize| {
+        let byte = byte.min(code.len());
+        code[..byte].iter().filter(|c| **c == b'\n').count() + 1
+    };
+    let start_line = line_of(start_byte);
+    let end_line = line_of(end_byte.saturating_sub(1).max(start_byte));
+
+    let mut touched = Vec::new();
+    let mut stack = vec![&root];
+    while let Some(space) = stack.pop() {
+        if space.start_line > end_line || space.end_line < start_line {
+            continue;
+        }
+        if space.kind == SpaceKind::Function {
+            touched.push(space.clone());
+        } else {
+            stack.extend(space.spaces.iter().rev());
+        }
+    }
+    touched
+}
+
+// The function spaces of a space and its subspaces, in source order
+fn functions(space: &FuncSpace) -> Vec<&FuncSpace> {
+    let mut functions = Vec::new();
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            functions.push(space);
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+    functions
+}
+
+// Functions sharing a name are told apart by their occurrence index
+fn next_key<'a>(
+    space: &'a FuncSpace,
+    is_taken: impl Fn(&(Option<&'a str>, usize)) -> bool,
+) -> (Option<&'a str>, usize) {
+    let name = space.name.as_deref();
+    let mut occurrence = 0;
+    while is_taken(&(name, occurrence)) {
+        occurrence += 1;
+    }
+    (name, occurrence)
+}
+
+// Returns the lines deleted from `base` and the lines inserted in `patched`,
+// numbered from 1, using the linear space variant of the Myers diff
+// algorithm.
+fn changed_lines(base: &[&[u8]], patched: &[&[u8]]) -> (Vec<usize>, Vec<usize>) {
+    let (mut deleted, mut inserted) = (Vec::new(), Vec::new());
+    for op in capture_diff_slices(Algorithm::Myers, base, patched) {
+        let (tag, base_range, patched_range) = op.as_tag_tuple();
+        if tag != DiffTag::Equal {
+            deleted.extend(base_range.map(|line| line + 1));
+            inserted.extend(patched_range.map(|line| line + 1));
+        }
+    }
+    (deleted, inserted)
+}
+
+/// How many times each keyword and identifier appears in a code.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TokenFrequency {
+    /// Occurrences of each operator, keyed by node kind.
+    ///
+    /// Keywords, statements and punctuation are all classified as
+    /// operators by the `Halstead` metric, e.g. `if_statement` or `=`.
+    pub keywords: BTreeMap<String, usize>,
+    /// Occurrences of each identifier, keyed by its text.
+    ///
+    /// Only the operands whose node kind is an identifier are counted,
+    /// literals are ignored.
+    pub identifiers: BTreeMap<String, usize>,
+}
+
+impl TokenFrequency {
+    /// Returns the most frequent identifier with its number of occurrences.
+    ///
+    /// Ties are broken in favour of the identifier which sorts first.
+    #[must_use]
+    pub fn most_frequent_identifier(&self) -> Option<(&str, usize)> {
+        self.identifiers
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, count)| (name.as_str(), *count))
+    }
+}
+
+/// Counts the keywords and the identifiers of a code.
+///
+/// Tokens are classified as operators and operands in the same way as
+/// the `Halstead` metric does.
+#[must_use]
+pub fn token_frequency(code: &[u8], lang: LANG) -> TokenFrequency {
+    action::<TokenFrequencyCode>(&lang, code.to_vec(), Path::new(""), None, ())
+}
+
+struct TokenFrequencyCode {
+    _guard: (),
+}
+
+impl Callback for TokenFrequencyCode {
+    type Res = TokenFrequency;
+    type Cfg = ();
+
+    fn call<T: ParserTrait>(_cfg: Self::Cfg, parser: &T) -> Self::Res {
+        let code = parser.get_code();
+        let mut frequency = TokenFrequency::default();
+        let mut stack = vec![parser.get_root()];
+        while let Some(node) = stack.pop() {
+            match T::Getter::get_op_type(&node) {
+                HalsteadType::Operator => {
+                    *frequency
+                        .keywords
+                        .entry(node.kind().to_owned())
+                        .or_insert(0) += 1;
+                }
+                HalsteadType::Operand if node.kind().contains("identifier") => {
+                    if let Some(name) = node.utf8_text(code) {
+                        *frequency.identifiers.entry(name.to_owned()).or_insert(0) += 1;
+                    }
+                }
+                _ => {}
+            }
+            stack.extend(node.children());
+        }
+        frequency
+    }
+}
+
+/// A node captured by a query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryCapture {
+    /// The name of the capture in the query, without the leading `@`.
+    pub name: String,
+    /// The code of the captured node.
+    pub text: String,
+    /// The first line of the node, starting from 1.
+    pub start_line: usize,
+    /// The first column of the node, starting from 1.
+    pub start_column: usize,
+    /// The last line of the node, starting from 1.
+    pub end_line: usize,
+    /// The column following the end of the node, starting from 1.
+    pub end_column: usize,
+}
+
+/// A match of a query pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryMatch {
+    /// The index of the matched pattern in the query.
+    pub pattern: usize,
+    /// The nodes captured by the pattern, in source order.
+    pub captures: Vec<QueryCapture>,
+}
+
+/// Error returned by [`query`] when a query cannot be compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    /// The line of the query where the error is, starting from 1.
+    pub line: usize,
+    /// The column of the query where the error is, starting from 1.
+    pub column: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid query at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<tree_sitter::QueryError> for QueryError {
+    fn from(err: tree_sitter::QueryError) -> Self {
+        let message = match err.kind {
+            QueryErrorKind::Syntax => format!("syntax error near `{}`", err.message),
+            QueryErrorKind::NodeType => format!("unknown node kind `{}`", err.message),
+            QueryErrorKind::Field => format!("unknown field `{}`", err.message),
+            QueryErrorKind::Capture => format!("unknown capture `{}`", err.message),
+            _ => err.message,
+        };
+        Self {
+            line: err.row + 1,
+            column: err.column + 1,
+            message,
+        }
+    }
+}
+
+/// Runs a tree-sitter query on a code and returns its matches.
+///
+/// The query is written in the tree-sitter query language against the
+/// grammar returned by [`LANG::tree_sitter_language`], e.g.
+/// `(function_item name: (identifier) @name)` captures the names of all
+/// the `Rust` functions.
+///
+/// # Errors
+/// Returns a [`QueryError`] if `query_src` is not a valid query for the
+/// grammar of `lang`.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{query, LANG};
+///
+/// let code = b"fn main() {\n    println!(\"hello\");\n}\n";
+/// let matches = query(code, LANG::Rust, "(macro_invocation) @call").unwrap();
+/// assert_eq!(matches[0].captures[0].text, "println!(\"hello\")");
+/// ```
+pub fn query(code: &[u8], lang: LANG, query_src: &str) -> Result<Vec<QueryMatch>, QueryError> {
+    let query = Query::new(&lang.tree_sitter_language(), query_src)?;
+    let names = query.capture_names();
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return Ok(Vec::new());
+    };
+
+    let mut cursor = QueryCursor::new();
+    let mut results = cursor.matches(&query, tree.get_root().get_ts_node(), code);
+    let mut matches = Vec::new();
+    while let Some(found) = results.next() {
+        let captures = found
+            .captures
+            .iter()
+            .map(|capture| {
+                let node = capture.node;
+                let (start, end) = (node.start_position(), node.end_position());
+                QueryCapture {
+                    name: names[capture.index as usize].to_owned(),
+                    text: String::from_utf8_lossy(&code[node.byte_range()]).into_owned(),
+                    start_line: start.row + 1,
+                    start_column: start.column + 1,
+                    end_line: end.row + 1,
+                    end_column: end.column + 1,
+                }
+            })
+            .collect();
+        matches.push(QueryMatch {
+            pattern: found.pattern_index,
+            captures,
+        });
+    }
+    Ok(matches)
+}
+
+/// How the names of a module are bound by an [`ImportedSymbol`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportKind {
+    /// A name exported by the module, e.g. `a` in `import { a } from 'mod'`
+    Named,
+    /// The default export of a `JavaScript` or `TypeScript` module, e.g.
+    /// `a` in `import a from 'mod'`
+    Default,
+    /// All the names of the module, either bound to an alias, e.g. `ns` in
+    /// `import * as ns from 'mod'`, or brought into scope, e.g. `*` in
+    /// `use mod::*`
+    Namespace,
+}
+
+/// A name imported from a module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedSymbol {
+    /// The name in the module, `default` for a default import and `*` for
+    /// a namespace import
+    pub name: String,
+    /// The name bound by the import, when it differs from `name`
+    pub alias: Option<String>,
+    /// How the name is bound
+    pub kind: ImportKind,
+}
+
+impl ImportedSymbol {
+    fn new(name: &str, alias: Option<String>, kind: ImportKind) -> Self {
+        Self {
+            name: name.to_owned(),
+            alias,
+            kind,
+        }
+    }
+}
+
+/// A module or a package imported by a code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Import {
+    /// The imported module, as written in the code without its quotes,
+    /// e.g. `os.path`, `./mod`, `std::collections` or `fmt`
+    pub module: String,
+    /// The names imported from the module, in source order, empty when
+    /// the module itself is imported, e.g. `import os`
+    pub symbols: Vec<ImportedSymbol>,
+    /// The line of the import, starting from 1
+    pub line: usize,
+}
+
+/// Returns the modules and packages imported by a code, in source order.
+///
+/// Imports are read from the `import` statements of `Python`,
+/// `JavaScript` and `TypeScript`, the `use` declarations of `Rust`, the
+/// `import` declarations of `Go` and `Java` and the `using` directives of
+/// `C#`, so no import is returned for the other languages. A declaration
+/// importing from several modules, e.g. `use std::{fmt, io::Read}`,
+/// returns an import for each module.
+///
+/// An aliased module, e.g. `import numpy as np` or `import f "fmt"`, is
+/// returned as a namespace import bound to its alias. Dynamic imports,
+/// such as `require` calls, are not listed.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{imports, ImportKind, LANG};
+///
+/// let imports = imports(b"from os import path as p\n", LANG::Python);
+/// assert_eq!(imports[0].module, "os");
+/// assert_eq!(imports[0].symbols[0].name, "path");
+/// assert_eq!(imports[0].symbols[0].alias.as_deref(), Some("p"));
+/// assert_eq!(imports[0].symbols[0].kind, ImportKind::Named);
+/// ```
+#[must_use]
+pub fn imports(code: &[u8], lang: LANG) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let Some(tree) = Tree::with_lang(code, &lang) else {
+        return imports;
+    };
+    let mut stack = vec![tree.get_root()];
+    while let Some(node) = stack.pop() {
+        let is_import = match lang {
+            LANG::Python => python_import(&node, code, &mut imports),
+            LANG::Javascript | LANG::Typescript | LANG::Tsx => js_import(&node, code, &mut imports),
+            LANG::Rust => rust_import(&node, code, &mut imports),
+            LANG::Go => go_import(&node, code, &mut imports),
+            LANG::Java => java_import(&node, code, &mut imports),
+            LANG::Csharp => csharp_import(&node, code, &mut imports),
+            _ => false,
+        };
+        if !is_import {
+            let children: Vec<_> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    imports
+}
+
+fn node_text(node: &Node, code: &[u8]) -> String {
+    node.utf8_text(code).unwrap_or_default().to_owned()
+}
+
+fn field_text(node: &Node, field: &str, code: &[u8]) -> Option<String> {
+    node.child_by_field_name(field)
+        .map(|child| node_text(&child, code))
+}
+
+fn unquote(text: &str) -> String {
+    text.trim_matches(['"', '\'', '`']).to_owned()
+}
+
+fn push_import(
+    imports: &mut Vec<Import>,
+    node: &Node,
+    module: String,
+    symbols: Vec<ImportedSymbol>,
+) {
+    imports.push(Import {
+        module,
+        symbols,
+        line: node.start_row() + 1,
+    });
+}
+
+// Pushes the imports of a `Python` `import` or `from ... import` statement
+fn python_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    match node.kind() {
+        "import_statement" => {
+            for name in node.children() {
+                match name.kind() {
+                    "dotted_name" => push_import(imports, node, node_text(&name, code), Vec::new()),
+                    "aliased_import" => {
+                        let module = field_text(&name, "name", code).unwrap_or_default();
+                        let alias = field_text(&name, "alias", code);
+                        let symbol = ImportedSymbol::new("*", alias, ImportKind::Namespace);
+                        push_import(imports, node, module, vec![symbol]);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        "import_from_statement" | "future_import_statement" => {
+            let module = node.child_by_field_name("module_name");
+            let symbols = node
+                .children()
+                .filter(|child| module.is_none_or(|module| module.id() != child.id()))
+                .filter_map(|child| match child.kind() {
+                    "dotted_name" => Some(ImportedSymbol::new(
+                        &node_text(&child, code),
+                        None,
+                        ImportKind::Named,
+                    )),
+                    "aliased_import" => Some(ImportedSymbol::new(
+                        &field_text(&child, "name", code).unwrap_or_default(),
+                        field_text(&child, "alias", code),
+                        ImportKind::Named,
+                    )),
+                    "wildcard_import" => {
+                        Some(ImportedSymbol::new("*", None, ImportKind::Namespace))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let module = module.map_or_else(
+                || "__future__".to_owned(),
+                |module| node_text(&module, code),
+            );
+            push_import(imports, node, module, symbols);
+        }
+        _ => return false,
+    }
+    true
+}
+
+// Pushes the import of a `JavaScript` or `TypeScript` `import` statement,
+// including the `TypeScript` `import a = require('mod')`
+fn js_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    if node.kind() != "import_statement" {
+        return false;
+    }
+    let mut source = node.child_by_field_name("source");
+    let mut symbols = Vec::new();
+    for clause in node.children() {
+        match clause.kind() {
+            "import_clause" => {
+                for child in clause.children() {
+                    match child.kind() {
+                        "identifier" => symbols.push(ImportedSymbol::new(
+                            "default",
+                            Some(node_text(&child, code)),
+                            ImportKind::Default,
+                        )),
+                        "namespace_import" => symbols.push(ImportedSymbol::new(
+                            "*",
+                            child
+                                .children()
+                                .find(|name| name.kind() == "identifier")
+                                .map(|name| node_text(&name, code)),
+                            ImportKind::Namespace,
+                        )),
+                        "named_imports" => {
+                            symbols.extend(
+                                child
+                                    .children()
+                                    .filter(|specifier| specifier.kind() == "import_specifier")
+                                    .map(|specifier| {
+                                        ImportedSymbol::new(
+                                            &unquote(
+                                                &field_text(&specifier, "name", code)
+                                                    .unwrap_or_default(),
+                                            ),
+                                            field_text(&specifier, "alias", code),
+                                            ImportKind::Named,
+                                        )
+                                    }),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            "import_require_clause" => {
+                source = clause.child_by_field_name("source");
+                symbols.push(ImportedSymbol::new(
+                    "*",
+                    clause
+                        .children()
+                        .find(|name| name.kind() == "identifier")
+                        .map(|name| node_text(&name, code)),
+                    ImportKind::Namespace,
+                ));
+            }
+            _ => {}
+        }
+    }
+    if let Some(source) = source {
+        push_import(imports, node, unquote(&node_text(&source, code)), symbols);
+    }
+    true
+}
+
+// Pushes the imports of a `Rust` `use` declaration, one for each module
+fn rust_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    if node.kind() != "use_declaration" {
+        return false;
+    }
+    if let Some(argument) = node.child_by_field_name("argument") {
+        let mut declared = Vec::new();
+        rust_use_tree(&argument, "", node, code, &mut declared);
+        imports.append(&mut declared);
+    }
+    true
+}
+
+fn join_rust_path(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_owned()
+    } else {
+        format!("{prefix}::{path}")
+    }
+}
+
+// Adds the names of a `Rust` use tree to the imports of their modules,
+// `prefix` being the path of the enclosing use lists
+fn rust_use_tree(tree: &Node, prefix: &str, node: &Node, code: &[u8], imports: &mut Vec<Import>) {
+    let mut add = |module: String, symbol: ImportedSymbol| {
+        if let Some(import) = imports.iter_mut().find(|import| import.module == module) {
+            import.symbols.push(symbol);
+        } else {
+            push_import(imports, node, module, vec![symbol]);
+        }
+    };
+    // Splits a path into its module and its last segment
+    let split = |path: &Node| match path.kind() {
+        "scoped_identifier" => (
+            join_rust_path(prefix, &field_text(path, "path", code).unwrap_or_default()),
+            field_text(path, "name", code).unwrap_or_default(),
+        ),
+        _ => (prefix.to_owned(), node_text(path, code)),
+    };
+    match tree.kind() {
+        "use_as_clause" => {
+            let Some(path) = tree.child_by_field_name("path") else {
+                return;
+            };
+            let alias = field_text(tree, "alias", code);
+            match split(&path) {
+                (module, name) if module.is_empty() => {
+                    add(name, ImportedSymbol::new("*", alias, ImportKind::Namespace));
+                }
+                (module, name) => add(module, ImportedSymbol::new(&name, alias, ImportKind::Named)),
+            }
+        }
+        "use_wildcard" => {
+            let module = tree.children().find(Node::is_named).map_or_else(
+                || prefix.to_owned(),
+                |path| join_rust_path(prefix, &node_text(&path, code)),
+            );
+            add(
+                module,
+                ImportedSymbol::new("*", None, ImportKind::Namespace),
+            );
+        }
+        "scoped_use_list" => {
+            let prefix = tree.child_by_field_name("path").map_or_else(
+                || prefix.to_owned(),
+                |path| join_rust_path(prefix, &node_text(&path, code)),
+            );
+            if let Some(list) = tree.child_by_field_name("list") {
+                rust_use_tree(&list, &prefix, node, code, imports);
+            }
+        }
+        "use_list" => {
+            for child in tree.children().filter(Node::is_named) {
+                rust_use_tree(&child, prefix, node, code, imports);
+            }
+        }
+        _ => match split(tree) {
+            // A crate, e.g. `use serde;`
+            (module, name) if module.is_empty() => push_import(imports, node, name, Vec::new()),
+            (module, name) => add(module, ImportedSymbol::new(&name, None, ImportKind::Named)),
+        },
+    }
+}
+
+// Pushes the import of a `Go` import spec, e.g. `f "fmt"`
+fn go_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    if node.kind() != "import_spec" {
+        return false;
+    }
+    let Some(path) = node.child_by_field_name("path") else {
+        return true;
+    };
+    let symbols = match node.child_by_field_name("name") {
+        Some(name) if name.kind() == "dot" => {
+            vec![ImportedSymbol::new("*", None, ImportKind::Namespace)]
+        }
+        Some(name) if name.kind() == "package_identifier" => vec![ImportedSymbol::new(
+            "*",
+            Some(node_text(&name, code)),
+            ImportKind::Namespace,
+        )],
+        // A blank import only runs the initialization of the package
+        _ => Vec::new(),
+    };
+    push_import(imports, node, unquote(&node_text(&path, code)), symbols);
+    true
+}
+
+// Pushes the import of a `Java` import declaration, e.g.
+// `import java.util.List;`
+fn java_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    if node.kind() != "import_declaration" {
+        return false;
+    }
+    let Some(path) = node
+        .children()
+        .find(|child| matches!(child.kind(), "identifier" | "scoped_identifier"))
+    else {
+        return true;
+    };
+    if node.children().any(|child| child.kind() == "asterisk") {
+        let symbol = ImportedSymbol::new("*", None, ImportKind::Namespace);
+        push_import(imports, node, node_text(&path, code), vec![symbol]);
+    } else if let (Some(scope), Some(name)) = (
+        field_text(&path, "scope", code),
+        field_text(&path, "name", code),
+    ) {
+        let symbol = ImportedSymbol::new(&name, None, ImportKind::Named);
+        push_import(imports, node, scope, vec![symbol]);
+    } else {
+        push_import(imports, node, node_text(&path, code), Vec::new());
+    }
+    true
+}
+
+// Pushes the import of a `C#` using directive, e.g. `using System.Text;`
+// or `using Text = System.Text;`
+fn csharp_import(node: &Node, code: &[u8], imports: &mut Vec<Import>) -> bool {
+    if node.kind() != "using_directive" {
+        return false;
+    }
+    let alias = node.child_by_field_name("name");
+    let module = node
+        .children()
+        .filter(|child| child.is_named() && alias.is_none_or(|alias| alias.id() != child.id()))
+        .last();
+    if let Some(module) = module {
+        let symbols = alias
+            .map(|alias| {
+                ImportedSymbol::new("*", Some(node_text(&alias, code)), ImportKind::Namespace)
+            })
+            .into_iter()
+            .collect();
+        push_import(imports, node, node_text(&module, code), symbols);
+    }
+    true
+}
+
 /// Configuration options for retrieving
 /// all the operands and operators in a code.
 #[derive(Debug)]
@@ -291,9 +1190,15 @@ impl Callback for OpsCode {
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
-    use crate::{get_ops, LANG};
+    use super::{
+        analyze_patch, analyze_range, flatten, imports, merge_spaces, query, token_frequency,
+        ImportKind,
+    };
+    use crate::{
+        get_function_spaces, get_ops, tools::check_func_space, JavaParser, SpaceKind, LANG,
+    };
 
     #[inline]
     fn check_ops(
@@ -718,4 +1623,271 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn java_flatten() {
+        check_func_space::<JavaParser, _>(
+            "class Counter {
+                int count;
+                void increment() {
+                    count++;
+                }
+                void reset() {
+                    count = 0;
+                }
+            }",
+            "foo.java",
+            |space| {
+                let flat: Vec<_> = flatten(&space)
+                    .into_iter()
+                    .map(|space| (space.name, space.kind, space.depth, space.parent))
+                    .collect();
+                assert_eq!(
+                    flat,
+                    [
+                        (Some("foo.java"), SpaceKind::Unit, 0, None),
+                        (Some("Counter"), SpaceKind::Class, 1, Some(0)),
+                        (Some("increment"), SpaceKind::Function, 2, Some(1)),
+                        (Some("reset"), SpaceKind::Function, 2, Some(1)),
+                    ]
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_token_frequency() {
+        let frequency = token_frequency(
+            b"def area(width, height):
+    if width > 0:
+        return width * height
+    return width
+",
+            LANG::Python,
+        );
+
+        assert_eq!(frequency.most_frequent_identifier(), Some(("width", 4)));
+        assert_eq!(frequency.identifiers["height"], 2);
+        assert_eq!(frequency.identifiers["area"], 1);
+        assert_eq!(frequency.keywords["return_statement"], 2);
+        assert_eq!(frequency.keywords["def"], 1);
+    }
+
+    #[test]
+    fn python_merge_spaces() {
+        let path = Path::new("foo.py");
+        let space =
+            |code: &str| get_function_spaces(&LANG::Python, code.into(), path, None).unwrap();
+        let first = space("def f(a):\n    if a:\n        return 1\n    return 0\n");
+        let second = space(
+            "# Both arguments\ndef g(a, b):\n    if a and b:\n        return 1\n    return 0\n",
+        );
+        let lloc = first.metrics.loc.lloc() + second.metrics.loc.lloc();
+
+        let merged = merge_spaces(&[first, second]);
+        assert_eq!(merged.spaces.len(), 2);
+        let metrics = &merged.metrics;
+        assert_eq!(metrics.cyclomatic.cyclomatic_sum(), 7.);
+        assert_eq!(metrics.cyclomatic.cyclomatic_min(), 1.);
+        assert_eq!(metrics.cyclomatic.cyclomatic_max(), 3.);
+        assert_eq!(metrics.cognitive.cognitive_sum(), 3.);
+        assert_eq!(metrics.cognitive.cognitive_min(), 0.);
+        assert_eq!(metrics.cognitive.cognitive_average(), 1.5);
+        assert_eq!(metrics.nom.functions_sum(), 2.);
+        // The same lines of both files are counted twice
+        assert_eq!(metrics.loc.sloc(), 9.);
+        assert_eq!(metrics.loc.ploc(), 8.);
+        assert_eq!(metrics.loc.cloc(), 1.);
+        assert_eq!(metrics.loc.blank(), 0.);
+        assert_eq!(metrics.loc.lloc(), lloc);
+
+        let empty = merge_spaces(&[]);
+        assert_eq!(empty.metrics.cognitive.cognitive_min(), 0.);
+        assert_eq!(empty.metrics.nexits.exit_min(), 0.);
+    }
+
+    #[test]
+    fn rust_query_function_names() {
+        let code = b"fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+impl Point {
+    fn norm(&self) -> f64 {
+        0.0
+    }
+}
+";
+        let matches = query(code, LANG::Rust, "(function_item name: (identifier) @name)").unwrap();
+        let names: Vec<_> = matches
+            .iter()
+            .flat_map(|found| &found.captures)
+            .map(|capture| (capture.name.as_str(), capture.text.as_str(), capture.start_line))
+            .collect();
+        assert_eq!(names, [("name", "add", 1), ("name", "norm", 6)]);
+        assert_eq!(matches[1].captures[0].start_column, 8);
+    }
+
+    #[test]
+    fn rust_query_invalid() {
+        let err = query(b"fn main() {}", LANG::Rust, "(function_itm) @f").unwrap_err();
+        assert_eq!(err.line, 1);
+        assert_eq!(err.column, 2);
+        assert_eq!(err.message, "unknown node kind `function_itm`");
+    }
+
+    #[test]
+    fn javascript_imports() {
+        let code = b"import React, * as all from 'react';
+import {a, b as c} from 'mod';
+import './side-effect.css';
+
+export function f() {}
+";
+        let imports = imports(code, LANG::Javascript);
+        let modules: Vec<_> = imports
+            .iter()
+            .map(|import| (import.module.as_str(), import.line))
+            .collect();
+        assert_eq!(
+            modules,
+            [("react", 1), ("mod", 2), ("./side-effect.css", 3)]
+        );
+
+        let symbols = |index: usize| -> Vec<_> {
+            imports[index]
+                .symbols
+                .iter()
+                .map(|symbol| (symbol.name.as_str(), symbol.alias.as_deref(), symbol.kind))
+                .collect()
+        };
+        assert_eq!(
+            symbols(0),
+            [
+                ("default", Some("React"), ImportKind::Default),
+                ("*", Some("all"), ImportKind::Namespace)
+            ]
+        );
+        assert_eq!(
+            symbols(1),
+            [
+                ("a", None, ImportKind::Named),
+                ("b", Some("c"), ImportKind::Named)
+            ]
+        );
+        assert!(symbols(2).is_empty());
+    }
+
+    #[test]
+    fn rust_imports() {
+        let code = b"use serde;
+use std::{
+    collections::{HashMap, HashSet as Set},
+    fmt,
+    io::*,
+};
+";
+        let imports = imports(code, LANG::Rust);
+        let modules: Vec<_> = imports
+            .iter()
+            .map(|import| {
+                let names: Vec<_> = import
+                    .symbols
+                    .iter()
+                    .map(|symbol| symbol.name.as_str())
+                    .collect();
+                (import.module.as_str(), names, import.line)
+            })
+            .collect();
+        assert_eq!(
+            modules,
+            [
+                ("serde", vec![], 1),
+                ("std::collections", vec!["HashMap", "HashSet"], 2),
+                ("std", vec!["fmt"], 2),
+                ("std::io", vec!["*"], 2)
+            ]
+        );
+        assert_eq!(imports[1].symbols[1].alias.as_deref(), Some("Set"));
+    }
+
+    #[test]
+    fn python_analyze_range() {
+        let code = b"def first(x):
+    if x:
+        return x
+    return 0
+
+def second(x):
+    return -x
+";
+        let start = code.windows(10).position(|window| window == b"def second").unwrap();
+
+        let spaces = analyze_range(code, LANG::Python, start, code.len());
+        assert_eq!(spaces.len(), 1);
+        let function = &spaces[0];
+        assert_eq!(function.name.as_deref(), Some("second"));
+        assert_eq!((function.start_line, function.end_line), (6, 7));
+        assert_eq!(function.metrics.cyclomatic.cyclomatic(), 1.);
+
+        // A selection across both functions touches both of them
+        let spaces = analyze_range(code, LANG::Python, start - 10, start + 3);
+        let names: Vec<_> = spaces.iter().map(|space| space.name.as_deref()).collect();
+        assert_eq!(names, [Some("first"), Some("second")]);
+    }
+
+    #[test]
+    fn python_analyze_patch() {
+        let base = b"def first(x):
+    return x
+
+def second(x):
+    return -x
+";
+        let patched = b"def first(x):
+    return x
+
+def second(x):
+    if x > 0:
+        return -x
+    return x
+";
+
+        let deltas = analyze_patch(base, patched, LANG::Python);
+        assert_eq!(deltas.len(), 1);
+
+        let delta = &deltas[0];
+        assert_eq!(delta.name.as_deref(), Some("second"));
+        assert_eq!((delta.start_line, delta.end_line), (4, 7));
+        assert!(delta.base.is_some());
+        assert_eq!(delta.cyclomatic(), 1.);
+        assert_eq!(delta.sloc(), 2.);
+    }
+
+    #[test]
+    fn changed_lines_of_a_patch() {
+        let base: Vec<&[u8]> = vec![b"a", b"b", b"c"];
+        let patched: Vec<&[u8]> = vec![b"a", b"x", b"c", b"d"];
+        assert_eq!(changed_lines(&base, &patched), (vec![2], vec![2, 4]));
+        assert_eq!(changed_lines(&base, &base), (vec![], vec![]));
+    }
+
+    #[test]
+    fn python_analyze_patch_removed_function() {
+        let base = b"def first(x):
+    return x
+
+def second(x):
+    return -x
+";
+        let patched = b"def first(x):
+    return x
+";
+
+        let deltas = analyze_patch(base, patched, LANG::Python);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].name.as_deref(), Some("second"));
+        assert!(deltas[0].patched.is_none());
+        assert_eq!(deltas[0].cyclomatic(), -1.);
+    }
 }
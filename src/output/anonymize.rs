@@ -0,0 +1,54 @@
+use crate::spaces::{FuncSpace, SpaceKind};
+use crate::tools::fnv1a;
+
+/// Replaces the names of all the subspaces of a space, and of the
+/// supertypes they declare, with stable hashes, e.g. `fn_7a3b09c2` for a
+/// function. The signatures of the functions, which spell out the names of
+/// their parameters, and the decorators, which spell out their arguments,
+/// are dropped.
+///
+/// The same name of the same kind of space is always replaced with the same
+/// hash, across runs and platforms, so anonymized reports can still be
+/// compared. The name of `space` itself, usually the path of the analyzed
+/// file, is kept.
+pub fn anonymize(space: &mut FuncSpace) {
+    for child in &mut space.spaces {
+        if let Some(name) = &child.name {
+            child.name = Some(anonymized_name(child.kind, name));
+        }
+        child.signature = None;
+        child.decorators.clear();
+        if let Some(supertypes) = &mut child.supertypes {
+            let extended_kind = if child.kind == SpaceKind::Interface {
+                SpaceKind::Interface
+            } else {
+                SpaceKind::Class
+            };
+            for name in &mut supertypes.extends {
+                *name = anonymized_name(extended_kind, name);
+            }
+            for name in &mut supertypes.implements {
+                *name = anonymized_name(SpaceKind::Interface, name);
+            }
+        }
+        anonymize(child);
+    }
+}
+
+/// Returns the stable hash replacing the name of a space of the given kind.
+#[must_use]
+pub fn anonymized_name(kind: SpaceKind, name: &str) -> String {
+    let prefix = match kind {
+        SpaceKind::Function => "fn",
+        SpaceKind::Class => "class",
+        SpaceKind::Struct => "struct",
+        SpaceKind::Trait => "trait",
+        SpaceKind::Impl => "impl",
+        SpaceKind::Namespace => "ns",
+        SpaceKind::Interface => "interface",
+        SpaceKind::Macro => "macro",
+        SpaceKind::Unit | SpaceKind::Unknown => "space",
+    };
+    let hash = fnv1a(name.as_bytes());
+    format!("{prefix}_{:08x}", hash >> 32)
+}
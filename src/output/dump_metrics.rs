@@ -3,10 +3,12 @@ use std::io::Write;
 use termcolor::{Color, ColorChoice, StandardStream, StandardStreamLock};
 
 use crate::{
-    abc, cognitive, cyclomatic, exit, halstead, loc, mi, nargs, nom, npa, npm,
+    abc, boolean_ops, cognitive, cyclomatic, exit, halstead, jsx, loc, locals, mi, nargs, nom, npa,
+    npm,
     spaces::{CodeMetrics, FuncSpace},
+    template,
     tools::{color, intense_color},
-    wmc,
+    unsafety, wmc,
 };
 
 /// Dumps the metrics of a code.
@@ -107,6 +109,11 @@ fn dump_metrics(
     dump_nom(&metrics.nom, &prefix, false, stdout)?;
     dump_mi(&metrics.mi, &prefix, false, stdout)?;
     dump_abc(&metrics.abc, &prefix, false, stdout)?;
+    dump_boolean_ops(&metrics.boolean_ops, &prefix, false, stdout)?;
+    dump_locals(&metrics.locals, &prefix, false, stdout)?;
+    dump_unsafe(&metrics.unsafe_count, &prefix, false, stdout)?;
+    dump_jsx(&metrics.jsx, &prefix, false, stdout)?;
+    dump_template(&metrics.template, &prefix, false, stdout)?;
     dump_wmc(&metrics.wmc, &prefix, false, stdout)?;
     dump_npm(&metrics.npm, &prefix, false, stdout)?;
     dump_npa(&metrics.npa, &prefix, true, stdout)
@@ -210,7 +217,8 @@ fn dump_loc(
     dump_value("ploc", stats.ploc(), &prefix, false, stdout)?;
     dump_value("lloc", stats.lloc(), &prefix, false, stdout)?;
     dump_value("cloc", stats.cloc(), &prefix, false, stdout)?;
-    dump_value("blank", stats.blank(), &prefix, true, stdout)
+    dump_value("blank", stats.blank(), &prefix, false, stdout)?;
+    dump_value("comment_ratio", stats.comment_ratio(), &prefix, true, stdout)
 }
 
 fn dump_nom(
@@ -230,6 +238,15 @@ fn dump_nom(
     let prefix = format!("{prefix}{pref_child}");
     dump_value("functions", stats.functions(), &prefix, false, stdout)?;
     dump_value("closures", stats.closures(), &prefix, false, stdout)?;
+    dump_value(
+        "nom_functions",
+        stats.free_functions_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value("nom_methods", stats.methods_sum(), &prefix, false, stdout)?;
+    dump_value("nom_closures", stats.closures_sum(), &prefix, false, stdout)?;
     dump_value("total", stats.total(), &prefix, true, stdout)
 }
 
@@ -326,6 +343,116 @@ fn dump_abc(
     dump_value("magnitude", stats.magnitude_sum(), &prefix, true, stdout)
 }
 
+fn dump_boolean_ops(
+    stats: &boolean_ops::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "boolean_ops")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("and", stats.and_ops_sum(), &prefix, false, stdout)?;
+    dump_value("or", stats.or_ops_sum(), &prefix, false, stdout)?;
+    dump_value("not", stats.not_ops_sum(), &prefix, false, stdout)?;
+    dump_value("total", stats.total(), &prefix, true, stdout)
+}
+
+fn dump_locals(
+    stats: &locals::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "locals")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("total", stats.locals_sum(), &prefix, false, stdout)?;
+    dump_value("max", stats.locals_max(), &prefix, true, stdout)
+}
+
+fn dump_unsafe(
+    stats: &unsafety::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "unsafe")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("blocks", stats.blocks_sum(), &prefix, false, stdout)?;
+    dump_value("functions", stats.functions_sum(), &prefix, false, stdout)?;
+    dump_value("total", stats.total(), &prefix, true, stdout)
+}
+
+fn dump_jsx(
+    stats: &jsx::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "jsx")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("elements", stats.elements_sum(), &prefix, false, stdout)?;
+    dump_value("max_depth", stats.max_depth_max(), &prefix, true, stdout)
+}
+
+fn dump_template(
+    stats: &template::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "template")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("count", stats.declarations_sum(), &prefix, false, stdout)?;
+    dump_value("depth", stats.depth_max(), &prefix, true, stdout)
+}
+
 fn dump_wmc(
     stats: &wmc::Stats,
     prefix: &str,
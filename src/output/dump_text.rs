@@ -0,0 +1,120 @@
+//! A plain text report of the key metrics of a file, e.g. to be printed
+//! on a terminal.
+
+use crate::spaces::FuncSpace;
+
+const HEADER: [&str; 6] = ["NAME", "LINES", "CC", "COGNITIVE", "SLOC", "NEXITS"];
+
+/// Returns a plain text report of the key metrics of a file.
+///
+/// The report is a table with a row for each space, indented as the
+/// spaces are nested and listing their lines, `Cyclomatic Complexity`,
+/// `Cognitive Complexity`, `SLOC` and `NExits`. The values of a row are
+/// those of its space alone, except for the `SLOC` which spans the whole
+/// space. A last line sums the metrics of the file.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{dump_text, metrics, ParserTrait, PythonParser};
+///
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(b"def f(x):\n    return x\n".to_vec(), &path, None);
+/// let space = metrics(&parser, &path).unwrap();
+///
+/// print!("{}", dump_text(&space, "foo.py"));
+/// ```
+#[must_use]
+pub fn dump_text(space: &FuncSpace, file_name: &str) -> String {
+    let mut rows = vec![HEADER.map(ToString::to_string)];
+    collect_rows(space, file_name, 0, &mut rows);
+
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut report = String::new();
+    for row in &rows {
+        let mut line = format!("{:<width$}", row[0], width = widths[0]);
+        for (cell, width) in row.iter().zip(widths).skip(1) {
+            line.push_str(&format!("  {cell:>width$}"));
+        }
+        report.push_str(&line);
+        report.push('\n');
+    }
+
+    let metrics = &space.metrics;
+    report.push_str(&format!(
+        "{file_name}: {} functions, cyclomatic {}, cognitive {}, sloc {}, nexits {}\n",
+        metrics.nom.total(),
+        metrics.cyclomatic.cyclomatic_sum(),
+        metrics.cognitive.cognitive_sum(),
+        metrics.loc.sloc(),
+        metrics.nexits.exit_sum(),
+    ));
+    report
+}
+
+fn collect_rows(space: &FuncSpace, name: &str, depth: usize, rows: &mut Vec<[String; 6]>) {
+    let metrics = &space.metrics;
+    rows.push([
+        format!("{:indent$}{name}", "", indent = 2 * depth),
+        format!("{}-{}", space.start_line, space.end_line),
+        metrics.cyclomatic.cyclomatic().to_string(),
+        metrics.cognitive.cognitive().to_string(),
+        metrics.loc.sloc().to_string(),
+        metrics.nexits.exit().to_string(),
+    ]);
+
+    for subspace in &space.spaces {
+        let name = subspace
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("<{}>", subspace.kind));
+        collect_rows(subspace, &name, depth + 1, rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{get_function_spaces, LANG};
+
+    #[test]
+    fn python_text_report() {
+        let code = "def simple(x):
+    return x
+
+class Shape:
+    def area(self, x):
+        if x > 0:
+            return x * x
+        return 0
+";
+        let space =
+            get_function_spaces(&LANG::Python, code.as_bytes().to_vec(), Path::new("foo.py"), None)
+                .unwrap();
+
+        let report = dump_text(&space, "foo.py");
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("NAME "));
+        assert!(lines[1].starts_with("foo.py "));
+        assert!(lines[2].starts_with("  simple "));
+        assert!(lines[3].starts_with("  Shape "));
+        assert!(lines[4].starts_with("    area "));
+        // The columns are aligned
+        assert!(lines[1..5].iter().all(|line| line.len() == lines[0].len()));
+
+        let cyclomatic = format!("cyclomatic {},", space.metrics.cyclomatic.cyclomatic_sum());
+        assert!(lines[5].starts_with("foo.py: 2 functions, "));
+        assert!(lines[5].contains(&cyclomatic));
+    }
+}
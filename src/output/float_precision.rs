@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use serde::{
+    ser::{SerializeStruct, Serializer},
+    Serialize,
+};
+use serde_json::Value;
+
+use crate::analysis_context::{enter_float_precision, round_float};
+
+/// Runs `f` with the floats of the metrics serialized on this thread
+/// rounded to `precision` decimals, or kept as they are when it is `None`.
+///
+/// The floats of the [`custom`](crate::FuncSpace::custom) metrics are
+/// rounded as well. Only the serialized values are rounded: the metrics
+/// themselves keep their full precision.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{metrics, with_float_precision, ParserTrait, PythonParser};
+///
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(b"a = b + c\n".to_vec(), &path, None);
+/// let space = metrics(&parser, &path).unwrap();
+///
+/// let json = with_float_precision(Some(2), || serde_json::to_value(&space)).unwrap();
+/// let volume = json["metrics"]["halstead"]["volume"].as_f64().unwrap();
+/// assert_eq!(volume, (volume * 100.).round() / 100.);
+/// ```
+pub fn with_float_precision<R>(precision: Option<u8>, f: impl FnOnce() -> R) -> R {
+    let _guard = enter_float_precision(precision);
+    f()
+}
+
+/// A metric value which can be rounded to the float precision of the
+/// current thread.
+pub(crate) trait RoundFloat {
+    fn round_float(&self) -> Self;
+}
+
+impl RoundFloat for f64 {
+    fn round_float(&self) -> Self {
+        round_float(*self)
+    }
+}
+
+impl RoundFloat for Option<f64> {
+    fn round_float(&self) -> Self {
+        self.map(round_float)
+    }
+}
+
+/// Serializes the fields of a metric, rounding their values to the float
+/// precision of the current thread.
+pub(crate) struct MetricStruct<S: Serializer>(S::SerializeStruct);
+
+impl<S: Serializer> MetricStruct<S> {
+    pub(crate) fn new(serializer: S, name: &'static str, len: usize) -> Result<Self, S::Error> {
+        serializer.serialize_struct(name, len).map(Self)
+    }
+
+    pub(crate) fn serialize_field<T>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error>
+    where
+        T: RoundFloat + Serialize,
+    {
+        self.0.serialize_field(key, &value.round_float())
+    }
+
+    pub(crate) fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+/// A `JSON` value whose floats are serialized rounded to the float
+/// precision of the current thread, see [`with_float_precision`].
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{with_float_precision, RoundedJson};
+///
+/// let value = serde_json::json!({ "ratio": 0.123_456, "count": 3 });
+/// let json = with_float_precision(Some(2), || serde_json::to_value(RoundedJson(&value)));
+/// assert_eq!(json.unwrap(), serde_json::json!({ "ratio": 0.12, "count": 3 }));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RoundedJson<'a>(pub &'a Value);
+
+impl Serialize for RoundedJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Number(number) if number.is_f64() => match number.as_f64() {
+                Some(value) => serializer.serialize_f64(round_float(value)),
+                None => number.serialize(serializer),
+            },
+            Value::Array(values) => serializer.collect_seq(values.iter().map(RoundedJson)),
+            Value::Object(values) => {
+                serializer.collect_map(values.iter().map(|(key, value)| (key, RoundedJson(value))))
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes the results of the custom metrics, rounding their floats to
+/// the float precision of the current thread.
+pub(crate) fn serialize_custom<S: Serializer>(
+    custom: &BTreeMap<String, Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(custom.iter().map(|(key, value)| (key, RoundedJson(value))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::{metrics, tools::check_metrics, ParserTrait, PythonParser};
+
+    #[test]
+    fn python_volume_rounded() {
+        check_metrics::<PythonParser>("a = b + c\n", "foo.py", |metric| {
+            let full = metric.halstead.volume();
+            let json = with_float_precision(Some(3), || serde_json::to_value(&metric.halstead));
+            let volume = json.unwrap()["volume"].as_f64().unwrap();
+
+            assert_ne!(volume, full);
+            assert_eq!(volume, (full * 1000.).round() / 1000.);
+            // The full precision is restored once the closure returns
+            let json = serde_json::to_value(&metric.halstead).unwrap();
+            assert_eq!(json["volume"].as_f64(), Some(full));
+        });
+    }
+
+    #[test]
+    fn python_custom_rounded() {
+        let path = Path::new("foo.py");
+        let parser = PythonParser::new(b"a = b + c\n".to_vec(), path, None);
+        let mut space = metrics(&parser, path).unwrap();
+        space.custom.insert(
+            "coverage".to_string(),
+            serde_json::json!({ "ratio": 0.666_666, "lines": [1.25, 3] }),
+        );
+
+        let json = with_float_precision(Some(1), || serde_json::to_value(&space)).unwrap();
+        assert_eq!(
+            json["custom"]["coverage"],
+            serde_json::json!({ "ratio": 0.7, "lines": [1.3, 3] })
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use crate::analysis_context::enter_key_style;
+
+/// How the keys of the serialized metrics are named.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyStyle {
+    /// The conventional names, e.g. `n1` and `N1` for the `Halstead`
+    /// distinct and total operators.
+    #[default]
+    Conventional,
+    /// Descriptive `snake_case` names, e.g. `distinct_operators` and
+    /// `total_operators`, for consumers which do not tell keys apart by
+    /// their case.
+    SnakeCase,
+}
+
+/// Runs `f` with the metrics serialized on this thread using the keys
+/// of `style`.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{metrics, with_key_style, KeyStyle, ParserTrait, PythonParser};
+///
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(b"a = b + c\n".to_vec(), &path, None);
+/// let space = metrics(&parser, &path).unwrap();
+///
+/// let json = with_key_style(KeyStyle::SnakeCase, || serde_json::to_value(&space)).unwrap();
+/// assert!(json["metrics"]["halstead"].get("distinct_operators").is_some());
+/// ```
+pub fn with_key_style<R>(style: KeyStyle, f: impl FnOnce() -> R) -> R {
+    let _guard = enter_key_style(style);
+    f()
+}
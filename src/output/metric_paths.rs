@@ -0,0 +1,90 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::spaces::FuncSpace;
+
+/// A metric value together with its path in the serialized space.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricPath {
+    /// The path of the value, e.g. `spaces[0].metrics.cyclomatic.max`
+    pub path: String,
+    /// The value of the metric, `NaN` when it is not defined
+    pub value: f64,
+}
+
+/// Flattens the metrics of a space and of all its subspaces into a list of
+/// values, each one with its path in the serialized space.
+///
+/// Paths are stable as long as the structure of the code does not change,
+/// so the lists of two analyses can be compared value by value, e.g. to
+/// diff reports or to raise alerts. Keys follow the current
+/// [`KeyStyle`](crate::KeyStyle). The values of a space are listed in the
+/// alphabetical order of their keys, before those of its subspaces.
+#[must_use]
+pub fn metric_paths(space: &FuncSpace) -> Vec<MetricPath> {
+    // The serialization of a space never fails: all its keys are strings
+    let value = serde_json::to_value(space).unwrap_or_default();
+    let mut paths = Vec::new();
+    collect_space(&value, "", &mut paths);
+    paths
+}
+
+fn collect_space(space: &Value, prefix: &str, paths: &mut Vec<MetricPath>) {
+    if let Some(metrics) = space.get("metrics") {
+        collect_metrics(metrics, &format!("{prefix}metrics"), paths);
+    }
+    if let Some(Value::Array(spaces)) = space.get("spaces") {
+        for (index, space) in spaces.iter().enumerate() {
+            collect_space(space, &format!("{prefix}spaces[{index}]."), paths);
+        }
+    }
+}
+
+fn collect_metrics(value: &Value, path: &str, paths: &mut Vec<MetricPath>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                collect_metrics(field, &format!("{path}.{key}"), paths);
+            }
+        }
+        Value::Number(number) => paths.push(MetricPath {
+            path: path.to_string(),
+            value: number.as_f64().unwrap_or(f64::NAN),
+        }),
+        // Undefined values, such as the averages of a code without
+        // functions, are serialized as `null`
+        Value::Null => paths.push(MetricPath {
+            path: path.to_string(),
+            value: f64::NAN,
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{get_function_spaces, LANG};
+
+    #[test]
+    fn python_metric_paths() {
+        let code = "def f(a):\n    if a:\n        return 1\n    return 0\n";
+        let space =
+            get_function_spaces(&LANG::Python, code.as_bytes().to_vec(), Path::new("foo.py"), None)
+                .unwrap();
+
+        let paths = metric_paths(&space);
+        let value = |path: &str| {
+            paths
+                .iter()
+                .find(|metric| metric.path == path)
+                .map(|metric| metric.value)
+        };
+        assert_eq!(value("metrics.cyclomatic.sum"), Some(3.));
+        assert_eq!(value("spaces[0].metrics.cyclomatic.sum"), Some(2.));
+        assert_eq!(value("metrics.cyclomatic.total"), None);
+        assert!(paths.iter().all(|metric| metric.path.contains("metrics.")));
+    }
+}
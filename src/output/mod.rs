@@ -1,3 +1,6 @@
+pub(crate) mod anonymize;
+pub use anonymize::*;
+
 pub(crate) mod dump;
 pub use dump::*;
 
@@ -6,3 +9,24 @@ pub use dump_metrics::*;
 
 pub(crate) mod dump_ops;
 pub use dump_ops::*;
+
+pub(crate) mod dump_text;
+pub use dump_text::*;
+
+pub(crate) mod float_precision;
+pub use float_precision::*;
+
+pub(crate) mod key_style;
+pub use key_style::*;
+
+pub(crate) mod metric_paths;
+pub use metric_paths::*;
+
+pub mod report;
+pub use report::FuncSpaceReport;
+
+pub(crate) mod summary;
+pub use summary::*;
+
+pub(crate) mod thresholds;
+pub use thresholds::*;
@@ -0,0 +1,503 @@
+//! Typed mirror of the `JSON` emitted when serializing a [`FuncSpace`].
+//!
+//! The metric structs serialize themselves through hand-written
+//! `Serialize` implementations, so their `JSON` shape is not visible from
+//! their Rust definitions. The types in this module describe that shape
+//! with named fields, so that a report can be deserialized type-safely
+//! instead of going through an untyped `JSON` value.
+//!
+//! Metric values are `Option<f64>` because values which are not defined,
+//! e.g. an average over zero functions, are emitted as `null`.
+//!
+//! [`FuncSpace`]: crate::FuncSpace
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::spaces::SpaceKind;
+
+/// A function space as found in a serialized metrics report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuncSpaceReport {
+    /// The name of the function space
+    pub name: Option<String>,
+    /// The first line of the function space
+    pub start_line: usize,
+    /// The last line of the function space
+    pub end_line: usize,
+    /// The space kind
+    pub kind: SpaceKind,
+    /// All subspaces contained in the function space
+    pub spaces: Vec<FuncSpaceReport>,
+    /// All metrics of the function space
+    pub metrics: Metrics,
+    /// The supertypes declared by a class or an interface space
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supertypes: Option<Supertypes>,
+    /// The signature of a named function space
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The decorators of a function or a class space
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decorators: Vec<String>,
+    /// The hash of the source of the function space, as 16 hexadecimal
+    /// digits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// The results of the custom metrics, keyed by metric name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, serde_json::Value>,
+}
+
+/// The supertypes declared by a class or an interface.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Supertypes {
+    /// The extended classes, or the extended interfaces of an interface
+    pub extends: Vec<String>,
+    /// The implemented interfaces
+    pub implements: Vec<String>,
+    /// The depth of inheritance within the analyzed code
+    pub dit_declared: usize,
+    /// The number of distinct declared supertypes
+    pub cbo_declared: usize,
+}
+
+/// All metrics of a function space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    /// `NArgs` data
+    pub nargs: Nargs,
+    /// `NExits` data
+    pub nexits: SumStats,
+    /// Kinds of the exit points
+    pub exit_kinds: ExitKinds,
+    /// `Cognitive` data
+    pub cognitive: SumStats,
+    /// `Cyclomatic` data
+    pub cyclomatic: SumStats,
+    /// `Halstead` data
+    pub halstead: Halstead,
+    /// `Loc` data
+    pub loc: Loc,
+    /// `Nom` data
+    pub nom: Nom,
+    /// `Mi` data
+    pub mi: Mi,
+    /// `Abc` data
+    pub abc: Abc,
+    /// `BooleanOps` data
+    #[serde(default)]
+    pub boolean_ops: BooleanOps,
+    /// `Locals` data
+    #[serde(default)]
+    pub locals: Locals,
+    /// `Wmc` data, only present for object-oriented spaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wmc: Option<Wmc>,
+    /// `Npm` data, only present for object-oriented spaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npm: Option<Npm>,
+    /// `Npa` data, only present for object-oriented spaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npa: Option<Npa>,
+    /// `Unsafe` data, only present for `Rust` code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsafe_count: Option<Unsafe>,
+    /// `Jsx` data, only present for code which can contain `JSX`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsx: Option<Jsx>,
+    /// `Template` data, only present for `C++` code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<Template>,
+}
+
+/// Sum, average, minimum and maximum of a metric.
+///
+/// Used by the `NExits`, `Cognitive` and `Cyclomatic` metrics.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SumStats {
+    /// The sum of the metric over a space and its subspaces
+    pub sum: Option<f64>,
+    /// The average of the metric
+    pub average: Option<f64>,
+    /// The minimum value of the metric
+    pub min: Option<f64>,
+    /// The maximum value of the metric
+    pub max: Option<f64>,
+}
+
+/// The kinds of the exit points of a space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitKinds {
+    /// Whether the space contains a `return`
+    pub normal_return: bool,
+    /// Whether the space throws or raises an exception, or panics
+    pub exception: bool,
+    /// Whether the space contains a `break` or a `continue` to a label
+    pub labeled_break: bool,
+    /// Whether the space contains a `goto`
+    pub goto: bool,
+    /// Whether the space calls a function terminating the process
+    pub process_exit: bool,
+}
+
+/// The `NArgs` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nargs {
+    /// The number of function arguments
+    pub total_functions: Option<f64>,
+    /// The number of closure arguments
+    pub total_closures: Option<f64>,
+    /// The average number of function arguments
+    pub average_functions: Option<f64>,
+    /// The average number of closure arguments
+    pub average_closures: Option<f64>,
+    /// The number of function and closure arguments
+    pub total: Option<f64>,
+    /// The average number of function and closure arguments
+    pub average: Option<f64>,
+    /// The minimum number of function arguments
+    pub functions_min: Option<f64>,
+    /// The maximum number of function arguments
+    pub functions_max: Option<f64>,
+    /// The minimum number of closure arguments
+    pub closures_min: Option<f64>,
+    /// The maximum number of closure arguments
+    pub closures_max: Option<f64>,
+}
+
+/// The `Halstead` metric suite.
+///
+/// The `snake_case` keys of [`KeyStyle::SnakeCase`] are accepted as well.
+///
+/// [`KeyStyle::SnakeCase`]: crate::KeyStyle::SnakeCase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Halstead {
+    /// The number of distinct operators
+    #[serde(rename = "n1", alias = "distinct_operators")]
+    pub u_operators: Option<f64>,
+    /// The total number of operators
+    #[serde(rename = "N1", alias = "total_operators")]
+    pub operators: Option<f64>,
+    /// The number of distinct operands
+    #[serde(rename = "n2", alias = "distinct_operands")]
+    pub u_operands: Option<f64>,
+    /// The total number of operands
+    #[serde(rename = "N2", alias = "total_operands")]
+    pub operands: Option<f64>,
+    /// The program length
+    pub length: Option<f64>,
+    /// The calculated estimated program length
+    pub estimated_program_length: Option<f64>,
+    /// The purity ratio
+    pub purity_ratio: Option<f64>,
+    /// The program vocabulary
+    pub vocabulary: Option<f64>,
+    /// The program volume
+    pub volume: Option<f64>,
+    /// The estimated difficulty
+    pub difficulty: Option<f64>,
+    /// The program level
+    pub level: Option<f64>,
+    /// The estimated effort
+    pub effort: Option<f64>,
+    /// The estimated programming time in seconds
+    pub time: Option<f64>,
+    /// The estimated number of delivered bugs
+    pub bugs: Option<f64>,
+}
+
+/// The `Loc` metric suite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loc {
+    /// Source lines of code
+    pub sloc: Option<f64>,
+    /// Physical lines of code
+    pub ploc: Option<f64>,
+    /// Logical lines of code
+    pub lloc: Option<f64>,
+    /// Comment lines of code
+    pub cloc: Option<f64>,
+    /// Blank lines
+    pub blank: Option<f64>,
+    /// Average `Sloc` over all spaces
+    pub sloc_average: Option<f64>,
+    /// Average `Ploc` over all spaces
+    pub ploc_average: Option<f64>,
+    /// Average `Lloc` over all spaces
+    pub lloc_average: Option<f64>,
+    /// Average `Cloc` over all spaces
+    pub cloc_average: Option<f64>,
+    /// Average number of blank lines over all spaces
+    pub blank_average: Option<f64>,
+    /// Minimum `Sloc`
+    pub sloc_min: Option<f64>,
+    /// Maximum `Sloc`
+    pub sloc_max: Option<f64>,
+    /// Minimum `Cloc`
+    pub cloc_min: Option<f64>,
+    /// Maximum `Cloc`
+    pub cloc_max: Option<f64>,
+    /// Minimum `Ploc`
+    pub ploc_min: Option<f64>,
+    /// Maximum `Ploc`
+    pub ploc_max: Option<f64>,
+    /// Minimum `Lloc`
+    pub lloc_min: Option<f64>,
+    /// Maximum `Lloc`
+    pub lloc_max: Option<f64>,
+    /// Minimum number of blank lines
+    pub blank_min: Option<f64>,
+    /// Maximum number of blank lines
+    pub blank_max: Option<f64>,
+    /// Length in physical lines of the longest function
+    pub function_length_max: Option<f64>,
+    /// Ratio of comment lines to comment and source lines
+    pub comment_ratio: Option<f64>,
+}
+
+/// The `Nom` metric suite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nom {
+    /// The number of functions
+    pub functions: Option<f64>,
+    /// The number of closures
+    pub closures: Option<f64>,
+    /// The average number of functions over all spaces
+    pub functions_average: Option<f64>,
+    /// The average number of closures over all spaces
+    pub closures_average: Option<f64>,
+    /// The number of functions and closures
+    pub total: Option<f64>,
+    /// The average number of functions and closures over all spaces
+    pub average: Option<f64>,
+    /// The minimum number of functions
+    pub functions_min: Option<f64>,
+    /// The maximum number of functions
+    pub functions_max: Option<f64>,
+    /// The minimum number of closures
+    pub closures_min: Option<f64>,
+    /// The maximum number of closures
+    pub closures_max: Option<f64>,
+    /// The number of functions which are not methods
+    pub nom_functions: Option<f64>,
+    /// The number of functions defined as members of a class, struct,
+    /// interface, trait or implementation
+    pub nom_methods: Option<f64>,
+    /// The number of closures and lambdas
+    pub nom_closures: Option<f64>,
+}
+
+/// The `Mi` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mi {
+    /// `Mi` computed with the original formula
+    pub mi_original: Option<f64>,
+    /// `Mi` computed with the SEI formula
+    pub mi_sei: Option<f64>,
+    /// `Mi` computed with the Visual Studio formula
+    pub mi_visual_studio: Option<f64>,
+}
+
+/// The `Abc` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Abc {
+    /// The number of assignments
+    pub assignments: Option<f64>,
+    /// The number of branches
+    pub branches: Option<f64>,
+    /// The number of conditions
+    pub conditions: Option<f64>,
+    /// The `Abc` magnitude
+    pub magnitude: Option<f64>,
+    /// The average number of assignments
+    pub assignments_average: Option<f64>,
+    /// The average number of branches
+    pub branches_average: Option<f64>,
+    /// The average number of conditions
+    pub conditions_average: Option<f64>,
+    /// The minimum number of assignments
+    pub assignments_min: Option<f64>,
+    /// The maximum number of assignments
+    pub assignments_max: Option<f64>,
+    /// The minimum number of branches
+    pub branches_min: Option<f64>,
+    /// The maximum number of branches
+    pub branches_max: Option<f64>,
+    /// The minimum number of conditions
+    pub conditions_min: Option<f64>,
+    /// The maximum number of conditions
+    pub conditions_max: Option<f64>,
+}
+
+/// The `Wmc` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wmc {
+    /// `Wmc` of the classes
+    pub classes: Option<f64>,
+    /// `Wmc` of the interfaces
+    pub interfaces: Option<f64>,
+    /// Total `Wmc`
+    pub total: Option<f64>,
+}
+
+/// The `Npm` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Npm {
+    /// Public methods of the classes
+    pub classes: Option<f64>,
+    /// Public methods of the interfaces
+    pub interfaces: Option<f64>,
+    /// Methods of the classes
+    pub class_methods: Option<f64>,
+    /// Methods of the interfaces
+    pub interface_methods: Option<f64>,
+    /// Class operation accessibility
+    pub classes_average: Option<f64>,
+    /// Interface operation accessibility
+    pub interfaces_average: Option<f64>,
+    /// Total public methods
+    pub total: Option<f64>,
+    /// Total methods
+    pub total_methods: Option<f64>,
+    /// Total operation accessibility
+    pub average: Option<f64>,
+}
+
+/// The `Npa` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Npa {
+    /// Public attributes of the classes
+    pub classes: Option<f64>,
+    /// Public attributes of the interfaces
+    pub interfaces: Option<f64>,
+    /// Attributes of the classes
+    pub class_attributes: Option<f64>,
+    /// Attributes of the interfaces
+    pub interface_attributes: Option<f64>,
+    /// Class data accessibility
+    pub classes_average: Option<f64>,
+    /// Interface data accessibility
+    pub interfaces_average: Option<f64>,
+    /// Total public attributes
+    pub total: Option<f64>,
+    /// Total attributes
+    pub total_attributes: Option<f64>,
+    /// Total data accessibility
+    pub average: Option<f64>,
+}
+
+/// The `BooleanOps` metric.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BooleanOps {
+    /// The number of conjunctions, such as `&&` or `and`
+    pub and: Option<f64>,
+    /// The number of disjunctions, such as `||` or `or`
+    pub or: Option<f64>,
+    /// The number of negations, such as `!` or `not`
+    pub not: Option<f64>,
+    /// The number of logical operators
+    pub total: Option<f64>,
+}
+
+/// The `Locals` metric.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Locals {
+    /// The number of local variables
+    pub total: Option<f64>,
+    /// The largest number of local variables of a single space
+    pub max: Option<f64>,
+}
+
+/// The `Unsafe` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Unsafe {
+    /// The number of `unsafe` blocks
+    pub blocks: Option<f64>,
+    /// The number of `unsafe` functions
+    pub functions: Option<f64>,
+    /// The number of `unsafe` blocks and functions
+    pub total: Option<f64>,
+}
+
+/// The `Jsx` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Jsx {
+    /// The number of `JSX` elements
+    pub jsx_elements: Option<f64>,
+    /// The deepest nesting of `JSX` elements
+    pub jsx_max_depth: Option<f64>,
+}
+
+/// The `Template` metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Template {
+    /// The number of `template` declarations
+    pub template_count: Option<f64>,
+    /// The deepest nesting of template argument lists
+    pub template_depth: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FuncSpaceReport;
+    use crate::{tools::check_func_space, JavaParser, ParserEngineRust, PythonParser};
+
+    fn check_round_trip(space: &crate::FuncSpace) {
+        let emitted = serde_json::to_value(space).unwrap();
+        let report: FuncSpaceReport = serde_json::from_value(emitted.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&report).unwrap(), emitted);
+    }
+
+    #[test]
+    fn python_report_round_trip() {
+        check_func_space::<PythonParser, _>(
+            "def f(a, b):
+                 if a:
+                     return b
+                 return lambda x: x + a",
+            "foo.py",
+            |mut space| {
+                space.custom.insert("todos".to_string(), 3.into());
+                check_round_trip(&space);
+
+                let report: FuncSpaceReport =
+                    serde_json::from_value(serde_json::to_value(&space).unwrap()).unwrap();
+                assert_eq!(report.spaces.len(), 1);
+                assert_eq!(report.spaces[0].name.as_deref(), Some("f"));
+                assert_eq!(report.custom["todos"], 3);
+                assert!(report.spaces[0].custom.is_empty());
+                // No class, so no method weighs on the `Wmc`
+                assert_eq!(report.metrics.wmc.and_then(|wmc| wmc.total), Some(0.));
+            },
+        );
+    }
+
+    #[test]
+    fn java_report_round_trip() {
+        check_func_space::<JavaParser, _>(
+            "class A {
+                 public int x;
+                 public int f() { return x; }
+             }",
+            "foo.java",
+            |space| {
+                check_round_trip(&space);
+            },
+        );
+    }
+
+    #[test]
+    fn rust_report_round_trip() {
+        check_func_space::<ParserEngineRust, _>(
+            "fn f(p: *const u8) -> u8 {
+                 unsafe { *p }
+             }",
+            "foo.rs",
+            |space| {
+                check_round_trip(&space);
+            },
+        );
+    }
+}
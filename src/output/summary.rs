@@ -0,0 +1,440 @@
+//! Repository-level distributions of the function metrics.
+//!
+//! Percentiles use the nearest-rank method: the `p`-th percentile of `n`
+//! sorted values is the value at rank `ceil(p / 100 * n)`, so it is always
+//! one of the observed values.
+
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// The number of worst functions listed by [`repo_summary`] for each metric.
+pub const REPO_SUMMARY_TOP_N: usize = 10;
+
+/// Summary of the functions of a set of files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepoSummary {
+    /// The number of analyzed files
+    pub files: usize,
+    /// The number of functions found in all files
+    pub functions: usize,
+    /// Distribution of the `Cyclomatic Complexity` of the functions
+    pub cyclomatic: MetricSummary,
+    /// Distribution of the `Cognitive Complexity` of the functions
+    pub cognitive: MetricSummary,
+    /// Distribution of the `SLOC` of the functions
+    pub sloc: MetricSummary,
+}
+
+/// Distribution of a metric across functions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricSummary {
+    /// The sum of the metric over all functions
+    pub total: f64,
+    /// The median value
+    pub p50: f64,
+    /// The 90th percentile
+    pub p90: f64,
+    /// The 95th percentile
+    pub p95: f64,
+    /// The maximum value
+    pub max: f64,
+    /// The functions with the highest values, in decreasing order
+    pub worst: Vec<FunctionSummary>,
+}
+
+/// A metric by which the functions are ranked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankMetric {
+    /// The `Cyclomatic Complexity`
+    #[default]
+    Cyclomatic,
+    /// The `Cognitive Complexity`
+    Cognitive,
+    /// The `SLOC`
+    Sloc,
+}
+
+impl RankMetric {
+    /// Returns the value of the metric for a function space alone, without
+    /// its subspaces.
+    #[must_use]
+    pub fn value(self, space: &FuncSpace) -> f64 {
+        match self {
+            Self::Cyclomatic => space.metrics.cyclomatic.cyclomatic(),
+            Self::Cognitive => space.metrics.cognitive.cognitive(),
+            Self::Sloc => space.metrics.loc.sloc(),
+        }
+    }
+}
+
+/// A function together with the value of a metric.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionSummary {
+    /// The file containing the function
+    pub path: PathBuf,
+    /// The name of the function
+    pub name: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The value of the metric
+    pub value: f64,
+}
+
+/// Summarizes the functions of the given files, listing the
+/// [`REPO_SUMMARY_TOP_N`] worst functions for each metric.
+///
+/// Metrics are taken from each function space alone, so the complexity
+/// of a nested function is not counted twice.
+#[must_use]
+pub fn repo_summary(spaces: &[(PathBuf, FuncSpace)]) -> RepoSummary {
+    repo_summary_with_top(spaces, REPO_SUMMARY_TOP_N)
+}
+
+/// Summarizes the functions of the given files, listing the `top`
+/// worst functions for each metric.
+///
+/// The percentiles need the values of all the functions, so the spaces of
+/// all the files are summarized at once. To bound the memory of the
+/// analysis of a large directory, [`run_top_functions`] ranks its
+/// functions for a single metric while the files are analyzed.
+///
+/// [`run_top_functions`]: crate::run_top_functions
+#[must_use]
+pub fn repo_summary_with_top(spaces: &[(PathBuf, FuncSpace)], top: usize) -> RepoSummary {
+    let mut functions = Vec::new();
+    for (path, space) in spaces {
+        collect_functions(path, space, &mut functions);
+    }
+
+    RepoSummary {
+        files: spaces.len(),
+        functions: functions.len(),
+        cyclomatic: summarize(&functions, top, RankMetric::Cyclomatic),
+        cognitive: summarize(&functions, top, RankMetric::Cognitive),
+        sloc: summarize(&functions, top, RankMetric::Sloc),
+    }
+}
+
+/// A file together with its worst function for a metric.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileSummary {
+    /// The path of the file
+    pub path: PathBuf,
+    /// The number of functions of the file
+    pub functions: usize,
+    /// The function of the file with the highest value, if any
+    pub worst: Option<FunctionSummary>,
+}
+
+/// The worst functions of a set of files for a metric, along with the files
+/// sorted by their worst function.
+///
+/// Files are added one at a time and only the `top` worst functions are
+/// kept, so the memory used does not grow with the number of functions:
+/// the function spaces of a file can be dropped as soon as it is added.
+/// The functions and the files are sorted by [`TopFunctions::finish`].
+///
+/// Functions and files with the same value are sorted by path, then by
+/// line, so the result does not depend on the order in which the files are
+/// added.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TopFunctions {
+    /// The metric ranking the functions
+    pub metric: RankMetric,
+    /// The functions with the highest values, in decreasing order
+    pub functions: Vec<FunctionSummary>,
+    /// The files, in decreasing order of the value of their worst function,
+    /// the files without functions coming last
+    pub files: Vec<FileSummary>,
+    #[serde(skip)]
+    top: usize,
+}
+
+impl TopFunctions {
+    /// Creates an empty ranking keeping the `top` worst functions for
+    /// `metric`.
+    #[must_use]
+    pub fn new(metric: RankMetric, top: usize) -> Self {
+        Self {
+            metric,
+            functions: Vec::new(),
+            files: Vec::new(),
+            top,
+        }
+    }
+
+    /// Adds the functions of a file to the ranking.
+    ///
+    /// The functions and the files are left unsorted until the ranking is
+    /// finished.
+    pub fn add(&mut self, path: &Path, space: &FuncSpace) {
+        let mut functions = Vec::new();
+        collect_functions(path, space, &mut functions);
+
+        let mut worst: Option<FunctionSummary> = None;
+        for (path, space) in &functions {
+            let function = function_summary(path, space, self.metric.value(space));
+            if worst
+                .as_ref()
+                .is_none_or(|worst| rank_functions(&function, worst) == Ordering::Less)
+            {
+                worst = Some(function.clone());
+            }
+            self.functions.push(function);
+        }
+        // Up to twice `top` functions are kept between two selections, so
+        // the cost of each added function stays constant
+        if self.functions.len() > 2 * self.top {
+            self.select_top();
+        }
+
+        self.files.push(FileSummary {
+            path: path.to_path_buf(),
+            functions: functions.len(),
+            worst,
+        });
+    }
+
+    /// Sorts the `top` worst functions and the files, once all the files are
+    /// added.
+    #[must_use]
+    pub fn finish(mut self) -> Self {
+        self.select_top();
+        self.functions.sort_by(rank_functions);
+        self.files.sort_by(rank_files);
+        self
+    }
+
+    // Keeps the `top` worst functions, unsorted
+    fn select_top(&mut self) {
+        if self.functions.len() > self.top {
+            if self.top > 0 {
+                self.functions
+                    .select_nth_unstable_by(self.top - 1, rank_functions);
+            }
+            self.functions.truncate(self.top);
+        }
+    }
+}
+
+/// Returns the `top` worst functions of the given files for `metric`, and
+/// the files sorted by their worst function.
+///
+/// See [`TopFunctions`] to rank the files while they are analyzed, without
+/// keeping all their spaces.
+#[must_use]
+pub fn top_functions(
+    spaces: &[(PathBuf, FuncSpace)],
+    metric: RankMetric,
+    top: usize,
+) -> TopFunctions {
+    let mut ranking = TopFunctions::new(metric, top);
+    for (path, space) in spaces {
+        ranking.add(path, space);
+    }
+    ranking.finish()
+}
+
+// Orders the functions by decreasing value, then by path and line
+fn rank_functions(a: &FunctionSummary, b: &FunctionSummary) -> Ordering {
+    b.value
+        .total_cmp(&a.value)
+        .then_with(|| a.path.cmp(&b.path))
+        .then(a.start_line.cmp(&b.start_line))
+}
+
+// Orders the files by their worst function, the files without functions
+// coming last in path order
+fn rank_files(a: &FileSummary, b: &FileSummary) -> Ordering {
+    match (&a.worst, &b.worst) {
+        (Some(a), Some(b)) => rank_functions(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.path.cmp(&b.path),
+    }
+}
+
+fn function_summary(path: &Path, space: &FuncSpace, value: f64) -> FunctionSummary {
+    FunctionSummary {
+        path: path.to_path_buf(),
+        name: space.name.clone(),
+        start_line: space.start_line,
+        end_line: space.end_line,
+        value,
+    }
+}
+
+fn collect_functions<'a>(
+    path: &'a Path,
+    space: &'a FuncSpace,
+    functions: &mut Vec<(&'a Path, &'a FuncSpace)>,
+) {
+    let mut stack = vec![space];
+    while let Some(space) = stack.pop() {
+        if space.kind == SpaceKind::Function {
+            functions.push((path, space));
+        }
+        stack.extend(space.spaces.iter().rev());
+    }
+}
+
+fn summarize(functions: &[(&Path, &FuncSpace)], top: usize, metric: RankMetric) -> MetricSummary {
+    let mut ranked: Vec<_> = functions
+        .iter()
+        .map(|(path, space)| (*path, *space, metric.value(space)))
+        .collect();
+    // Stable sort: functions with the same value keep their source order
+    ranked.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut sorted: Vec<f64> = ranked.iter().map(|(_, _, metric)| *metric).collect();
+    sorted.reverse();
+
+    MetricSummary {
+        total: sorted.iter().sum(),
+        p50: percentile(&sorted, 50),
+        p90: percentile(&sorted, 90),
+        p95: percentile(&sorted, 95),
+        max: sorted.last().copied().unwrap_or_default(),
+        worst: ranked
+            .into_iter()
+            .take(top)
+            .map(|(path, space, metric)| function_summary(path, space, metric))
+            .collect(),
+    }
+}
+
+// Nearest-rank percentile of values sorted in increasing order
+fn percentile(sorted: &[f64], p: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::{get_function_spaces, LANG};
+
+    fn space(path: &str, code: &str) -> (PathBuf, FuncSpace) {
+        let path = PathBuf::from(path);
+        let space =
+            get_function_spaces(&LANG::Python, code.as_bytes().to_vec(), Path::new(&path), None)
+                .unwrap();
+        (path, space)
+    }
+
+    #[test]
+    fn python_repo_summary() {
+        let spaces = [
+            space(
+                "a.py",
+                "def one(x):
+    return x
+
+def three(x):
+    if x > 0:
+        return 1
+    elif x < 0:
+        return -1
+    return 0
+",
+            ),
+            space(
+                "b.py",
+                "def two(x):
+    if x:
+        return 1
+    return 0
+
+def four(x):
+    if x and x > 1:
+        return 2
+    elif x:
+        return 1
+    return 0
+",
+            ),
+        ];
+
+        let summary = repo_summary_with_top(&spaces, 3);
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.functions, 4);
+
+        let cyclomatic = &summary.cyclomatic;
+        assert_eq!(cyclomatic.total, 10.);
+        assert_eq!(cyclomatic.p50, 2.);
+        assert_eq!(cyclomatic.p90, 4.);
+        assert_eq!(cyclomatic.p95, 4.);
+        assert_eq!(cyclomatic.max, 4.);
+
+        let worst: Vec<_> = cyclomatic
+            .worst
+            .iter()
+            .map(|function| (function.path.to_str().unwrap(), function.name.as_deref()))
+            .collect();
+        assert_eq!(
+            worst,
+            [
+                ("b.py", Some("four")),
+                ("a.py", Some("three")),
+                ("b.py", Some("two"))
+            ]
+        );
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["cyclomatic"]["worst"][0]["value"], 4.);
+        assert_eq!(json["sloc"]["max"], 6.);
+    }
+
+    #[test]
+    fn empty_repo_summary() {
+        let summary = repo_summary(&[]);
+        assert_eq!(summary.functions, 0);
+        assert_eq!(summary.cognitive.p95, 0.);
+        assert!(summary.cognitive.worst.is_empty());
+    }
+
+    #[test]
+    fn top_functions_of_many_files() {
+        // Branches of increasing number, so each function has its own value
+        let spaces: Vec<_> = (0..20)
+            .map(|i| {
+                let code = format!(
+                    "def f{i}(x):\n{}    return 0\n",
+                    "    if x:\n        x\n".repeat(i)
+                );
+                space(&format!("f{i:02}.py"), &code)
+            })
+            .collect();
+        let mut reversed = spaces.clone();
+        reversed.reverse();
+
+        let ranking = top_functions(&spaces, RankMetric::Cyclomatic, 3);
+        let values: Vec<_> = ranking
+            .functions
+            .iter()
+            .map(|function| function.value)
+            .collect();
+        assert_eq!(values, [20., 19., 18.]);
+        assert_eq!(ranking.files.len(), 20);
+        assert_eq!(ranking.files[0].path, PathBuf::from("f19.py"));
+        assert_eq!(ranking.files[19].path, PathBuf::from("f00.py"));
+        assert_eq!(top_functions(&reversed, RankMetric::Cyclomatic, 3), ranking);
+        assert!(top_functions(&spaces, RankMetric::Cyclomatic, 0)
+            .functions
+            .is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Pass or fail checks of the function metrics against limits, e.g. to
+//! gate a continuous integration build.
+
+use serde::Serialize;
+
+use crate::find::{functions_over, FunctionMetric};
+use crate::spaces::FuncSpace;
+
+/// The highest value allowed for a metric of every function.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Threshold {
+    /// The checked metric
+    pub metric: FunctionMetric,
+    /// The highest value allowed, inclusive
+    pub limit: f64,
+}
+
+/// A function exceeding a [`Threshold`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThresholdViolation {
+    /// The name of the function
+    pub function: Option<String>,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The exceeded metric
+    pub metric: FunctionMetric,
+    /// The value of the metric for the function
+    pub value: f64,
+    /// The limit of the exceeded threshold
+    pub limit: f64,
+}
+
+/// The result of [`evaluate_thresholds`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThresholdReport {
+    /// Whether no threshold is exceeded, so a command line tool can
+    /// exit with a nonzero code when it is `false`
+    pub passed: bool,
+    /// The functions exceeding a threshold, grouped by threshold in the
+    /// order of the thresholds, then in source order
+    pub violations: Vec<ThresholdViolation>,
+}
+
+/// Checks the functions inside `space` against `thresholds`.
+///
+/// A function violates a threshold when its metric is strictly greater
+/// than the limit, and it is listed once for each threshold it violates.
+/// Metrics are taken from each function space alone, so the complexity
+/// of a nested function only counts against that function.
+#[must_use]
+pub fn evaluate_thresholds(space: &FuncSpace, thresholds: &[Threshold]) -> ThresholdReport {
+    let violations: Vec<_> = thresholds
+        .iter()
+        .flat_map(|threshold| {
+            functions_over(space, threshold.metric, threshold.limit)
+                .into_iter()
+                .map(|function| ThresholdViolation {
+                    function: function.name.clone(),
+                    start_line: function.start_line,
+                    end_line: function.end_line,
+                    metric: threshold.metric,
+                    value: threshold.metric.value(function),
+                    limit: threshold.limit,
+                })
+        })
+        .collect();
+
+    ThresholdReport {
+        passed: violations.is_empty(),
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{get_function_spaces, LANG};
+
+    #[test]
+    fn python_cyclomatic_threshold() {
+        let code = "def simple(x):
+    return x
+
+def branchy(x):
+    if x > 0:
+        return 1
+    elif x < 0:
+        return -1
+    return 0
+";
+        let space =
+            get_function_spaces(&LANG::Python, code.as_bytes().to_vec(), Path::new("foo.py"), None)
+                .unwrap();
+        let thresholds = [Threshold {
+            metric: FunctionMetric::Cyclomatic,
+            limit: 2.,
+        }];
+
+        let report = evaluate_thresholds(&space, &thresholds);
+        assert!(!report.passed);
+        assert_eq!(
+            report.violations,
+            [ThresholdViolation {
+                function: Some("branchy".to_string()),
+                start_line: 4,
+                end_line: 9,
+                metric: FunctionMetric::Cyclomatic,
+                value: 3.,
+                limit: 2.,
+            }]
+        );
+
+        let thresholds = [Threshold {
+            metric: FunctionMetric::Cyclomatic,
+            limit: 3.,
+        }];
+        assert!(evaluate_thresholds(&space, &thresholds).passed);
+    }
+}
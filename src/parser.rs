@@ -3,6 +3,7 @@ use std::{marker::PhantomData, path::Path, sync::Arc};
 use crate::{
     abc::Abc,
     alterator::Alterator,
+    boolean_ops::BooleanOps,
     c_macro,
     checker::Checker,
     cognitive::Cognitive,
@@ -10,8 +11,10 @@ use crate::{
     exit::Exit,
     getter::Getter,
     halstead::Halstead,
+    jsx::Jsx,
     langs::*,
     loc::Loc,
+    locals::Locals,
     mi::Mi,
     nargs::NArgs,
     node::{Node, Tree},
@@ -19,7 +22,9 @@ use crate::{
     npa::Npa,
     npm::Npm,
     preproc::{get_macros, PreprocResults},
+    template::Template,
     traits::*,
+    unsafety::Unsafety,
     wmc::Wmc,
 };
 
@@ -30,16 +35,21 @@ pub struct Parser<
         + Checker
         + Getter
         + Abc
+        + BooleanOps
+        + Locals
         + Cognitive
         + Cyclomatic
         + Exit
         + Halstead
+        + Jsx
         + Loc
         + Mi
         + NArgs
         + Nom
         + Npa
         + Npm
+        + Template
+        + Unsafety
         + Wmc,
 > {
     code: Vec<u8>,
@@ -75,7 +85,13 @@ fn get_fake_code<T: LanguageInfo>(
         match T::get_lang() {
             LANG::Cpp => {
                 let macros = get_macros(path, &pr.files);
-                c_macro::replace(code, &macros)
+                if pr.defines.is_empty() {
+                    return c_macro::replace(code, &macros);
+                }
+                match c_macro::resolve_conditionals(code, &pr.defines) {
+                    Some(resolved) => c_macro::replace(&resolved, &macros).or(Some(resolved)),
+                    None => c_macro::replace(code, &macros),
+                }
             }
             _ => None,
         }
@@ -91,16 +107,21 @@ impl<
             + Checker
             + Getter
             + Abc
+            + BooleanOps
+            + Locals
             + Cognitive
             + Cyclomatic
             + Exit
             + Halstead
+            + Jsx
             + Loc
             + Mi
             + NArgs
             + Nom
             + Npa
             + Npm
+            + Template
+            + Unsafety
             + Wmc,
     > ParserTrait for Parser<T>
 {
@@ -109,6 +130,7 @@ impl<
     type Cognitive = T;
     type Cyclomatic = T;
     type Halstead = T;
+    type Jsx = T;
     type Loc = T;
     type Nom = T;
     type Mi = T;
@@ -116,24 +138,18 @@ impl<
     type Exit = T;
     type Wmc = T;
     type Abc = T;
+    type BooleanOps = T;
+    type Locals = T;
     type Npm = T;
     type Npa = T;
+    type Unsafety = T;
+    type Template = T;
 
+    // The grammars are built along with this crate, so they can always be
+    // loaded unless a mismatched tree-sitter version is linked
+    #[allow(clippy::expect_used)]
     fn new(code: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Self {
-        let fake_code = get_fake_code::<T>(&code, path, pr);
-        let code = if let Some(fake) = fake_code {
-            fake
-        } else {
-            code
-        };
-
-        let tree = Tree::new::<T>(&code);
-
-        Self {
-            code,
-            tree,
-            phantom: PhantomData,
-        }
+        Self::try_new(code, path, pr).expect("the grammar of the language cannot be loaded")
     }
 
     #[inline]
@@ -181,3 +197,61 @@ impl<
         Filter { filters: res }
     }
 }
+
+impl<
+        T: 'static
+            + LanguageInfo
+            + Alterator
+            + Checker
+            + Getter
+            + Abc
+            + BooleanOps
+            + Locals
+            + Cognitive
+            + Cyclomatic
+            + Exit
+            + Halstead
+            + Jsx
+            + Loc
+            + Mi
+            + NArgs
+            + Nom
+            + Npa
+            + Npm
+            + Template
+            + Unsafety
+            + Wmc,
+    > Parser<T>
+{
+    // Parses `code`, or returns `None` when the grammar of the language
+    // cannot be loaded
+    pub(crate) fn try_new(
+        code: Vec<u8>,
+        path: &Path,
+        pr: Option<Arc<PreprocResults>>,
+    ) -> Option<Self> {
+        let fake_code = get_fake_code::<T>(&code, path, pr);
+        let code = if let Some(fake) = fake_code {
+            fake
+        } else {
+            code
+        };
+
+        let tree = Tree::new::<T>(&code)?;
+
+        Some(Self {
+            code,
+            tree,
+            phantom: PhantomData,
+        })
+    }
+
+    // Builds a parser from a tree already parsed from `code`
+    pub(crate) fn from_tree(code: Vec<u8>, tree: Tree) -> Self {
+        Self {
+            code,
+            tree,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -2,9 +2,10 @@ use std::{collections::HashMap, path::Path, sync::Arc};
 
 use crate::traits::{LanguageInfo, ParserTrait};
 use crate::{
-    abc::Abc, alterator::Alterator, checker::Checker, cognitive::Cognitive, cyclomatic::Cyclomatic,
-    exit::Exit, getter::Getter, halstead::Halstead, langs::*, loc::Loc, mi::Mi, nargs::NArgs,
-    nom::Nom, npa::Npa, npm::Npm, preproc::PreprocResults, wmc::Wmc,
+    abc::Abc, alterator::Alterator, boolean_ops::BooleanOps, checker::Checker,
+    cognitive::Cognitive, cyclomatic::Cyclomatic, exit::Exit, getter::Getter, halstead::Halstead,
+    jsx::Jsx, langs::*, loc::Loc, locals::Locals, mi::Mi, nargs::NArgs, nom::Nom, npa::Npa,
+    npm::Npm, preproc::PreprocResults, template::Template, unsafety::Unsafety, wmc::Wmc,
 };
 
 /// A registry for managing parsers for different programming languages.
@@ -48,16 +49,21 @@ impl ParserRegistry {
             + Checker
             + Getter
             + Abc
+            + BooleanOps
+            + Locals
             + Cognitive
             + Cyclomatic
             + Exit
             + Halstead
+            + Jsx
             + Loc
             + Mi
             + NArgs
             + Nom
             + Npa
             + Npm
+            + Template
+            + Unsafety
             + Wmc,
     {
         self.parsers.insert(language, factory);
@@ -124,6 +130,9 @@ impl ParserRegistry {
         self.register_parser::<LuaCode>(LANG::Lua);
         self.register_parser::<GoCode>(LANG::Go);
         self.register_parser::<CsharpCode>(LANG::Csharp);
+        self.register_parser::<RCode>(LANG::R);
+        self.register_parser::<PerlCode>(LANG::Perl);
+        self.register_parser::<GraphqlCode>(LANG::Graphql);
     }
 
     /// Helper method to register a built-in parser.
@@ -137,16 +146,21 @@ impl ParserRegistry {
             + Checker
             + Getter
             + Abc
+            + BooleanOps
+            + Locals
             + Cognitive
             + Cyclomatic
             + Exit
             + Halstead
+            + Jsx
             + Loc
             + Mi
             + NArgs
             + Nom
             + Npa
             + Npm
+            + Template
+            + Unsafety
             + Wmc,
     {
         let factory = Box::new(BuiltinParserFactory::<T>::new());
@@ -195,16 +209,21 @@ impl<
             + Checker
             + Getter
             + Abc
+            + BooleanOps
+            + Locals
             + Cognitive
             + Cyclomatic
             + Exit
             + Halstead
+            + Jsx
             + Loc
             + Mi
             + NArgs
             + Nom
             + Npa
             + Npm
+            + Template
+            + Unsafety
             + Wmc
             + Send
             + Sync,
@@ -224,7 +243,7 @@ impl<
         // This is a simplified implementation - in practice you'd need to
         // extract this from the language definitions
         match T::get_lang() {
-            LANG::Javascript => vec!["js", "mjs", "jsx"],
+            LANG::Javascript => vec!["js", "mjs", "cjs", "jsx"],
             LANG::Java => vec!["java"],
             LANG::Rust => vec!["rs"],
             LANG::Cpp => vec![
@@ -232,7 +251,7 @@ impl<
             ],
             LANG::Python => vec!["py"],
             LANG::Tsx => vec!["tsx"],
-            LANG::Typescript => vec!["ts", "jsw", "jsmw"],
+            LANG::Typescript => vec!["ts", "mts", "cts", "jsw", "jsmw"],
             LANG::Elixir => vec!["ex", "exs"],
             LANG::Erlang => vec!["erl", "hrl"],
             LANG::Gleam => vec!["gleam"],
@@ -240,6 +259,9 @@ impl<
             LANG::Go => vec!["go"],
             LANG::Csharp => vec!["cs", "csx"],
             LANG::Kotlin => vec!["kt", "kts"],
+            LANG::R => vec!["r", "R"],
+            LANG::Perl => vec!["pl", "pm"],
+            LANG::Graphql => vec!["graphql", "gql"],
             // C not yet fully implemented
         }
     }
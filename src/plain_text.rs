@@ -0,0 +1,142 @@
+//! Line metrics of the files written in a language without a parser.
+//!
+//! Such files are not parsed: their lines are only told apart into code,
+//! comment and blank lines with a heuristic recognizing the `#` and `//`
+//! line comments and the `/* */` block comments, which covers most of the
+//! configuration and scripting languages. Other comment delimiters can be
+//! added with a [`CommentSyntax`].
+
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{CodeMetrics, FuncSpace, SpaceKind};
+
+/// The comment delimiters of a language without a parser, recognized in
+/// addition to the `#` and `//` line comments and the `/* */` block
+/// comments.
+///
+/// Block comments are looked for before line comments, so an opening
+/// delimiter such as `--[[` is not mistaken for a line comment starting
+/// with `--`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentSyntax {
+    /// The prefixes of the comments ending with their line, e.g. `;`
+    pub line: Vec<String>,
+    /// The opening and closing delimiters of the block comments, e.g. `(*`
+    /// and `*)`
+    pub block: Vec<(String, String)>,
+}
+
+/// Returns the space of a code written in a language without a parser.
+///
+/// Only the `SLOC`, `PLOC`, `CLOC` and `BLANK` metrics are computed, the
+/// other metrics keep their default values, and the returned space has no
+/// subspaces.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::plain_text_space;
+///
+/// let space = plain_text_space(b"# Settings\nname = foo\n\nsize = 3\n", Path::new("foo.cfg"));
+/// assert_eq!(space.metrics.loc.sloc(), 4.);
+/// assert_eq!(space.metrics.loc.cloc(), 1.);
+/// assert_eq!(space.metrics.loc.blank(), 1.);
+/// ```
+#[must_use]
+pub fn plain_text_space(code: &[u8], path: &Path) -> FuncSpace {
+    plain_text_space_with_syntax(code, path, &CommentSyntax::default())
+}
+
+/// Returns the space of a code written in a language without a parser,
+/// recognizing the comment delimiters of the given [`CommentSyntax`] too.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{plain_text_space_with_syntax, CommentSyntax};
+///
+/// let syntax = CommentSyntax {
+///     line: vec![";".to_string()],
+///     ..CommentSyntax::default()
+/// };
+/// let code = b"; Settings\nname = foo ; inline\n";
+/// let space = plain_text_space_with_syntax(code, Path::new("foo.ini"), &syntax);
+/// assert_eq!(space.metrics.loc.cloc(), 2.);
+/// ```
+#[must_use]
+pub fn plain_text_space_with_syntax(
+    code: &[u8],
+    path: &Path,
+    syntax: &CommentSyntax,
+) -> FuncSpace {
+    let mut metrics = CodeMetrics::default();
+    metrics.loc.compute_plain_text(code, syntax);
+    metrics.compute_minmax();
+    let lines = code
+        .strip_suffix(b"\n")
+        .unwrap_or(code)
+        .split(|byte| *byte == b'\n')
+        .count();
+
+    FuncSpace {
+        name: path.to_str().map(ToString::to_string),
+        start_line: 1,
+        end_line: lines,
+        kind: SpaceKind::Unit,
+        spaces: Vec::new(),
+        metrics,
+        supertypes: None,
+        signature: None,
+        decorators: Vec::new(),
+        content_hash: None,
+        custom: BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_and_line_comments() {
+        let code = "/* Header\n\n   end */\nint a; // one\n// two\nb /* three */ c\n";
+        let space = plain_text_space(code.as_bytes(), Path::new("foo.txt"));
+
+        let loc = &space.metrics.loc;
+        assert_eq!(space.end_line, 6);
+        assert_eq!(loc.sloc(), 6.);
+        assert_eq!(loc.ploc(), 2.);
+        assert_eq!(loc.cloc(), 6.);
+        assert_eq!(loc.blank(), 0.);
+        assert_eq!(loc.lloc(), 0.);
+    }
+
+    #[test]
+    fn custom_comment_syntax() {
+        let code = "(* Header\n   end *) x := 1;\n-- one\n--[[ two\n]] y := 2;\n";
+        let syntax = CommentSyntax {
+            line: vec!["--".to_string()],
+            block: vec![
+                ("(*".to_string(), "*)".to_string()),
+                ("--[[".to_string(), "]]".to_string()),
+            ],
+        };
+
+        let loc = plain_text_space_with_syntax(code.as_bytes(), Path::new("foo.txt"), &syntax)
+            .metrics
+            .loc;
+        assert_eq!(loc.sloc(), 5.);
+        assert_eq!(loc.ploc(), 2.);
+        assert_eq!(loc.cloc(), 5.);
+
+        // Without the syntax, the comments are code
+        let loc = plain_text_space(code.as_bytes(), Path::new("foo.txt"))
+            .metrics
+            .loc;
+        assert_eq!(loc.cloc(), 0.);
+    }
+}
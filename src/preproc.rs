@@ -30,6 +30,13 @@ pub struct PreprocFile {
 pub struct PreprocResults {
     /// The preprocessor data of each `C/C++` file
     pub files: HashMap<PathBuf, PreprocFile>,
+    /// Macros defined before the analysis, with their values.
+    ///
+    /// When not empty, the branches of the `#if`/`#ifdef`/`#ifndef` groups
+    /// which are not compiled with these macros are removed before
+    /// computing the metrics.
+    #[serde(default)]
+    pub defines: HashMap<String, String>,
 }
 
 impl PreprocFile {
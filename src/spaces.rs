@@ -1,11 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt,
+    marker::PhantomData,
     path::{Path, PathBuf},
     string::ToString,
 };
 
-use serde::Serialize;
+use serde::{ser::Serializer, Deserialize, Serialize};
 
 #[inline]
 fn f64_to_usize(value: f64) -> usize {
@@ -17,27 +18,35 @@ fn f64_to_usize(value: f64) -> usize {
 
 use crate::{
     abc::{self, Abc},
+    boolean_ops::{self, BooleanOps},
     checker::Checker,
-    cognitive::{self, Cognitive},
-    cyclomatic::{self, Cyclomatic},
+    cognitive::{self, Cognitive, CognitiveConfig},
+    custom::CustomMetric,
+    cyclomatic::{self, Cyclomatic, CyclomaticConfig},
     dump_metrics::dump_root,
     enter_code_context,
     exit::{self, Exit},
+    float_precision::serialize_custom,
     getter::Getter,
     halstead::{self, Halstead, HalsteadMaps},
+    jsx::{self, Jsx},
     loc::{self, Loc},
+    locals::{self, Locals},
     mi::{self, Mi},
     nargs::{self, NArgs},
-    node::Node,
+    node::{Cursor, Node},
     nom::{self, Nom},
     npa::{self, Npa},
     npm::{self, Npm},
+    template::{self, Template},
+    tools::fnv1a,
     traits::{Callback, ParserTrait},
+    unsafety::{self, Unsafety},
     wmc::{self, Wmc},
 };
 
 /// The list of supported space kinds.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SpaceKind {
     /// An unknown space
@@ -59,6 +68,8 @@ pub enum SpaceKind {
     Namespace,
     /// An interface
     Interface,
+    /// A `Rust` macro definition
+    Macro,
 }
 
 impl fmt::Display for SpaceKind {
@@ -73,6 +84,7 @@ impl fmt::Display for SpaceKind {
             SpaceKind::Unit => "unit",
             SpaceKind::Namespace => "namespace",
             SpaceKind::Interface => "interface",
+            SpaceKind::Macro => "macro",
         };
         write!(f, "{s}")
     }
@@ -85,6 +97,8 @@ pub struct CodeMetrics {
     pub nargs: nargs::Stats,
     /// `NExits` data
     pub nexits: exit::Stats,
+    /// Kinds of the exit points
+    pub exit_kinds: exit::ExitKinds,
     pub cognitive: cognitive::Stats,
     /// `Cyclomatic` data
     pub cyclomatic: cyclomatic::Stats,
@@ -98,6 +112,10 @@ pub struct CodeMetrics {
     pub mi: mi::Stats,
     /// `Abc` data
     pub abc: abc::Stats,
+    /// `BooleanOps` data
+    pub boolean_ops: boolean_ops::Stats,
+    /// `Locals` data
+    pub locals: locals::Stats,
     /// `Wmc` data
     #[serde(skip_serializing_if = "wmc::Stats::is_disabled")]
     pub wmc: wmc::Stats,
@@ -107,6 +125,15 @@ pub struct CodeMetrics {
     /// `Npa` data
     #[serde(skip_serializing_if = "npa::Stats::is_disabled")]
     pub npa: npa::Stats,
+    /// `Unsafe` data
+    #[serde(skip_serializing_if = "unsafety::Stats::is_disabled")]
+    pub unsafe_count: unsafety::Stats,
+    /// `Jsx` data
+    #[serde(skip_serializing_if = "jsx::Stats::is_disabled")]
+    pub jsx: jsx::Stats,
+    /// `Template` data
+    #[serde(skip_serializing_if = "template::Stats::is_disabled")]
+    pub template: template::Stats,
 }
 
 impl fmt::Display for CodeMetrics {
@@ -132,14 +159,104 @@ impl CodeMetrics {
         self.mi.merge(&other.mi);
         self.nargs.merge(&other.nargs);
         self.nexits.merge(&other.nexits);
+        self.exit_kinds.merge(&other.exit_kinds);
         self.abc.merge(&other.abc);
+        self.boolean_ops.merge(&other.boolean_ops);
+        self.locals.merge(&other.locals);
         self.wmc.merge(&other.wmc);
         self.npm.merge(&other.npm);
         self.npa.merge(&other.npa);
+        self.unsafe_count.merge(&other.unsafe_count);
+        self.jsx.merge(&other.jsx);
+        self.template.merge(&other.template);
+    }
+
+    // Computes the minimum and maximum values of the metrics
+    pub(crate) fn compute_minmax(&mut self) {
+        self.cyclomatic.compute_minmax();
+        self.nexits.compute_minmax();
+        self.cognitive.compute_minmax();
+        self.nargs.compute_minmax();
+        self.nom.compute_minmax();
+        self.loc.compute_minmax();
+        self.abc.compute_minmax();
+    }
+
+    // Computes the averages of the metrics over the functions counted
+    // by the `Nom` metric, `is_unit` telling whether the metrics are the
+    // ones of a unit space
+    pub(crate) fn compute_averages(
+        &mut self,
+        count_anonymous: bool,
+        averages: AverageSpaces,
+        is_unit: bool,
+    ) {
+        let nom_functions = f64_to_usize(self.nom.functions_sum());
+        let nom_closures = f64_to_usize(self.nom.closures_sum());
+        let nom_total = if count_anonymous {
+            f64_to_usize(self.nom.total())
+        } else {
+            nom_functions
+        };
+        let spaces = match averages {
+            AverageSpaces::PerMetric => None,
+            // A code without functions is averaged over a single space
+            AverageSpaces::Functions => Some(nom_total.max(1)),
+            AverageSpaces::FunctionsAndUnit => Some(nom_total + usize::from(is_unit)),
+        };
+        // Cognitive average
+        self.cognitive.finalize(spaces.unwrap_or(nom_total));
+        // Nexit average
+        self.nexits.finalize(spaces.unwrap_or(nom_total));
+        // Nargs average
+        self.nargs.finalize(nom_functions, nom_closures);
+        // The averages over all the spaces
+        if let Some(spaces) = spaces {
+            self.cyclomatic.set_space_count(spaces);
+            self.abc.set_space_count(spaces);
+            self.loc.set_space_count(spaces);
+        }
+    }
+}
+
+/// The supertypes declared by a class or an interface.
+///
+/// Supertypes are recorded by name, as written in the declaration, and are
+/// not resolved across files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Supertypes {
+    /// The extended classes, or the extended interfaces of an interface
+    pub extends: Vec<String>,
+    /// The implemented interfaces
+    pub implements: Vec<String>,
+    /// The depth of inheritance, following the extended classes declared
+    /// in the same code, where a class declared elsewhere is one level
+    pub dit_declared: usize,
+    /// The number of distinct declared supertypes
+    pub cbo_declared: usize,
+}
+
+impl Supertypes {
+    pub(crate) fn new(extends: Vec<String>, implements: Vec<String>) -> Self {
+        let cbo_declared = extends
+            .iter()
+            .chain(&implements)
+            .collect::<BTreeSet<_>>()
+            .len();
+        Self {
+            dit_declared: 0,
+            cbo_declared,
+            extends,
+            implements,
+        }
     }
 }
 
 /// Function space data.
+///
+/// A space owns all its data, i.e. its name, its span in lines and its
+/// metrics, and holds no reference to the analyzed code nor to its syntax
+/// tree, so it can be kept after the code and the parser are dropped.
 #[derive(Debug, Clone, Serialize)]
 pub struct FuncSpace {
     /// The name of a function space
@@ -157,6 +274,30 @@ pub struct FuncSpace {
     pub spaces: Vec<FuncSpace>,
     /// All metrics of a function space
     pub metrics: CodeMetrics,
+    /// The supertypes declared by a class or an interface space
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supertypes: Option<Supertypes>,
+    /// The signature of a named function space
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The decorators of a function or a class space
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub decorators: Vec<String>,
+    /// The hash of the source of the space, only computed with
+    /// [`MetricsOptions::content_hash`]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_content_hash"
+    )]
+    pub content_hash: Option<u64>,
+    /// The results of the custom metrics, keyed by metric name
+    ///
+    /// Only the root space of a code has custom metrics.
+    #[serde(
+        skip_serializing_if = "BTreeMap::is_empty",
+        serialize_with = "serialize_custom"
+    )]
+    pub custom: BTreeMap<String, serde_json::Value>,
 }
 
 impl FuncSpace {
@@ -177,11 +318,127 @@ impl FuncSpace {
                 .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" ")),
             spaces: Vec::new(),
             metrics: CodeMetrics::default(),
+            supertypes: T::get_supertypes(node, code),
+            signature: T::get_signature(node, code),
+            decorators: T::get_decorators(node, code),
+            content_hash: None,
+            custom: BTreeMap::new(),
             kind,
             start_line: start_position,
             end_line: end_position,
         }
     }
+
+    /// Returns the signature of a named function space, i.e. its name
+    /// followed by its parameters and by its return type when it is
+    /// declared, e.g. `f(a: i32) -> bool` for a `Rust` function.
+    ///
+    /// Returns `None` for the other spaces, for the anonymous functions and
+    /// for the functions of the languages without parameters in their
+    /// syntax tree.
+    #[must_use]
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
+    /// Returns the decorators of a function or a class space, in order, as
+    /// written without their `@`, e.g. `staticmethod` for a `Python` static
+    /// method.
+    #[must_use]
+    pub fn decorators(&self) -> &[String] {
+        &self.decorators
+    }
+
+    /// Returns the hash of the source of the space, from its first to its
+    /// last byte, or `None` when it is not computed, see
+    /// [`MetricsOptions::content_hash`].
+    ///
+    /// The hash is stable across runs and platforms, so it can be stored to
+    /// skip the spaces which did not change since a previous analysis. It
+    /// only depends on the source of the space, not on its position in the
+    /// file.
+    #[must_use]
+    pub fn content_hash(&self) -> Option<u64> {
+        self.content_hash
+    }
+
+    /// Returns the length of the space in physical lines, from its first to
+    /// its last line, blank and comment lines included.
+    #[must_use]
+    pub fn length(&self) -> usize {
+        (self.end_line + 1).saturating_sub(self.start_line)
+    }
+
+    /// Returns the length in physical lines of the longest function space
+    /// inside this space, itself included, or `0` when there is none.
+    #[must_use]
+    pub fn function_length_max(&self) -> usize {
+        f64_to_usize(self.metrics.loc.function_length_max())
+    }
+}
+
+/// Returns the innermost space covering a line, starting from `1`, among
+/// `space` and its subspaces, or `None` when the line is outside `space`.
+///
+/// When several nested spaces cover the line, e.g. a closure and the
+/// function containing it, the deepest one is returned. When sibling spaces
+/// share the line, the first of the deepest ones is returned.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{metrics, space_for_line, ParserTrait, PythonParser};
+///
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(b"x = 1\n\ndef f():\n    return x\n".to_vec(), &path, None);
+/// let space = metrics(&parser, &path).unwrap();
+///
+/// assert_eq!(space_for_line(&space, 4).unwrap().name.as_deref(), Some("f"));
+/// assert_eq!(space_for_line(&space, 1).unwrap().name.as_deref(), Some("foo.py"));
+/// assert!(space_for_line(&space, 5).is_none());
+/// ```
+#[must_use]
+pub fn space_for_line(space: &FuncSpace, line: usize) -> Option<&FuncSpace> {
+    covers_line(space, line).then(|| deepest_space(space, line).1)
+}
+
+fn covers_line(space: &FuncSpace, line: usize) -> bool {
+    (space.start_line..=space.end_line).contains(&line)
+}
+
+// Returns the deepest subspace covering a line, with its depth below
+// `space`, which covers the line
+fn deepest_space(space: &FuncSpace, line: usize) -> (usize, &FuncSpace) {
+    space
+        .spaces
+        .iter()
+        .filter(|subspace| covers_line(subspace, line))
+        .map(|subspace| {
+            let (depth, deepest) = deepest_space(subspace, line);
+            (depth + 1, deepest)
+        })
+        .fold((0, space), |best, candidate| {
+            if candidate.0 > best.0 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+// Hashes are serialized as hexadecimal strings, since most `JSON` parsers
+// do not read integers beyond 2^53 exactly
+#[allow(clippy::ref_option)]
+fn serialize_content_hash<S>(hash: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match hash {
+        Some(hash) => serializer.serialize_str(&format!("{hash:016x}")),
+        None => serializer.serialize_none(),
+    }
 }
 
 #[inline]
@@ -203,31 +460,17 @@ fn compute_halstead_mi_and_wmc<T: ParserTrait>(state: &mut State) {
 }
 
 #[inline]
-fn compute_averages(state: &mut State) {
-    let nom_functions = f64_to_usize(state.space.metrics.nom.functions_sum());
-    let nom_closures = f64_to_usize(state.space.metrics.nom.closures_sum());
-    let nom_total = f64_to_usize(state.space.metrics.nom.total());
-    // Cognitive average
-    state.space.metrics.cognitive.finalize(nom_total);
-    // Nexit average
-    state.space.metrics.nexits.finalize(nom_total);
-    // Nargs average
+fn compute_averages(state: &mut State, options: &MetricsOptions) {
+    let is_unit = state.space.kind == SpaceKind::Unit;
     state
         .space
         .metrics
-        .nargs
-        .finalize(nom_functions, nom_closures);
+        .compute_averages(options.count_anonymous, options.averages, is_unit);
 }
 
 #[inline]
 fn compute_minmax(state: &mut State) {
-    state.space.metrics.cyclomatic.compute_minmax();
-    state.space.metrics.nexits.compute_minmax();
-    state.space.metrics.cognitive.compute_minmax();
-    state.space.metrics.nargs.compute_minmax();
-    state.space.metrics.nom.compute_minmax();
-    state.space.metrics.loc.compute_minmax();
-    state.space.metrics.abc.compute_minmax();
+    state.space.metrics.compute_minmax();
 }
 
 #[inline]
@@ -235,9 +478,18 @@ fn compute_sum(state: &mut State) {
     state.space.metrics.wmc.compute_sum();
     state.space.metrics.npm.compute_sum();
     state.space.metrics.npa.compute_sum();
+    state.space.metrics.boolean_ops.compute_sum();
+    state.space.metrics.locals.compute_sum();
+    state.space.metrics.unsafe_count.compute_sum();
+    state.space.metrics.jsx.compute_sum();
+    state.space.metrics.template.compute_sum();
 }
 
-fn finalize<T: ParserTrait>(state_stack: &mut Vec<State>, diff_level: usize) {
+fn finalize<T: ParserTrait>(
+    state_stack: &mut Vec<State>,
+    diff_level: usize,
+    options: &MetricsOptions,
+) {
     if state_stack.is_empty() {
         return;
     }
@@ -247,14 +499,14 @@ fn finalize<T: ParserTrait>(state_stack: &mut Vec<State>, diff_level: usize) {
             compute_minmax(last_state);
             compute_sum(last_state);
             compute_halstead_mi_and_wmc::<T>(last_state);
-            compute_averages(last_state);
+            compute_averages(last_state, options);
             break;
         }
         let mut state = state_stack.pop().expect("TODO: Add context for why this shouldn't fail");
         compute_minmax(&mut state);
         compute_sum(&mut state);
         compute_halstead_mi_and_wmc::<T>(&mut state);
-        compute_averages(&mut state);
+        compute_averages(&mut state, options);
 
         let last_state = state_stack.last_mut().expect("TODO: Add context for why this shouldn't fail");
         last_state.halstead_maps.merge(&state.halstead_maps);
@@ -272,6 +524,132 @@ struct State<'a> {
     halstead_maps: HalsteadMaps<'a>,
 }
 
+/// Options tuning how the function spaces of a code are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsOptions {
+    /// Whether closures and lambdas create their own function spaces.
+    ///
+    /// When `false`, their metrics roll up to the enclosing space and
+    /// averages are computed over named functions only.
+    pub count_anonymous: bool,
+    /// Whether the arguments of well-known `Rust` macros, such as
+    /// `matches!` or `assert!`, count toward the `Cognitive Complexity`.
+    ///
+    /// Macro arguments are token trees and not parsed code, so this is a
+    /// heuristic: the `if`, `&&` and `||` tokens are counted as if they
+    /// were written outside of the macro.
+    pub rust_macro_bodies: bool,
+    /// Configuration of the `Cyclomatic` metric.
+    pub cyclomatic: CyclomaticConfig,
+    /// Configuration of the `Cognitive Complexity` metric.
+    pub cognitive: CognitiveConfig,
+    /// Whether the lines of top-level imports, such as `Python` `import`
+    /// and `from` statements or `Rust` `use` declarations, are left out
+    /// of the `SLOC`, `PLOC` and `LLOC` metrics.
+    pub exclude_imports: bool,
+    /// The increment of the `Cognitive Complexity` for the `Go` error
+    /// checks, such as `if err != nil { return err }`.
+    ///
+    /// An error check is an `if` statement comparing an identifier to `nil`,
+    /// without an `else` branch and whose body is a single `return`. When
+    /// `None`, error checks are counted as any other `if` statement,
+    /// otherwise they add this flat increment, regardless of their nesting.
+    pub go_error_check_increment: Option<usize>,
+    /// The increment of the `Cognitive Complexity` for each `Rust` `?`
+    /// operator.
+    ///
+    /// Error propagation adds this flat increment, regardless of its
+    /// nesting. When `0`, the `?` operators are ignored.
+    pub rust_try_increment: usize,
+    /// The heuristics recognizing test code, which is skipped when set.
+    ///
+    /// Skipped code creates no spaces and counts toward no metric, except
+    /// the `SLOC` of an enclosing space spanning it.
+    pub exclude_tests: Option<TestFilter>,
+    /// Whether each space gets the hash of its source, see
+    /// [`FuncSpace::content_hash`].
+    pub content_hash: bool,
+    /// The spaces over which the averages of the metrics are computed.
+    ///
+    /// The `NArgs` averages are always computed over the functions and the
+    /// closures, as the other spaces have no arguments.
+    pub averages: AverageSpaces,
+}
+
+/// The spaces over which the averages of the metrics are computed, see
+/// [`MetricsOptions::averages`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AverageSpaces {
+    /// Each metric keeps its own denominator: the `Cyclomatic`, `ABC` and
+    /// `LOC` averages are computed over all the spaces, including the unit
+    /// and the class spaces, while the `Cognitive` and `NExits` averages
+    /// are computed over the functions counted by the `Nom` metric.
+    #[default]
+    PerMetric,
+    /// All the averages are computed over the functions counted by the
+    /// `Nom` metric. For a code without functions, they are computed over a
+    /// single space, so they are equal to the sums.
+    Functions,
+    /// All the averages are computed over the functions counted by the
+    /// `Nom` metric and the unit space, as if the top level of a code were
+    /// a function.
+    FunctionsAndUnit,
+}
+
+/// The heuristics recognizing test code, see
+/// [`MetricsOptions::exclude_tests`].
+///
+/// The default heuristics skip:
+/// - the `Rust` items with a `#[test]`, `#[tokio::test]` or `#[cfg(test)]`
+///   attribute, e.g. a `mod tests`;
+/// - the `Python` functions named `test_*` and the classes named `Test*`,
+///   only in the files named `test_*`;
+/// - the `JavaScript` and `TypeScript` `describe`, `it` and `test` calls,
+///   along with their variants such as `it.only`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestFilter {
+    /// The `Rust` attributes marking a test item, written without `#[` and
+    /// `]` nor whitespaces.
+    pub rust_attributes: &'static [&'static str],
+    /// The prefixes of the names of the `Python` test functions.
+    pub python_functions: &'static [&'static str],
+    /// The prefixes of the names of the `Python` test classes.
+    pub python_classes: &'static [&'static str],
+    /// The prefixes of the names of the `Python` test files.
+    pub python_files: &'static [&'static str],
+    /// The `JavaScript` and `TypeScript` functions declaring tests.
+    pub js_functions: &'static [&'static str],
+}
+
+impl Default for TestFilter {
+    fn default() -> Self {
+        Self {
+            rust_attributes: &["test", "tokio::test", "cfg(test)"],
+            python_functions: &["test_"],
+            python_classes: &["Test"],
+            python_files: &["test_"],
+            js_functions: &["describe", "it", "test"],
+        }
+    }
+}
+
+impl Default for MetricsOptions {
+    fn default() -> Self {
+        Self {
+            count_anonymous: true,
+            rust_macro_bodies: false,
+            cyclomatic: CyclomaticConfig::default(),
+            cognitive: CognitiveConfig::default(),
+            exclude_imports: false,
+            go_error_check_increment: None,
+            rust_try_increment: 0,
+            exclude_tests: None,
+            content_hash: false,
+            averages: AverageSpaces::default(),
+        }
+    }
+}
+
 /// Returns all function spaces data of a code. This function needs a parser to
 /// be created a priori in order to work.
 ///
@@ -294,79 +672,315 @@ struct State<'a> {
 /// // Gets all function spaces data of the code contained in foo.c
 /// metrics(&parser, &path).unwrap();
 /// ```
-pub fn metrics<'a, T: ParserTrait>(parser: &'a T, path: &'a Path) -> Option<FuncSpace> {
-    let code = parser.get_code();
-    let _code_guard = enter_code_context(code);
-    let node = parser.get_root();
-
-    let mut cursor = node.cursor();
-    let mut stack = Vec::new();
-    let mut children = Vec::new();
-    let mut state_stack: Vec<State> = Vec::new();
-    let mut last_level = 0;
-    // Initialize nesting_map used for storing nesting information for cognitive
+pub fn metrics<T: ParserTrait>(parser: &T, path: &Path) -> Option<FuncSpace> {
+    metrics_with_options(parser, path, &MetricsOptions::default())
+}
+
+/// Returns all function spaces data of a code, computed according
+/// to the given [`MetricsOptions`].
+pub fn metrics_with_options<T: ParserTrait>(
+    parser: &T,
+    path: &Path,
+    options: &MetricsOptions,
+) -> Option<FuncSpace> {
+    metrics_with_custom(parser, path, options, Vec::new())
+}
+
+/// Returns all function spaces data of a code, computed according
+/// to the given [`MetricsOptions`], along with the given custom metrics.
+///
+/// The results of the custom metrics are stored in the
+/// [`custom`](FuncSpace::custom) map of the returned root space.
+///
+/// The code is visited in a single traversal, and the metrics of each space
+/// are finalized as soon as its last node is visited, so only the spaces
+/// enclosing the current node keep temporary data borrowed from the code.
+/// The returned space does not borrow from `parser` nor from `path`.
+pub fn metrics_with_custom<T: ParserTrait>(
+    parser: &T,
+    path: &Path,
+    options: &MetricsOptions,
+    custom: Vec<Box<dyn CustomMetric>>,
+) -> Option<FuncSpace> {
+    let mut walker = SpaceWalker::new(parser, options);
+    walker.custom = custom;
+    walker.test_file = options.exclude_tests.is_some_and(|filter| {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        filter.python_files.iter().any(|prefix| name.starts_with(prefix))
+    });
+    let _code_guard = enter_code_context(walker.code);
+    while walker.step() {}
+
+    walker.state_stack.pop().map(|mut state| {
+        compute_declared_dit(&mut state.space);
+        state.space.name = path.to_str().map(ToString::to_string);
+        state.space.custom = std::mem::take(&mut walker.custom)
+            .into_iter()
+            .map(|metric| (metric.name().to_string(), metric.finalize()))
+            .collect();
+        state.space
+    })
+}
+
+// Computes the declared depth of inheritance of the classes in `root`,
+// following the first extended class of each one among the classes
+// declared in the same code
+fn compute_declared_dit(root: &mut FuncSpace) {
+    let mut parents = HashMap::new();
+    let mut stack = vec![&*root];
+    while let Some(space) = stack.pop() {
+        if let (Some(name), Some(supertypes)) = (&space.name, &space.supertypes) {
+            parents.insert(name.clone(), supertypes.extends.first().cloned());
+        }
+        stack.extend(&space.spaces);
+    }
+
+    let mut stack = vec![root];
+    while let Some(space) = stack.pop() {
+        if let Some(supertypes) = &mut space.supertypes {
+            let mut depth = 0;
+            let mut seen = HashSet::new();
+            let mut parent = supertypes.extends.first();
+            // A cycle of declarations stops the count at its first repetition
+            while let Some(name) = parent
+                && seen.insert(name)
+            {
+                depth += 1;
+                // Type arguments are not part of the declared name
+                let base = name.split('<').next().unwrap_or_default().trim();
+                parent = parents.get(base).and_then(Option::as_ref);
+            }
+            supertypes.dit_declared = depth;
+        }
+        stack.extend(&mut space.spaces);
+    }
+}
+
+// Visits the nodes of a code one at a time, computing the metrics of
+// each function space as soon as all its nodes have been visited.
+struct SpaceWalker<'a, T: ParserTrait> {
+    code: &'a [u8],
+    cursor: Cursor<'a>,
+    stack: Vec<(Node<'a>, usize)>,
+    children: Vec<(Node<'a>, usize)>,
+    state_stack: Vec<State<'a>>,
+    last_level: usize,
+    // Nesting information used by cognitive
     // Three type of nesting info: conditionals, functions and lambdas
-    let mut nesting_map = HashMap::<usize, (usize, usize, usize)>::default();
-    nesting_map.insert(node.id(), (0, 0, 0));
-    stack.push((node, 0));
-
-    while let Some((node, level)) = stack.pop() {
-        if level < last_level {
-            finalize::<T>(&mut state_stack, last_level - level);
-            last_level = level;
+    nesting_map: HashMap<usize, (usize, usize, usize)>,
+    options: MetricsOptions,
+    custom: Vec<Box<dyn CustomMetric>>,
+    // Whether the file name marks a test file
+    test_file: bool,
+    is_finished: bool,
+    parser: PhantomData<T>,
+}
+
+impl<'a, T: ParserTrait> SpaceWalker<'a, T> {
+    fn new(parser: &'a T, options: &MetricsOptions) -> Self {
+        let node = parser.get_root();
+        let mut nesting_map = HashMap::<usize, (usize, usize, usize)>::default();
+        nesting_map.insert(node.id(), (0, 0, 0));
+
+        Self {
+            code: parser.get_code(),
+            cursor: node.cursor(),
+            stack: vec![(node, 0)],
+            children: Vec::new(),
+            state_stack: Vec::new(),
+            last_level: 0,
+            nesting_map,
+            options: *options,
+            custom: Vec::new(),
+            test_file: false,
+            is_finished: false,
+            parser: PhantomData,
+        }
+    }
+
+    // Visits the next node. Once all nodes have been visited, finalizes
+    // the remaining spaces and returns `false`.
+    fn step(&mut self) -> bool {
+        let Some((node, level)) = self.stack.pop() else {
+            if !self.is_finished {
+                finalize::<T>(&mut self.state_stack, usize::MAX, &self.options);
+                self.is_finished = true;
+            }
+            return false;
+        };
+
+        if level < self.last_level {
+            finalize::<T>(&mut self.state_stack, self.last_level - level, &self.options);
+            self.last_level = level;
+        }
+
+        // Test code is skipped along with all its descendants
+        if let Some(filter) = &self.options.exclude_tests
+            && T::Checker::is_test(&node, self.code, filter, self.test_file)
+        {
+            return true;
         }
 
         let kind = T::Getter::get_space_kind(&node);
 
-        let func_space = T::Checker::is_func(&node) || T::Checker::is_func_space(&node);
+        let func_space = (T::Checker::is_func(&node) || T::Checker::is_func_space(&node))
+            && (self.options.count_anonymous || !T::Checker::is_closure(&node));
         let unit = kind == SpaceKind::Unit;
 
         let new_level = if func_space {
-            let state = State {
-                space: FuncSpace::new::<T::Getter>(&node, code, kind),
+            let member = self.state_stack.last().is_some_and(|parent| {
+                matches!(
+                    parent.space.kind,
+                    SpaceKind::Class
+                        | SpaceKind::Struct
+                        | SpaceKind::Interface
+                        | SpaceKind::Trait
+                        | SpaceKind::Impl
+                )
+            });
+            let mut state = State {
+                space: FuncSpace::new::<T::Getter>(&node, self.code, kind),
                 halstead_maps: HalsteadMaps::new(),
             };
-            state_stack.push(state);
-            last_level = level + 1;
-            last_level
+            if self.options.content_hash {
+                let source = self.code.get(node.start_byte()..node.end_byte());
+                state.space.content_hash = source.map(fnv1a);
+            }
+            let length = state.space.length();
+            let metrics = &mut state.space.metrics;
+            metrics
+                .cognitive
+                .set_macro_bodies(self.options.rust_macro_bodies);
+            metrics
+                .cognitive
+                .set_go_error_check_increment(self.options.go_error_check_increment);
+            metrics
+                .cognitive
+                .set_rust_try_increment(self.options.rust_try_increment);
+            metrics.cognitive.set_config(self.options.cognitive);
+            metrics.cyclomatic.set_config(self.options.cyclomatic);
+            metrics.loc.set_exclude_imports(self.options.exclude_imports);
+            if kind == SpaceKind::Function {
+                metrics.loc.set_function_length(length);
+            }
+            metrics.nom.set_member(member);
+            self.state_stack.push(state);
+            self.last_level = level + 1;
+            self.last_level
         } else {
             level
         };
 
-        if let Some(state) = state_stack.last_mut() {
+        if let Some(state) = self.state_stack.last_mut() {
             let last = &mut state.space;
-            T::Cognitive::compute(&node, &mut last.metrics.cognitive, &mut nesting_map);
+            T::Cognitive::compute(&node, &mut last.metrics.cognitive, &mut self.nesting_map);
             T::Cyclomatic::compute(&node, &mut last.metrics.cyclomatic);
-            T::Halstead::compute(&node, code, &mut state.halstead_maps);
-            T::Loc::compute(&node, &mut last.metrics.loc, func_space, unit);
+            T::Halstead::compute(&node, self.code, &mut state.halstead_maps);
+            loc::compute_loc::<T::Loc>(&node, &mut last.metrics.loc, func_space, unit);
             T::Nom::compute(&node, &mut last.metrics.nom);
             T::NArgs::compute(&node, &mut last.metrics.nargs);
             T::Exit::compute(&node, &mut last.metrics.nexits);
+            T::Exit::compute_kinds(&node, &mut last.metrics.exit_kinds);
             T::Abc::compute(&node, &mut last.metrics.abc);
+            T::BooleanOps::compute(&node, &mut last.metrics.boolean_ops);
+            T::Locals::compute(&node, &mut last.metrics.locals);
             T::Npm::compute(&node, &mut last.metrics.npm);
             T::Npa::compute(&node, &mut last.metrics.npa);
+            T::Unsafety::compute(&node, &mut last.metrics.unsafe_count);
+            T::Jsx::compute(&node, &mut last.metrics.jsx);
+            T::Template::compute(&node, &mut last.metrics.template);
+        }
+        for metric in &mut self.custom {
+            metric.visit(&node, self.code);
         }
 
-        cursor.reset(&node);
-        if cursor.goto_first_child() {
+        self.cursor.reset(&node);
+        if self.cursor.goto_first_child() {
             loop {
-                children.push((cursor.node(), new_level));
-                if !cursor.goto_next_sibling() {
+                self.children.push((self.cursor.node(), new_level));
+                if !self.cursor.goto_next_sibling() {
                     break;
                 }
             }
-            for child in std::mem::take(&mut children).into_iter().rev() {
-                stack.push(child);
+            for child in std::mem::take(&mut self.children).into_iter().rev() {
+                self.stack.push(child);
             }
         }
+        true
     }
+}
 
-    finalize::<T>(&mut state_stack, usize::MAX);
+/// An iterator over the top-level function spaces of a code.
+///
+/// Each top-level space, e.g. a class or a function, is yielded with its
+/// metrics as soon as all its nodes have been visited, so the spaces of a
+/// large code can be consumed before the whole code has been analyzed.
+/// The yielded spaces are the same contained in the root space returned
+/// by [`metrics`].
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use singularity_code_analysis::{ParserTrait, PythonParser, SpaceIterator};
+///
+/// let source_code = "def f():\n    pass\n\ndef g():\n    pass\n";
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(source_code.as_bytes().to_vec(), &path, None);
+///
+/// for space in SpaceIterator::new(&parser) {
+///     println!("{:?}: {}", space.name, space.metrics.cyclomatic.cyclomatic());
+/// }
+/// ```
+pub struct SpaceIterator<'a, T: ParserTrait> {
+    walker: SpaceWalker<'a, T>,
+    yielded: usize,
+}
 
-    state_stack.pop().map(|mut state| {
-        state.space.name = path.to_str().map(ToString::to_string);
-        state.space
-    })
+impl<'a, T: ParserTrait> SpaceIterator<'a, T> {
+    /// Creates an iterator over the top-level function spaces of the
+    /// code contained in `parser`.
+    #[must_use]
+    pub fn new(parser: &'a T) -> Self {
+        Self::with_options(parser, &MetricsOptions::default())
+    }
+
+    /// Creates an iterator over the top-level function spaces of the
+    /// code contained in `parser`, computed according to `options`.
+    #[must_use]
+    pub fn with_options(parser: &'a T, options: &MetricsOptions) -> Self {
+        Self {
+            walker: SpaceWalker::new(parser, options),
+            yielded: 0,
+        }
+    }
+}
+
+impl<T: ParserTrait> Iterator for SpaceIterator<'_, T> {
+    type Item = FuncSpace;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The code context is entered at each step, so that several
+        // iterators can be interleaved on the same thread
+        let _code_guard = enter_code_context(self.walker.code);
+        loop {
+            // Completed top-level spaces are merged into the root space
+            let completed = self
+                .walker
+                .state_stack
+                .first()
+                .and_then(|root| root.space.spaces.get(self.yielded));
+            if let Some(space) = completed {
+                self.yielded += 1;
+                return Some(space.clone());
+            }
+            if self.walker.is_finished {
+                return None;
+            }
+            self.walker.step();
+        }
+    }
 }
 
 /// Configuration options for computing
@@ -395,7 +1009,145 @@ impl Callback for Metrics {
 
 #[cfg(test)]
 mod tests {
-    use crate::{check_func_space, CppParser};
+    use std::path::Path;
+
+    use super::{
+        metrics_with_options, space_for_line, AverageSpaces, MetricsOptions, SpaceIterator,
+        Supertypes,
+    };
+    use crate::{
+        check_func_space, metrics, CppParser, CsharpParser, FuncSpace, JavaParser,
+        ParserEngineRust, ParserTrait, PythonParser, SpaceKind, TypescriptParser,
+    };
+
+    #[test]
+    fn java_declared_supertypes() {
+        check_func_space::<JavaParser, _>(
+            "class A {}
+             class B extends A implements I1, I2 {}
+             class C extends B {}",
+            "foo.java",
+            |func_space| {
+                let supertypes: Vec<_> = func_space
+                    .spaces
+                    .iter()
+                    .map(|space| space.supertypes.clone().unwrap())
+                    .collect();
+                assert_eq!(
+                    supertypes,
+                    [
+                        Supertypes::default(),
+                        Supertypes {
+                            extends: vec!["A".to_string()],
+                            implements: vec!["I1".to_string(), "I2".to_string()],
+                            dit_declared: 1,
+                            cbo_declared: 3,
+                        },
+                        Supertypes {
+                            extends: vec!["B".to_string()],
+                            implements: Vec::new(),
+                            dit_declared: 2,
+                            cbo_declared: 1,
+                        },
+                    ]
+                );
+                assert!(func_space.supertypes.is_none());
+            },
+        );
+    }
+
+    #[test]
+    fn rust_signature() {
+        check_func_space::<ParserEngineRust, _>(
+            "fn f(a: i32) -> bool {
+                 let g = |b: i32| b > 0;
+                 g(a)
+             }",
+            "foo.rs",
+            |func_space| {
+                assert_eq!(func_space.signature(), None);
+                let function = &func_space.spaces[0];
+                assert_eq!(function.signature(), Some("f(a: i32) -> bool"));
+                // Closures have no name, so no signature
+                assert_eq!(function.spaces[0].signature(), None);
+            },
+        );
+    }
+
+    #[test]
+    fn typescript_signature() {
+        check_func_space::<TypescriptParser, _>(
+            "function f(a: number): boolean {
+                 return a > 0;
+             }
+             function g(a,
+                        b) {}",
+            "foo.ts",
+            |func_space| {
+                let signatures: Vec<_> = func_space
+                    .spaces
+                    .iter()
+                    .map(FuncSpace::signature)
+                    .collect();
+                assert_eq!(signatures, [Some("f(a: number): boolean"), Some("g(a, b)")]);
+            },
+        );
+    }
+
+    #[test]
+    fn python_decorators() {
+        check_func_space::<PythonParser, _>(
+            "@dataclass
+class A:
+    @staticmethod
+    def f():
+        pass
+
+    @app.route(
+        '/'
+    )
+    @property
+    def g(self):
+        return 1
+
+    def h(self):
+        pass
+",
+            "foo.py",
+            |func_space| {
+                let class = &func_space.spaces[0];
+                assert_eq!(class.decorators(), ["dataclass"]);
+                let decorators: Vec<_> = class.spaces.iter().map(FuncSpace::decorators).collect();
+                assert_eq!(
+                    decorators,
+                    [
+                        &["staticmethod".to_string()][..],
+                        &["app.route( '/' )".to_string(), "property".to_string()],
+                        &[],
+                    ]
+                );
+                assert!(func_space.decorators().is_empty());
+            },
+        );
+    }
+
+    #[test]
+    fn space_outlives_code() {
+        fn owned<T: 'static + Send>(value: T) -> T {
+            value
+        }
+
+        let space = {
+            let code = String::from("def double(x):\n    return x * 2\n");
+            let path = Path::new("foo.py").to_path_buf();
+            let parser = PythonParser::new(code.into_bytes(), &path, None);
+            owned::<FuncSpace>(metrics(&parser, &path).unwrap())
+        };
+
+        assert_eq!(space.name.as_deref(), Some("foo.py"));
+        assert_eq!(space.spaces[0].name.as_deref(), Some("double"));
+        assert_eq!(space.spaces[0].metrics.loc.sloc(), 2.);
+    }
 
     #[test]
     fn c_scope_resolution_operator() {
@@ -416,4 +1168,273 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn csharp_constructor_name() {
+        check_func_space::<CsharpParser, _>(
+            "class Account {
+                private int balance;
+                public Account(int initial) {
+                    balance = initial;
+                }
+            }",
+            "foo.cs",
+            |func_space| {
+                let class = &func_space.spaces[0];
+                assert_eq!(class.name.as_deref(), Some("Account"));
+                assert_eq!(class.spaces[0].kind, crate::SpaceKind::Function);
+                assert_eq!(class.spaces[0].name, class.name);
+            },
+        );
+    }
+
+    #[test]
+    fn csharp_local_function_name() {
+        check_func_space::<CsharpParser, _>(
+            "class Math {
+                public int Twice(int x) {
+                    int Double(int y) {
+                        return y * 2;
+                    }
+                    return Double(x);
+                }
+            }",
+            "foo.cs",
+            |func_space| {
+                let method = &func_space.spaces[0].spaces[0];
+                assert_eq!(method.name.as_deref(), Some("Twice"));
+                assert_eq!(method.spaces[0].name.as_deref(), Some("Double"));
+                assert_eq!(method.spaces[0].kind, crate::SpaceKind::Function);
+            },
+        );
+    }
+
+    #[test]
+    fn rust_impl_names() {
+        check_func_space::<ParserEngineRust, _>(
+            "impl<T> Stack<T> {
+                fn push(&mut self, item: T) {}
+            }
+            impl<T: fmt::Debug> fmt::Debug for Stack<T> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    Ok(())
+                }
+            }",
+            "foo.rs",
+            |func_space| {
+                let names: Vec<_> = func_space
+                    .spaces
+                    .iter()
+                    .map(|space| (space.kind, space.name.as_deref()))
+                    .collect();
+                assert_eq!(
+                    names,
+                    [
+                        (SpaceKind::Impl, Some("Stack<T>")),
+                        (SpaceKind::Impl, Some("fmt::Debug for Stack<T>"))
+                    ]
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_macro_rules_space() {
+        check_func_space::<ParserEngineRust, _>(
+            "macro_rules! foo {
+                 () => {};
+                 ($x:expr) => { $x };
+                 ($x:expr, $($rest:expr),+) => { $x + foo!($($rest),+) };
+             }
+
+             fn f() -> i32 {
+                 foo!(1, 2)
+             }",
+            "foo.rs",
+            |func_space| {
+                let spaces: Vec<_> = func_space
+                    .spaces
+                    .iter()
+                    .map(|space| (space.kind, space.name.as_deref()))
+                    .collect();
+                assert_eq!(
+                    spaces,
+                    [
+                        (SpaceKind::Macro, Some("foo")),
+                        (SpaceKind::Function, Some("f"))
+                    ]
+                );
+
+                let macro_space = &func_space.spaces[0];
+                assert_eq!((macro_space.start_line, macro_space.end_line), (1, 5));
+                assert_eq!(macro_space.metrics.loc.sloc(), 5.);
+                // One path for each of the three rules
+                assert_eq!(macro_space.metrics.cyclomatic.cyclomatic(), 3.);
+            },
+        );
+    }
+
+    #[test]
+    fn python_space_iterator() {
+        let path = Path::new("foo.py");
+        let parser = PythonParser::new(
+            b"class A:
+    def f(self):
+        return 1
+
+def g(x):
+    if x:
+        return lambda y: y
+    return None
+
+def h():
+    pass
+"
+            .to_vec(),
+            path,
+            None,
+        );
+
+        let batch = metrics(&parser, path).unwrap();
+        let streamed: Vec<_> = SpaceIterator::new(&parser).collect();
+
+        let names: Vec<_> = streamed.iter().map(|space| space.name.as_deref()).collect();
+        assert_eq!(names, [Some("A"), Some("g"), Some("h")]);
+        assert_eq!(
+            serde_json::to_value(&streamed).unwrap(),
+            serde_json::to_value(&batch.spaces).unwrap()
+        );
+    }
+
+    #[test]
+    fn python_content_hash() {
+        fn hashes(code: &str) -> Vec<Option<u64>> {
+            let path = Path::new("foo.py");
+            let parser = PythonParser::new(code.as_bytes().to_vec(), path, None);
+            let options = MetricsOptions {
+                content_hash: true,
+                ..MetricsOptions::default()
+            };
+            let space = metrics_with_options(&parser, path, &options).unwrap();
+            space.spaces.iter().map(FuncSpace::content_hash).collect()
+        }
+
+        let code = "def f(x):
+    return x + 1
+
+def g(x):
+    return x * 2
+";
+        let first = hashes(code);
+        assert!(first.iter().all(Option::is_some));
+        assert_ne!(first[0], first[1]);
+        // The hashes are stable across analyses
+        assert_eq!(hashes(code), first);
+
+        // Moving the functions keeps their hashes
+        let moved = hashes(&format!("\n\n\n{}", code.replace("\n\ndef", "\n\n\n\ndef")));
+        assert_eq!(moved, first);
+
+        // Changing a body only changes the hash of its function
+        let changed = hashes(&code.replace("x * 2", "x * 3"));
+        assert_eq!(changed[0], first[0]);
+        assert_ne!(changed[1], first[1]);
+
+        // The hashes are not computed by default
+        let parser = PythonParser::new(code.as_bytes().to_vec(), Path::new("foo.py"), None);
+        let space = metrics(&parser, Path::new("foo.py")).unwrap();
+        assert_eq!(space.spaces[0].content_hash(), None);
+    }
+
+    #[test]
+    fn python_uniform_averages() {
+        // The cyclomatic complexity is 1 for the unit, 3 for each function,
+        // the cognitive complexity 2 for `f` and 3 for `g`
+        let code = "def f(a, b):
+    if a and b:
+        return 1
+    return 0
+
+def g(items):
+    for item in items:
+        if item:
+            return item
+    return None
+";
+        let averages = |averages: AverageSpaces| {
+            let path = Path::new("foo.py");
+            let parser = PythonParser::new(code.as_bytes().to_vec(), path, None);
+            let options = MetricsOptions {
+                averages,
+                ..MetricsOptions::default()
+            };
+            let space = metrics_with_options(&parser, path, &options).unwrap();
+            (
+                space.metrics.cyclomatic.cyclomatic_average(),
+                space.metrics.cognitive.cognitive_average(),
+            )
+        };
+
+        // By default, the cyclomatic average counts the unit space while
+        // the cognitive one does not
+        assert_eq!(averages(AverageSpaces::PerMetric), (7. / 3., 5. / 2.));
+        assert_eq!(averages(AverageSpaces::FunctionsAndUnit), (7. / 3., 5. / 3.));
+        assert_eq!(averages(AverageSpaces::Functions), (7. / 2., 5. / 2.));
+    }
+
+    #[test]
+    fn python_function_averages_without_functions() {
+        let code = "x = 1
+if x:
+    y = 2
+";
+        let path = Path::new("foo.py");
+        let parser = PythonParser::new(code.as_bytes().to_vec(), path, None);
+        let options = MetricsOptions {
+            averages: AverageSpaces::Functions,
+            ..MetricsOptions::default()
+        };
+        let metrics = metrics_with_options(&parser, path, &options)
+            .unwrap()
+            .metrics;
+
+        assert_eq!(metrics.cyclomatic.cyclomatic_average(), 2.);
+        assert_eq!(metrics.cognitive.cognitive_average(), 1.);
+        assert_eq!(metrics.nexits.exit_average(), 0.);
+        assert_eq!(metrics.loc.sloc_average(), metrics.loc.sloc());
+    }
+
+    #[test]
+    fn python_space_for_line() {
+        check_func_space::<PythonParser, _>(
+            "class Shape:
+    sides = 4
+
+    def area(self, x):
+        def square(y): return y * y
+        return square(x)
+
+    def name(self):
+        return 'shape'
+",
+            "foo.py",
+            |func_space| {
+                let name = |line| {
+                    space_for_line(&func_space, line).map(|space| (space.kind, space.name.clone()))
+                };
+                let class = Some((SpaceKind::Class, Some("Shape".to_string())));
+                let area = Some((SpaceKind::Function, Some("area".to_string())));
+
+                assert_eq!(name(2), class);
+                assert_eq!(name(4), area);
+                assert_eq!(name(6), area);
+                // Between the methods
+                assert_eq!(name(7), class);
+                assert_eq!(name(9), Some((SpaceKind::Function, Some("name".to_string()))));
+                // The nested function is deeper than its method
+                assert_eq!(name(5), Some((SpaceKind::Function, Some("square".to_string()))));
+                assert_eq!(name(20), None);
+            },
+        );
+    }
 }
@@ -8,6 +8,7 @@ use std::{
 };
 
 use regex::bytes::Regex;
+use serde::Serialize;
 use termcolor::{Color, ColorSpec, StandardStreamLock, WriteColor};
 
 use crate::langs::{fake, *};
@@ -33,6 +34,7 @@ pub fn read_file(path: &Path) -> std::io::Result<Vec<u8>> {
     let mut data = Vec::new();
     file.read_to_end(&mut data)?;
 
+    normalize_eol(&mut data);
     remove_blank_lines(&mut data);
 
     Ok(data)
@@ -89,11 +91,81 @@ pub fn read_file_with_eol(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
 
     file.read_to_end(&mut data)?;
 
+    normalize_eol(&mut data);
     remove_blank_lines(&mut data);
 
     Ok(Some(data))
 }
 
+/// The end-of-line style used by a source file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EolStyle {
+    /// The file contains no line endings.
+    #[default]
+    None,
+    /// Unix line endings (`\n`).
+    Lf,
+    /// Windows line endings (`\r\n`).
+    Crlf,
+    /// Classic Mac OS line endings (`\r`).
+    Cr,
+    /// More than one line ending style is used.
+    Mixed,
+}
+
+/// Flags describing file-level properties found while analyzing a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFlags {
+    /// The file mixes different line ending styles.
+    MixedLineEndings,
+}
+
+/// Detects the end-of-line style used in a buffer.
+///
+/// # Examples
+///
+/// ```
+/// use singularity_code_analysis::{detect_eol, EolStyle};
+///
+/// assert_eq!(detect_eol(b"a\r\nb\r\n"), EolStyle::Crlf);
+/// assert_eq!(detect_eol(b"a\r\nb\n"), EolStyle::Mixed);
+/// ```
+#[must_use]
+pub fn detect_eol(data: &[u8]) -> EolStyle {
+    let mut style = EolStyle::None;
+    let mut bytes = data.iter().peekable();
+
+    while let Some(&byte) = bytes.next() {
+        let current = match byte {
+            b'\r' if bytes.next_if_eq(&&b'\n').is_some() => EolStyle::Crlf,
+            b'\r' => EolStyle::Cr,
+            b'\n' => EolStyle::Lf,
+            _ => continue,
+        };
+        if style == EolStyle::None {
+            style = current;
+        } else if style != current {
+            return EolStyle::Mixed;
+        }
+    }
+    style
+}
+
+/// Replaces lone `\r` line endings with `\n`, so that every line
+/// is terminated by a `\n` whatever the file style.
+///
+/// The length of the buffer is preserved, hence byte offsets are unchanged.
+pub(crate) fn normalize_eol(data: &mut [u8]) {
+    let mut bytes = data.iter_mut().peekable();
+    while let Some(byte) = bytes.next() {
+        if *byte == b'\r' && bytes.peek().is_none_or(|next| **next != b'\n') {
+            *byte = b'\n';
+        }
+    }
+}
+
 /// Writes data to a file.
 ///
 /// # Errors
@@ -197,11 +269,28 @@ fn get_emacs_mode(buf: &[u8]) -> Option<String> {
     None
 }
 
+// The interpreter named by a `#!` line, e.g. `perl` for `#!/usr/bin/env perl`
+fn get_shebang_mode(buf: &[u8]) -> Option<String> {
+    let line = buf.strip_prefix(b"#!")?.split(|c| *c == b'\n').next()?;
+    let line = std::str::from_utf8(line).ok()?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+    // `perl5.36` and `python3` name the same languages as `perl` and `python`
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(name.to_lowercase())
+}
+
 /// Guesses the language of a code.
 ///
 /// Returns a tuple containing a [`LANG`] as first argument
 /// and the language name as a second one.
 ///
+/// When the code has no `Emacs` or `Vim` mode line, the interpreter of a
+/// `#!` line is used instead, so extensionless scripts are recognized too.
+///
 /// # Examples
 ///
 /// ```
@@ -228,7 +317,9 @@ pub fn guess_language<'a, P: AsRef<Path>>(buf: &[u8], path: P) -> (Option<LANG>,
         .map_or_else(String::new, str::to_lowercase);
     let from_ext = get_from_ext(&ext);
 
-    let mode = get_emacs_mode(buf).unwrap_or_default();
+    let mode = get_emacs_mode(buf)
+        .or_else(|| get_shebang_mode(buf))
+        .unwrap_or_default();
 
     let from_mode = get_from_emacs_mode(&mode);
 
@@ -375,6 +466,15 @@ pub(crate) fn guess_file<S: ::std::hash::BuildHasher>(
     vec![]
 }
 
+// 64-bit FNV-1a, which does not depend on the hasher of the standard
+// library, so its values are stable across runs and platforms
+#[inline]
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 #[inline]
 pub(crate) fn color(stdout: &mut StandardStreamLock, color: Color) -> std::io::Result<()> {
     stdout.set_color(ColorSpec::new().set_fg(Some(color)))
@@ -409,6 +509,11 @@ pub(crate) fn check_func_space<T: crate::ParserTrait, F: Fn(crate::FuncSpace)>(
             kind: crate::SpaceKind::Unit,
             spaces: Vec::new(),
             metrics: crate::CodeMetrics::default(),
+            supertypes: None,
+            signature: None,
+            decorators: Vec::new(),
+            content_hash: None,
+            custom: std::collections::BTreeMap::new(),
         };
         check(default_space);
     }
@@ -449,6 +554,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_eol() {
+        assert_eq!(detect_eol(b"abc"), EolStyle::None);
+        assert_eq!(detect_eol(b"a\nb\n"), EolStyle::Lf);
+        assert_eq!(detect_eol(b"a\r\nb\r\n"), EolStyle::Crlf);
+        assert_eq!(detect_eol(b"a\rb\r"), EolStyle::Cr);
+        assert_eq!(detect_eol(b"a\r\nb\n"), EolStyle::Mixed);
+        assert_eq!(detect_eol(b"a\rb\n"), EolStyle::Mixed);
+
+        let mut data = b"a\rb\r\nc\r".to_vec();
+        normalize_eol(&mut data);
+        assert_eq!(data, b"a\nb\r\nc\n");
+    }
+
     #[test]
     fn test_guess_language() {
         let buf = b"// -*- foo: bar; mode: c++; hello: world\n";
@@ -477,5 +596,11 @@ mod tests {
             guess_language(buf, "foo.mm"),
             (Some(LANG::Cpp), "obj-c/c++")
         );
+
+        let buf = b"#!/usr/bin/env perl\nprint \"hello\\n\";\n";
+        assert_eq!(guess_language(buf, "hello"), (Some(LANG::Perl), "perl"));
+
+        let buf = b"#!/usr/bin/perl5.36 -w\nuse strict;\n";
+        assert_eq!(guess_language(buf, "foo.pl"), (Some(LANG::Perl), "perl"));
     }
 }
@@ -1,9 +1,11 @@
-use std::{path::Path, sync::Arc};
+use std::{ops::ControlFlow, path::Path, sync::Arc};
 
 use crate::{
-    abc::Abc, alterator::Alterator, checker::Checker, cognitive::Cognitive, cyclomatic::Cyclomatic,
-    exit::Exit, getter::Getter, halstead::Halstead, langs::*, loc::Loc, mi::Mi, nargs::NArgs,
-    node::Node, nom::Nom, npa::Npa, npm::Npm, parser::Filter, preproc::PreprocResults, wmc::Wmc,
+    abc::Abc, alterator::Alterator, boolean_ops::BooleanOps, checker::Checker,
+    cognitive::Cognitive, cyclomatic::Cyclomatic, exit::Exit, getter::Getter, halstead::Halstead,
+    jsx::Jsx, langs::*, loc::Loc, locals::Locals, mi::Mi, nargs::NArgs, node::Node, nom::Nom,
+    npa::Npa, npm::Npm, parser::Filter, preproc::PreprocResults, template::Template,
+    unsafety::Unsafety, wmc::Wmc,
 };
 
 /// A trait for callback functions.
@@ -41,8 +43,13 @@ pub trait ParserTrait {
     type Exit: Exit;
     type Wmc: Wmc;
     type Abc: Abc;
+    type BooleanOps: BooleanOps;
+    type Locals: Locals;
     type Npm: Npm;
     type Npa: Npa;
+    type Unsafety: Unsafety;
+    type Jsx: Jsx;
+    type Template: Template;
 
     fn new(code: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Self;
     fn get_language(&self) -> LANG;
@@ -56,6 +63,13 @@ pub(crate) trait Search<'a> {
     fn first_occurrence(&self, pred: fn(u16) -> bool) -> Option<Node<'a>>;
     fn first_occurrence_kind(&self, pred: fn(&Node<'a>) -> bool) -> Option<Node<'a>>;
     fn act_on_node(&self, pred: &mut dyn FnMut(&Node<'a>));
+    /// Visits the nodes of a tree in pre-order until `visit` breaks,
+    /// returning the break value, so that the nodes after it are never
+    /// visited.
+    fn try_for_each<B>(
+        &self,
+        visit: &mut dyn FnMut(&Node<'a>) -> ControlFlow<B>,
+    ) -> ControlFlow<B>;
     fn first_child(&self, pred: fn(u16) -> bool) -> Option<Node<'a>>;
     fn first_child_kind(&self, pred: fn(&Node<'a>) -> bool) -> Option<Node<'a>>;
     fn act_on_child(&self, action: &mut dyn FnMut(&Node<'a>));
@@ -82,6 +82,7 @@ pub fn compare_rca_output_with_files(repo_name: &str, include: &[&str], exclude:
         include: include_globs.build().expect("TODO: Add context for why this shouldn't fail"),
         exclude: exclude_globs.build().expect("TODO: Add context for why this shouldn't fail"),
         paths: vec![Path::new(REPO).join(repo_name)],
+        only_files: None,
     };
 
     if let Err(e) = ConcurrentRunner::new(num_jobs, act_on_file).run(cfg, files_data) {